@@ -0,0 +1,43 @@
+use super::utils::ActionPipeline;
+use crate::registry::TokenId;
+use crate::types::{SeasonId, SeasonStanding};
+use crate::{Contract, ContractExt, KudosError};
+use near_sdk::json_types::U128;
+use near_sdk::{near_bindgen, AccountId, PromiseError, PromiseOrValue};
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn on_season_sbts_minted(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        season_id: SeasonId,
+        #[callback_result] callback_result: Result<Vec<TokenId>, PromiseError>,
+    ) -> PromiseOrValue<Vec<SeasonStanding>> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        let result = match callback_result {
+            Ok(minted_tokens_ids) if minted_tokens_ids.is_empty() => {
+                Err(KudosError::EmptyMintResponse)
+            }
+            Ok(_) => Ok(self
+                .season_snapshots
+                .get(&season_id)
+                .cloned()
+                .unwrap_or_default()),
+            Err(e) => Err(KudosError::external_call_failure(
+                "IAHRegistry::sbt_mint()",
+                e,
+            )),
+        };
+
+        // Reopen season, so it could be closed again, and return deposit back to an owner
+        // if SBTs weren't minted
+        if result.is_err() {
+            self.unfreeze_season(season_id);
+        }
+
+        pipeline.complete(result)
+    }
+}
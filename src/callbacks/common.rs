@@ -1,10 +1,10 @@
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, KudosError};
 use near_sdk::{env, near_bindgen};
 
 #[near_bindgen]
 impl Contract {
     #[private]
-    pub fn on_failure(&mut self, error: String) {
-        env::panic_str(&error)
+    pub fn on_failure(&mut self, error: KudosError) {
+        env::panic_str(&error.to_string())
     }
 }
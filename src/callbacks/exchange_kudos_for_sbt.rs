@@ -1,14 +1,16 @@
-use super::utils::parse_kudos_and_verify_if_allowed_to_exchange;
+use super::utils::{
+    optional_promise_result, parse_kudos_and_verify_if_allowed_to_exchange, ActionPipeline,
+};
 use crate::consts::*;
-use crate::external_db::ext_db;
 use crate::registry::{ext_sbtreg, TokenId};
 use crate::settings::Settings;
-use crate::types::KudosId;
+use crate::storage::{KudosField, KudosStorage, StorageBackend};
+use crate::types::{HumanityAction, KudosId};
 use crate::utils::*;
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, KudosError};
 use near_sdk::json_types::U128;
 use near_sdk::serde_json::Value;
-use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseError, PromiseOrValue};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseError, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
@@ -17,59 +19,29 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
-        external_db_id: AccountId,
+        storage: StorageBackend,
         kudos_id: KudosId,
         #[callback_result] callback_result: Result<Vec<(AccountId, Vec<TokenId>)>, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
 
-        let result = callback_result
-            .map_err(|e| format!("IAHRegistry::is_human() call failure: {e:?}"))
-            .and_then(|tokens| {
-                if tokens.is_empty() {
-                    return Err("IAHRegistry::is_human() returns result: Not a human".to_owned());
-                }
-
-                let receiver_id = env::signer_account_id();
-                let root_id = env::current_account_id();
-                let kudos_upvotes_path =
-                    build_kudos_upvotes_path(&root_id, &receiver_id, &kudos_id);
-                let acquire_upvotes_req = [&kudos_upvotes_path, "/*"].concat();
-                let kudos_kind_path = build_kudos_kind_path(&root_id, &receiver_id, &kudos_id);
-
-                let upvotes_acquired_callback_gas = KUDOS_UPVOTES_ACQUIRED_CALLBACK_GAS
-                    + PROOF_OF_KUDOS_SBT_MINT_GAS
-                    + PROOF_OF_KUDOS_SBT_MINT_CALLBACK_GAS
-                    + FAILURE_CALLBACK_GAS;
-
-                let acquire_upvotes_gas = env::prepaid_gas()
-                    - (ACQUIRE_NUMBER_OF_UPVOTES_RESERVED_GAS + upvotes_acquired_callback_gas);
-
-                Ok(ext_db::ext(external_db_id)
-                    .with_static_gas(acquire_upvotes_gas)
-                    .get(vec![acquire_upvotes_req, kudos_kind_path.clone()], None)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(upvotes_acquired_callback_gas)
-                            .on_kudos_upvotes_acquired(
-                                predecessor_account_id.clone(),
-                                attached_deposit.into(),
-                                kudos_id,
-                                kudos_upvotes_path,
-                                kudos_kind_path,
-                            ),
-                    ))
+        let result = self
+            .verify_humanity(
+                &env::signer_account_id(),
+                HumanityAction::Exchange,
+                callback_result,
+                optional_promise_result(1).and_then(Result::ok),
+            )
+            .map(|_| {
+                self.acquire_number_of_upvotes_verified(
+                    pipeline.predecessor_account_id.clone(),
+                    pipeline.attached_deposit,
+                    storage,
+                    kudos_id,
+                )
             });
 
-        result.unwrap_or_else(|e| {
-            Promise::new(predecessor_account_id)
-                .transfer(attached_deposit)
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(FAILURE_CALLBACK_GAS)
-                        .on_failure(e),
-                )
-        })
+        pipeline.step(result)
     }
 
     #[private]
@@ -80,53 +52,59 @@ impl Contract {
         kudos_id: KudosId,
         kudos_upvotes_path: String,
         kudos_kind_path: String,
+        kudos_sender_path: String,
+        kudos_created_at_path: String,
         #[callback_result] kudos_result: Result<Value, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
         let settings = Settings::from(&self.settings);
+        let receiver_id = env::signer_account_id();
+        let now = env::block_timestamp_ms();
 
-        match parse_kudos_and_verify_if_allowed_to_exchange(
+        let result = parse_kudos_and_verify_if_allowed_to_exchange(
             kudos_result,
             kudos_upvotes_path,
             kudos_kind_path,
+            kudos_sender_path,
+            kudos_created_at_path,
             settings.min_number_of_upvotes_to_exchange_kudos as usize,
+            |kind| self.kudos_kind_info(kind),
+            |upvoter_id, kudos_sender_id, kudos_created_at| {
+                self.is_upvote_counted(
+                    &settings,
+                    upvoter_id,
+                    &receiver_id,
+                    &kudos_id,
+                    kudos_sender_id,
+                    kudos_created_at,
+                )
+            },
         )
         .and_then(|_| {
-            let issued_at = env::block_timestamp_ms();
-            let expires_at = settings.acquire_pok_sbt_expire_at_ts(issued_at)?;
-
-            Ok(build_pok_sbt_metadata(issued_at, expires_at))
-        }) {
-            Ok(metadata) => {
-                self.exchanged_kudos.insert(kudos_id.clone());
-
-                ext_sbtreg::ext(self.iah_registry.clone())
-                    .with_attached_deposit(PROOF_OF_KUDOS_SBT_MINT_COST)
-                    .with_static_gas(PROOF_OF_KUDOS_SBT_MINT_GAS)
-                    .sbt_mint(vec![(env::signer_account_id(), vec![metadata])])
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(
-                                PROOF_OF_KUDOS_SBT_MINT_CALLBACK_GAS + FAILURE_CALLBACK_GAS,
-                            )
-                            .on_pok_sbt_mint(
-                                predecessor_account_id,
-                                attached_deposit.into(),
-                                kudos_id,
-                            ),
-                    )
-            }
-            Err(e) => {
-                // Return leave comment deposit back to sender if failed
-                Promise::new(predecessor_account_id)
-                    .transfer(attached_deposit)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(FAILURE_CALLBACK_GAS)
-                            .on_failure(e),
-                    )
-            }
-        }
+            let expires_at = settings.acquire_pok_sbt_expire_at_ts(now)?;
+            let metadata = build_pok_sbt_metadata(now, expires_at);
+
+            self.exchanged_kudos.insert(kudos_id.clone());
+
+            Ok(ext_sbtreg::ext(self.iah_registry.clone())
+                .with_attached_deposit(PROOF_OF_KUDOS_SBT_MINT_COST)
+                .with_static_gas(PROOF_OF_KUDOS_SBT_MINT_GAS)
+                .sbt_mint(vec![(receiver_id.clone(), vec![metadata])])
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(ActionPipeline::callback_gas(
+                            PROOF_OF_KUDOS_SBT_MINT_CALLBACK_GAS,
+                        ))
+                        .on_pok_sbt_mint(
+                            pipeline.predecessor_account_id.clone(),
+                            pipeline.attached_deposit.into(),
+                            kudos_id,
+                        ),
+                ))
+        });
+
+        // Return exchange kudos deposit back to sender if failed
+        pipeline.step(result)
     }
 
     #[private]
@@ -137,30 +115,92 @@ impl Contract {
         attached_deposit: U128,
         kudos_id: KudosId,
         #[callback_result] callback_result: Result<Vec<u64>, PromiseError>,
-    ) -> Result<PromiseOrValue<Vec<u64>>, &'static str> {
-        let attached_deposit = attached_deposit.0;
+    ) -> Result<PromiseOrValue<Vec<u64>>, KudosError> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
 
         match callback_result {
             Ok(minted_tokens_ids) if minted_tokens_ids.is_empty() => {
                 // If IAHRegistry contract succeeds but returns an empty tokens list,
                 // we treat is an unexpected failure and panic. No user deposit returns for this case.
-                Err("IAHRegistry::sbt_mint() responses with an empty tokens array")
+                Err(KudosError::EmptyMintResponse)
+            }
+            Ok(minted_tokens_ids) => {
+                let receiver_id = env::signer_account_id();
+                self.record_pok_sbts_stats(&receiver_id, minted_tokens_ids.len());
+                self.tally_season_exchange(
+                    &receiver_id,
+                    minted_tokens_ids.len(),
+                    env::block_timestamp_ms(),
+                );
+
+                Ok(PromiseOrValue::Value(minted_tokens_ids))
             }
-            Ok(minted_tokens_ids) => Ok(PromiseOrValue::Value(minted_tokens_ids)),
             Err(e) => {
                 // If tokens weren't minted, remove kudos from exchanged table
                 self.exchanged_kudos.remove(&kudos_id);
 
                 // Return deposit back to sender if IAHRegistry::sbt_mint fails
-                Ok(Promise::new(predecessor_account_id)
-                    .transfer(attached_deposit)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(FAILURE_CALLBACK_GAS)
-                            .on_failure(format!("IAHRegistry::sbt_mint() call failure: {:?}", e)),
-                    )
+                Ok(pipeline
+                    .fail(KudosError::external_call_failure(
+                        "IAHRegistry::sbt_mint()",
+                        e,
+                    ))
                     .into())
             }
         }
     }
 }
+
+impl Contract {
+    /// Acquire kudos upvotes from a storage backend to verify that kudos could be exchanged for ProofOfKudos SBT
+    /// by its receiver, who was verified as a human
+    pub(crate) fn acquire_number_of_upvotes_verified(
+        &self,
+        predecessor_account_id: AccountId,
+        attached_deposit: Balance,
+        storage: StorageBackend,
+        kudos_id: KudosId,
+    ) -> Promise {
+        let receiver_id = env::signer_account_id();
+        let root_id = env::current_account_id();
+        let kudos_upvotes_path = build_kudos_upvotes_path(&root_id, &receiver_id, &kudos_id);
+        let kudos_kind_path = build_kudos_kind_path(&root_id, &receiver_id, &kudos_id);
+        let kudos_sender_path = build_kudos_sender_path(&root_id, &receiver_id, &kudos_id);
+        let kudos_created_at_path = build_kudos_created_at_path(&root_id, &receiver_id, &kudos_id);
+
+        let upvotes_acquired_callback_gas = ActionPipeline::callback_gas(
+            KUDOS_UPVOTES_ACQUIRED_CALLBACK_GAS
+                + PROOF_OF_KUDOS_SBT_MINT_GAS
+                + PROOF_OF_KUDOS_SBT_MINT_CALLBACK_GAS,
+        );
+
+        let acquire_upvotes_gas = env::prepaid_gas()
+            - (ACQUIRE_NUMBER_OF_UPVOTES_RESERVED_GAS + upvotes_acquired_callback_gas);
+
+        storage
+            .read_kudos(
+                &receiver_id,
+                &kudos_id,
+                &[
+                    KudosField::Upvotes,
+                    KudosField::Kind,
+                    KudosField::SenderId,
+                    KudosField::CreatedAt,
+                ],
+                acquire_upvotes_gas,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(upvotes_acquired_callback_gas)
+                    .on_kudos_upvotes_acquired(
+                        predecessor_account_id,
+                        attached_deposit.into(),
+                        kudos_id,
+                        kudos_upvotes_path,
+                        kudos_kind_path,
+                        kudos_sender_path,
+                        kudos_created_at_path,
+                    ),
+            )
+    }
+}
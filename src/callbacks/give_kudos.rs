@@ -1,11 +1,14 @@
-use crate::external_db::ext_db;
+use super::utils::{optional_promise_result, ActionPipeline};
+use crate::events::{CidsAccepted, KudosEvent};
 use crate::registry::TokenId;
-use crate::types::KudosId;
-use crate::{consts::*, Hashtag, KudosKind};
-use crate::{utils::*, WrappedCid};
-use crate::{Contract, ContractExt};
+use crate::settings::Settings;
+use crate::storage::{KudosStorage, NewKudos, StorageBackend};
+use crate::types::{HumanityAction, KudosId, KudosStatus, RateLimitedAction};
+use crate::utils::give_kudos_cost;
+use crate::{consts::*, Hashtag, KudosKind, KudosMedia, KudosMessage, WrappedCid};
+use crate::{Contract, ContractExt, KudosError};
 use near_sdk::json_types::U128;
-use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseError, PromiseOrValue};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseError, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
@@ -14,66 +17,37 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
-        external_db_id: AccountId,
+        storage: StorageBackend,
         receiver_id: AccountId,
         kind: KudosKind,
-        message: String,
-        icon_cid: Option<WrappedCid>,
+        message: KudosMessage,
+        media: KudosMedia,
         hashtags: Option<Vec<Hashtag>>,
         #[callback_result] callback_result: Result<Vec<(AccountId, Vec<TokenId>)>, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
 
-        let result = callback_result
-            .map_err(|e| format!("IAHRegistry::is_human() call failure: {e:?}"))
-            .and_then(|tokens| {
-                if tokens.is_empty() {
-                    return Err("IAHRegistry::is_human() returns result: Not a human".to_owned());
-                }
-
-                let sender_id = env::signer_account_id();
-                let root_id = env::current_account_id();
-                let created_at = env::block_timestamp_ms();
-                let kudos_id = KudosId::from(self.last_incremental_id.inc());
-                let kudos_json = build_give_kudos_request(
-                    &root_id,
-                    &sender_id,
-                    &receiver_id,
-                    &kudos_id,
-                    created_at,
+        let result = self
+            .verify_humanity(
+                &env::signer_account_id(),
+                HumanityAction::from(&kind),
+                callback_result,
+                optional_promise_result(1).and_then(Result::ok),
+            )
+            .and_then(|_| {
+                self.save_kudos_verified(
+                    pipeline.predecessor_account_id.clone(),
+                    pipeline.attached_deposit,
+                    storage,
+                    receiver_id,
                     kind,
-                    &message,
-                    icon_cid.as_ref(),
-                    hashtags.as_deref(),
-                )?;
-
-                let save_kudos_gas = env::prepaid_gas()
-                    - (SAVE_KUDOS_RESERVED_GAS + KUDOS_SAVED_CALLBACK_GAS + FAILURE_CALLBACK_GAS);
-
-                Ok(ext_db::ext(external_db_id)
-                    .with_static_gas(save_kudos_gas)
-                    .with_attached_deposit(attached_deposit)
-                    .set(kudos_json)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(KUDOS_SAVED_CALLBACK_GAS + FAILURE_CALLBACK_GAS)
-                            .on_kudos_saved(
-                                predecessor_account_id.clone(),
-                                attached_deposit.into(),
-                                kudos_id,
-                            ),
-                    ))
+                    message,
+                    media,
+                    hashtags,
+                )
             });
 
-        result.unwrap_or_else(|e| {
-            Promise::new(predecessor_account_id)
-                .transfer(attached_deposit)
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(FAILURE_CALLBACK_GAS)
-                        .on_failure(e),
-                )
-        })
+        pipeline.step(result)
     }
 
     #[private]
@@ -81,24 +55,134 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
+        receiver_id: AccountId,
         kudos_id: KudosId,
+        kind: KudosKind,
+        status: Option<KudosStatus>,
+        hashtags: Option<Vec<Hashtag>>,
+        cids: Vec<WrappedCid>,
         #[callback_result] callback_result: Result<(), PromiseError>,
     ) -> PromiseOrValue<KudosId> {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to sender if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    // Content referred by saved kudos could be pinned by ipfs gateways
+                    if !cids.is_empty() {
+                        KudosEvent::CidsAccepted(vec![CidsAccepted {
+                            receiver_id: &receiver_id,
+                            kudos_id: &kudos_id,
+                            cids: &cids,
+                        }])
+                        .emit();
+                    }
+
+                    self.record_rate_limited_action(
+                        &Settings::from(&self.settings),
+                        &env::signer_account_id(),
+                        RateLimitedAction::from(&kind),
+                        env::block_timestamp_ms(),
+                    );
+                    self.tally_season_kudos(&receiver_id, &kind, env::block_timestamp_ms());
 
-        match callback_result {
-            Ok(_) => PromiseOrValue::Value(kudos_id),
-            Err(e) => {
-                // Return deposit back to sender if NEAR SocialDb write failure
-                Promise::new(predecessor_account_id)
-                    .transfer(attached_deposit)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(FAILURE_CALLBACK_GAS)
-                            .on_failure(format!("SocialDB::set() call failure: {e:?}")),
-                    )
-                    .into()
-            }
+                    // Proposed ding is counted once it's published
+                    match status {
+                        Some(KudosStatus::Proposed) => self.propose_ding(
+                            kudos_id.clone(),
+                            env::signer_account_id(),
+                            receiver_id,
+                        ),
+                        Some(status) => {
+                            self.record_kudos_stats(&env::signer_account_id(), &receiver_id, &kind);
+                            self.kudos_status.insert(kudos_id.clone(), status);
+                        }
+                        None => {
+                            self.record_kudos_stats(&env::signer_account_id(), &receiver_id, &kind)
+                        }
+                    }
+
+                    if let Some(hashtags) = hashtags {
+                        self.record_hashtags_usage(&hashtags, env::block_timestamp_ms());
+                    }
+
+                    kudos_id
+                })
+                .map_err(|e| KudosError::external_call_failure("KudosStorage::save_kudos()", e)),
+        )
+    }
+}
+
+impl Contract {
+    /// Save kudos given by a sender, who was verified as a human, to a storage backend
+    pub(crate) fn save_kudos_verified(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: Balance,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kind: KudosKind,
+        message: KudosMessage,
+        media: KudosMedia,
+        hashtags: Option<Vec<Hashtag>>,
+    ) -> Result<Promise, KudosError> {
+        let sender_id = env::signer_account_id();
+        if self.is_sender_blocked(&receiver_id, &sender_id) {
+            return Err(KudosError::SenderBlocked);
         }
+
+        // Dings await a quorum of upvotes if it's enabled, and stay pending until accepted by
+        // receiver if it was required
+        let status = if kind != KudosKind::Ding {
+            None
+        } else if Settings::from(&self.settings).ding_upvotes_quorum > 0 {
+            Some(KudosStatus::Proposed)
+        } else {
+            Some(self.published_ding_status(&receiver_id)).filter(|status| !status.is_public())
+        };
+
+        let kudos_id = KudosId::from(self.last_incremental_id.inc());
+        let cids = media
+            .icon_cid
+            .iter()
+            .chain(media.attachments.iter().map(|attachment| &attachment.cid))
+            .cloned()
+            .collect::<Vec<_>>();
+        let kudos = NewKudos {
+            sender_id,
+            receiver_id: receiver_id.clone(),
+            kudos_id: kudos_id.clone(),
+            created_at: env::block_timestamp_ms().into(),
+            kind: kind.clone(),
+            message: message.text,
+            icon_cid: media.icon_cid,
+            attachments: media.attachments,
+            hashtags: hashtags.clone(),
+            mentions: message.mentions,
+            status: status.clone(),
+        };
+
+        let save_kudos_gas =
+            ActionPipeline::gas_left(SAVE_KUDOS_RESERVED_GAS + KUDOS_SAVED_CALLBACK_GAS);
+        // Deposit for hashtags usage is kept by this contract to pay for its storage
+        let save_kudos_deposit = give_kudos_cost(kudos.attachments.len(), 0);
+
+        Ok(storage
+            .save_kudos(kudos, save_kudos_deposit, save_kudos_gas)?
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(ActionPipeline::callback_gas(KUDOS_SAVED_CALLBACK_GAS))
+                    .on_kudos_saved(
+                        predecessor_account_id,
+                        attached_deposit.into(),
+                        receiver_id,
+                        kudos_id,
+                        kind,
+                        status,
+                        hashtags,
+                        cids,
+                    ),
+            ))
     }
 }
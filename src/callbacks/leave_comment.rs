@@ -1,12 +1,14 @@
-use crate::external_db::ext_db;
+use super::utils::{comment_thread_depth, optional_promise_result, ActionPipeline};
 use crate::registry::TokenId;
-use crate::types::{CommentId, KudosId};
+use crate::settings::Settings;
+use crate::storage::{KudosField, KudosStorage, StorageBackend};
+use crate::types::{CommentId, CommentThread, HumanityAction, KudosId, RateLimitedAction};
 use crate::utils::*;
 use crate::{consts::*, EncodedCommentary};
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, KudosError};
 use near_sdk::json_types::U128;
 use near_sdk::serde_json::Value;
-use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseError, PromiseOrValue};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseError, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
@@ -15,84 +17,35 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
-        external_db_id: AccountId,
+        storage: StorageBackend,
         receiver_id: AccountId,
         kudos_id: KudosId,
         parent_comment_id: Option<CommentId>,
         comment: EncodedCommentary,
         #[callback_result] callback_result: Result<Vec<(AccountId, Vec<TokenId>)>, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
 
-        let result = callback_result
-            .map_err(|e| format!("IAHRegistry::is_human() call failure: {e:?}"))
-            .and_then(|tokens| {
-                if tokens.is_empty() {
-                    return Err("IAHRegistry::is_human() returns result: Not a human".to_owned());
-                }
-
-                let root_id = env::current_account_id();
-                let comment_id = CommentId::from(self.last_incremental_id.inc());
-                let leave_comment_req = build_leave_comment_request(
-                    &root_id,
-                    &receiver_id,
-                    &kudos_id,
-                    &comment_id,
-                    &comment,
-                )?;
-                let get_kudos_by_id_req =
-                    build_get_kudos_by_id_request(&root_id, &receiver_id, &kudos_id);
-                let mut get_req = vec![get_kudos_by_id_req.clone()];
-
-                if let Some(comment_id) = parent_comment_id.as_ref() {
-                    get_req.push(build_get_kudos_comment_by_id_request(
-                        &root_id,
-                        &receiver_id,
-                        &kudos_id,
-                        comment_id,
-                    ));
-                }
-
-                // Compute minimum required gas and split the remaining gas by two equal parts for
-                // NEAR Social db subsequent calls
-                let get_kudos_by_id_gas = (env::prepaid_gas()
-                    - (ACQUIRE_KUDOS_INFO_RESERVED_GAS
-                        + KUDOS_INFO_ACQUIRED_CALLBACK_GAS
-                        + KUDOS_COMMENT_SAVED_CALLBACK_GAS
-                        + FAILURE_CALLBACK_GAS))
-                    / 2;
-                let get_kudos_by_id_callback_gas = get_kudos_by_id_gas
-                    + KUDOS_INFO_ACQUIRED_CALLBACK_GAS
-                    + KUDOS_COMMENT_SAVED_CALLBACK_GAS
-                    + FAILURE_CALLBACK_GAS;
-
-                Ok(ext_db::ext(external_db_id.clone())
-                    .with_static_gas(get_kudos_by_id_gas)
-                    .get(get_req, None)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(get_kudos_by_id_callback_gas)
-                            .on_kudos_info_acquired(
-                                predecessor_account_id.clone(),
-                                attached_deposit.into(),
-                                external_db_id,
-                                get_kudos_by_id_req,
-                                leave_comment_req,
-                                parent_comment_id,
-                                comment_id,
-                            ),
-                    ))
+        let result = self
+            .verify_humanity(
+                &env::signer_account_id(),
+                HumanityAction::Comment,
+                callback_result,
+                optional_promise_result(1).and_then(Result::ok),
+            )
+            .and_then(|_| {
+                self.acquire_kudos_info_verified(
+                    pipeline.predecessor_account_id.clone(),
+                    pipeline.attached_deposit,
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                    parent_comment_id,
+                    comment,
+                )
             });
 
-        result.unwrap_or_else(|e| {
-            Promise::new(predecessor_account_id)
-                .transfer(attached_deposit)
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(FAILURE_CALLBACK_GAS)
-                        .on_failure(e),
-                )
-        })
+        pipeline.step(result)
     }
 
     #[private]
@@ -100,62 +53,91 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
-        external_db_id: AccountId,
-        get_kudos_by_id_req: String,
-        leave_comment_req: Value,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
         parent_comment_id: Option<CommentId>,
         comment_id: CommentId,
+        comment: EncodedCommentary,
         #[callback_result] callback_result: Result<Value, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
 
-        let Err(e) = callback_result
-            .map_err(|e| {
-                format!(
-                    "SocialDB::get({get_kudos_by_id_req}) call failure: {e:?}"
-                )
-            })
+        let get_kudos_by_id_req =
+            build_get_kudos_by_id_request(&env::current_account_id(), &receiver_id, &kudos_id);
+        let result = callback_result
+            .map_err(|e| KudosError::external_call_failure("KudosStorage::read_kudos()", e))
             .and_then(|mut kudos_by_id_res| {
-                if let Some(comment_id) = parent_comment_id.as_ref() {
-                    // We do not verify if extracted base64-encoded commentary is valid, we assume 
-                    // that data stored in social db is not corrupted.
-                    let _ = extract_kudos_encoded_comment_by_id_from_response(&get_kudos_by_id_req, comment_id, &mut kudos_by_id_res)
-                        .ok_or_else(|| {
-                            "Unable to verify parent commentary id".to_owned()
-                        })?;
-                }
+                let (parent_comment_sender_id, depth) = match parent_comment_id.as_ref() {
+                    // We do not verify if extracted base64-encoded commentary is valid, we assume
+                    // that data stored by a storage backend is not corrupted.
+                    Some(comment_id) => {
+                        let comments = extract_kudos_encoded_comments_from_response(
+                            &get_kudos_by_id_req,
+                            &mut kudos_by_id_res,
+                        );
+                        // Depth of a parent is known from its thread, which was verified before
+                        // kudos was read, so only the existence of a parent is verified
+                        let thread_depth = self
+                            .comment_threads
+                            .get(comment_id)
+                            .map(|thread| thread.depth.saturating_add(1));
+                        let depth = match thread_depth {
+                            Some(depth) if comments.contains_key(comment_id) => depth,
+                            Some(_) => return Err(KudosError::ParentCommentNotFound),
+                            None => comment_thread_depth(
+                                &comments,
+                                comment_id,
+                                Settings::from(&self.settings).max_comment_thread_depth,
+                            )?,
+                        };
+
+                        (
+                            comments
+                                .get(comment_id)
+                                .and_then(|comment| comment.sender_id()),
+                            depth,
+                        )
+                    }
+                    None => (None, 0),
+                };
 
                 extract_kudos_id_sender_from_response(&get_kudos_by_id_req, &mut kudos_by_id_res)
-                    .ok_or_else(|| {
-                        "Unable to acquire a Kudos sender account id".to_owned()
-                    })
-            }) else {
-                let gas_left = env::prepaid_gas()
-                    - (KUDOS_INFO_ACQUIRED_CALLBACK_GAS + KUDOS_COMMENT_SAVED_CALLBACK_GAS + FAILURE_CALLBACK_GAS);
-
-                return ext_db::ext(external_db_id)
-                    .with_attached_deposit(attached_deposit)
-                    .with_static_gas(gas_left)
-                    .set(leave_comment_req)
+                    .ok_or(KudosError::KudosSenderNotFound)?;
+
+                let gas_left = ActionPipeline::gas_left(
+                    KUDOS_INFO_ACQUIRED_CALLBACK_GAS + KUDOS_COMMENT_SAVED_CALLBACK_GAS,
+                );
+
+                Ok(storage
+                    .add_comment(
+                        &receiver_id,
+                        &kudos_id,
+                        &comment_id,
+                        &comment,
+                        parent_comment_sender_id.as_ref(),
+                        pipeline.attached_deposit,
+                        gas_left,
+                    )?
                     .then(
                         Self::ext(env::current_account_id())
-                            .with_static_gas(KUDOS_COMMENT_SAVED_CALLBACK_GAS + FAILURE_CALLBACK_GAS)
+                            .with_static_gas(ActionPipeline::callback_gas(
+                                KUDOS_COMMENT_SAVED_CALLBACK_GAS,
+                            ))
                             .on_commentary_saved(
-                                predecessor_account_id,
-                                attached_deposit.into(),
+                                pipeline.predecessor_account_id.clone(),
+                                pipeline.attached_deposit.into(),
+                                receiver_id.clone(),
+                                kudos_id.clone(),
                                 comment_id,
+                                parent_comment_id,
+                                depth,
                             ),
-                    );
-            };
+                    ))
+            });
 
         // Return leave comment deposit back to sender if failed
-        Promise::new(predecessor_account_id)
-            .transfer(attached_deposit)
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(FAILURE_CALLBACK_GAS)
-                    .on_failure(e),
-            )
+        pipeline.step(result)
     }
 
     #[private]
@@ -163,24 +145,112 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
         comment_id: CommentId,
+        parent_comment_id: Option<CommentId>,
+        depth: u8,
         #[callback_result] callback_result: Result<(), PromiseError>,
     ) -> PromiseOrValue<CommentId> {
-        let attached_deposit = attached_deposit.0;
-
-        match callback_result {
-            Ok(_) => PromiseOrValue::Value(comment_id),
-            Err(e) => {
-                // Return deposit back to sender if NEAR SocialDb write failure
-                Promise::new(predecessor_account_id)
-                    .transfer(attached_deposit)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(FAILURE_CALLBACK_GAS)
-                            .on_failure(format!("SocialDB::set() call failure: {e:?}")),
-                    )
-                    .into()
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to sender if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    self.record_rate_limited_action(
+                        &Settings::from(&self.settings),
+                        &env::signer_account_id(),
+                        RateLimitedAction::Comment,
+                        env::block_timestamp_ms(),
+                    );
+                    self.record_comment_stats(&env::signer_account_id(), &receiver_id);
+                    self.comment_threads.insert(
+                        comment_id.clone(),
+                        CommentThread {
+                            kudos_id,
+                            parent_comment_id,
+                            depth,
+                        },
+                    );
+
+                    comment_id
+                })
+                .map_err(|e| KudosError::external_call_failure("KudosStorage::add_comment()", e)),
+        )
+    }
+}
+
+impl Contract {
+    /// Acquire kudos information from a storage backend to verify that commentary message could be left by
+    /// a sender, who was verified as a human
+    pub(crate) fn acquire_kudos_info_verified(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: Balance,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        parent_comment_id: Option<CommentId>,
+        comment: EncodedCommentary,
+    ) -> Result<Promise, KudosError> {
+        if self.is_sender_blocked(&receiver_id, &env::signer_account_id()) {
+            return Err(KudosError::SenderBlocked);
+        }
+
+        let mut fields = vec![KudosField::Info];
+
+        // Depth of a reply is known from a thread of a parent commentary message, so only a parent is read
+        // to verify that it exists. Parents left before threads were recorded are verified by reading all
+        // commentary messages of kudos and walking a thread up to a top-level one.
+        if let Some(parent_comment_id) = parent_comment_id.as_ref() {
+            match self.comment_threads.get(parent_comment_id) {
+                Some(thread) => {
+                    if thread.kudos_id != kudos_id {
+                        return Err(KudosError::ParentCommentNotFound);
+                    }
+
+                    let max_depth = Settings::from(&self.settings).max_comment_thread_depth;
+                    let depth = thread.depth.saturating_add(1);
+                    if depth > max_depth {
+                        return Err(KudosError::CommentThreadTooDeep { max_depth });
+                    }
+
+                    fields.push(KudosField::Comment(parent_comment_id.clone()));
+                }
+                None => fields.push(KudosField::Comments),
             }
         }
+        let comment_id = CommentId::from(self.last_incremental_id.inc());
+
+        // Compute minimum required gas and split the remaining gas by two equal parts for
+        // storage backend subsequent calls
+        let get_kudos_by_id_gas = ActionPipeline::gas_left(
+            ACQUIRE_KUDOS_INFO_RESERVED_GAS
+                + KUDOS_INFO_ACQUIRED_CALLBACK_GAS
+                + KUDOS_COMMENT_SAVED_CALLBACK_GAS,
+        ) / 2;
+        let get_kudos_by_id_callback_gas = ActionPipeline::callback_gas(
+            get_kudos_by_id_gas
+                + KUDOS_INFO_ACQUIRED_CALLBACK_GAS
+                + KUDOS_COMMENT_SAVED_CALLBACK_GAS,
+        );
+
+        Ok(storage
+            .read_kudos(&receiver_id, &kudos_id, &fields, get_kudos_by_id_gas)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(get_kudos_by_id_callback_gas)
+                    .on_kudos_info_acquired(
+                        predecessor_account_id,
+                        attached_deposit.into(),
+                        storage.clone(),
+                        receiver_id,
+                        kudos_id,
+                        parent_comment_id,
+                        comment_id,
+                        comment,
+                    ),
+            ))
     }
 }
@@ -0,0 +1,252 @@
+use super::utils::{next_migration_batch, next_migration_entries};
+use crate::consts::*;
+use crate::external_db::ext_db;
+use crate::types::{ExternalDbMigration, MigrationSection};
+use crate::utils::*;
+use crate::{Contract, ContractExt, KudosError};
+use near_sdk::serde_json::Value;
+use near_sdk::{env, near_bindgen, AccountId, PromiseError, PromiseOrValue};
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    #[handle_result]
+    pub fn on_migration_db_init(
+        &mut self,
+        new_external_db_id: AccountId,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> Result<ExternalDbMigration, KudosError> {
+        callback_result.map_err(|e| KudosError::external_call_failure("SocialDB::set()", e))?;
+
+        let migration = ExternalDbMigration::new(new_external_db_id);
+        self.external_db_migration = Some(migration.clone());
+
+        Ok(migration)
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn on_migration_keys_acquired(
+        &mut self,
+        external_db_id: AccountId,
+        section: MigrationSection,
+        limit: u32,
+        #[callback_result] callback_result: Result<Value, PromiseError>,
+    ) -> Result<PromiseOrValue<ExternalDbMigration>, KudosError> {
+        let root_id = env::current_account_id();
+        let mut keys_res = callback_result.map_err(|e| {
+            KudosError::external_call_failure(
+                format!(
+                    "SocialDB::keys({})",
+                    build_migration_section_keys_request(&root_id, section)
+                ),
+                e,
+            )
+        })?;
+
+        let migration = self.migration_in_section(section)?;
+        let keys = remove_key_from_json(&mut keys_res, &format!("{root_id}/{section}"))
+            .unwrap_or_default();
+        let batch = next_migration_batch(keys, migration.cursor.as_deref(), limit as usize);
+
+        let Some(last_key) = batch.last().cloned() else {
+            // Current section is copied entirely, proceed with the next one
+            migration.section = section.next();
+            migration.cursor = None;
+
+            return Ok(PromiseOrValue::Value(migration.clone()));
+        };
+
+        // Compute minimum required gas and split the remaining gas by three equal parts for
+        // NEAR social db subsequent calls
+        let get_entries_gas = (env::prepaid_gas()
+            - (env::used_gas()
+                + MIGRATION_KEYS_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_SAVED_CALLBACK_GAS))
+            / 3;
+        let get_entries_callback_gas = get_entries_gas * 2
+            + MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS
+            + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+            + MIGRATION_BATCH_SAVED_CALLBACK_GAS;
+        let get_entries_req = batch
+            .iter()
+            .map(|key| build_migration_key_entries_request(&root_id, section, key))
+            .collect();
+
+        Ok(ext_db::ext(external_db_id.clone())
+            .with_static_gas(get_entries_gas)
+            .keys(get_entries_req, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(get_entries_callback_gas)
+                    .on_migration_entries_acquired(external_db_id, section, last_key, limit),
+            )
+            .into())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn on_migration_entries_acquired(
+        &mut self,
+        external_db_id: AccountId,
+        section: MigrationSection,
+        last_key: String,
+        limit: u32,
+        #[callback_result] callback_result: Result<Value, PromiseError>,
+    ) -> Result<PromiseOrValue<ExternalDbMigration>, KudosError> {
+        let root_id = env::current_account_id();
+        let mut entries_res = callback_result
+            .map_err(|e| KudosError::external_call_failure("SocialDB::keys()", e))?;
+
+        let migration = self.migration_in_section(section)?;
+        let keys = remove_key_from_json(&mut entries_res, &format!("{root_id}/{section}"))
+            .unwrap_or_default();
+        // Kudos of a single key are copied by several batches if there are more of them than a limit,
+        // so a batch never exceeds a limit regardless of how many kudos a receiver has
+        let (entries, cursor) =
+            next_migration_entries(keys, migration.cursor.as_deref(), &last_key, limit as usize);
+
+        if entries.is_empty() {
+            return self
+                .save_migration_cursor(section, cursor, 0)
+                .map(PromiseOrValue::Value);
+        }
+
+        // Compute minimum required gas and split the remaining gas by two equal parts for
+        // NEAR social db subsequent calls
+        let get_batch_gas = (env::prepaid_gas()
+            - (env::used_gas()
+                + MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_SAVED_CALLBACK_GAS))
+            / 2;
+        let get_batch_callback_gas = get_batch_gas
+            + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+            + MIGRATION_BATCH_SAVED_CALLBACK_GAS;
+        let entries_count = entries.len() as u64;
+        let get_batch_req = entries
+            .iter()
+            .map(|entry| build_migration_entry_data_request(&root_id, section, entry))
+            .collect();
+
+        Ok(ext_db::ext(external_db_id)
+            .with_static_gas(get_batch_gas)
+            .get(get_batch_req, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(get_batch_callback_gas)
+                    .on_migration_batch_acquired(section, cursor, entries_count),
+            )
+            .into())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn on_migration_batch_acquired(
+        &mut self,
+        section: MigrationSection,
+        cursor: String,
+        entries_count: u64,
+        #[callback_result] callback_result: Result<Value, PromiseError>,
+    ) -> Result<PromiseOrValue<ExternalDbMigration>, KudosError> {
+        let batch =
+            callback_result.map_err(|e| KudosError::external_call_failure("SocialDB::get()", e))?;
+
+        // Entries without stored data, e.g. deleted ones, are skipped
+        let is_empty = batch
+            .as_object()
+            .map(|batch| batch.is_empty())
+            .unwrap_or(true);
+        if is_empty {
+            return self
+                .save_migration_cursor(section, cursor, entries_count)
+                .map(PromiseOrValue::Value);
+        }
+
+        let new_external_db_id = self
+            .migration_in_section(section)?
+            .new_external_db_id
+            .clone();
+        let set_batch_gas = env::prepaid_gas()
+            - (env::used_gas()
+                + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_SAVED_CALLBACK_GAS);
+
+        // Storage of a new NEAR social db is paid from the storage balance of this contract,
+        // which should be deposited in advance
+        Ok(ext_db::ext(new_external_db_id)
+            .with_static_gas(set_batch_gas)
+            .set(batch)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(MIGRATION_BATCH_SAVED_CALLBACK_GAS)
+                    .on_migration_batch_saved(section, cursor, entries_count),
+            )
+            .into())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn on_migration_batch_saved(
+        &mut self,
+        section: MigrationSection,
+        cursor: String,
+        entries_count: u64,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> Result<ExternalDbMigration, KudosError> {
+        callback_result.map_err(|e| KudosError::external_call_failure("SocialDB::set()", e))?;
+
+        self.save_migration_cursor(section, cursor, entries_count)
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn on_external_db_migrated(
+        &mut self,
+        new_external_db_id: AccountId,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> Result<(), KudosError> {
+        callback_result.map_err(|e| {
+            KudosError::external_call_failure(
+                format!("SocialDB::grant_write_permission(`{}`)", self.iah_registry),
+                e,
+            )
+        })?;
+
+        // Switch to a new external db and complete migration at once, so storage backend
+        // becomes available again only with a new external db
+        self.external_db_id = Some(new_external_db_id);
+        self.external_db_migration = None;
+
+        Ok(())
+    }
+}
+
+impl Contract {
+    /// Return [`ExternalDbMigration`] if it's still copying provided [`MigrationSection`]
+    fn migration_in_section(
+        &mut self,
+        section: MigrationSection,
+    ) -> Result<&mut ExternalDbMigration, KudosError> {
+        self.external_db_migration
+            .as_mut()
+            .filter(|migration| migration.section == Some(section))
+            .ok_or(KudosError::ExternalDbMigrationNotStarted)
+    }
+
+    /// Memorise the last copied entry of [`MigrationSection`], so the next step continues after it
+    fn save_migration_cursor(
+        &mut self,
+        section: MigrationSection,
+        cursor: String,
+        entries_count: u64,
+    ) -> Result<ExternalDbMigration, KudosError> {
+        let migration = self.migration_in_section(section)?;
+        migration.cursor = Some(cursor);
+        migration.migrated_keys = migration.migrated_keys.saturating_add(entries_count);
+
+        Ok(migration.clone())
+    }
+}
@@ -1,6 +1,11 @@
+mod close_season;
 mod common;
 mod exchange_kudos_for_sbt;
 mod give_kudos;
 mod leave_comment;
+mod migrate_external_db;
+mod react_to_comment;
+mod report_content;
+mod update_kudos_status;
 mod upvote_kudos;
 mod utils;
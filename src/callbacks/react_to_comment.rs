@@ -0,0 +1,188 @@
+use super::utils::{optional_promise_result, ActionPipeline};
+use crate::consts::*;
+use crate::registry::TokenId;
+use crate::settings::Settings;
+use crate::storage::{KudosField, KudosStorage, StorageBackend};
+use crate::types::{CommentId, HumanityAction, KudosId, RateLimitedAction};
+use crate::utils::*;
+use crate::{Contract, ContractExt, KudosError};
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::Value;
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseError, PromiseOrValue};
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn acquire_reacted_comment(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+        reaction: String,
+        #[callback_result] callback_result: Result<Vec<(AccountId, Vec<TokenId>)>, PromiseError>,
+    ) -> Promise {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        let result = self
+            .verify_humanity(
+                &env::signer_account_id(),
+                HumanityAction::Comment,
+                callback_result,
+                optional_promise_result(1).and_then(Result::ok),
+            )
+            .map(|_| {
+                self.acquire_reacted_comment_verified(
+                    pipeline.predecessor_account_id.clone(),
+                    pipeline.attached_deposit,
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                    comment_id,
+                    reaction,
+                )
+            });
+
+        pipeline.step(result)
+    }
+
+    #[private]
+    pub fn on_reacted_comment_acquired(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+        reaction: String,
+        comment_path: String,
+        #[callback_result] callback_result: Result<Value, PromiseError>,
+    ) -> Promise {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        let result = callback_result
+            .map_err(|e| KudosError::external_call_failure("KudosStorage::read_kudos()", e))
+            .and_then(|mut comment_res| {
+                remove_key_from_json(&mut comment_res, &comment_path)
+                    .ok_or(KudosError::CommentNotFound)?;
+
+                let gas_left = ActionPipeline::gas_left(
+                    REACTED_COMMENT_ACQUIRED_CALLBACK_GAS + COMMENT_REACTION_SAVED_CALLBACK_GAS,
+                );
+
+                Ok(storage
+                    .add_comment_reaction(
+                        &receiver_id,
+                        &kudos_id,
+                        &comment_id,
+                        &env::signer_account_id(),
+                        &reaction,
+                        pipeline.attached_deposit,
+                        gas_left,
+                    )?
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(ActionPipeline::callback_gas(
+                                COMMENT_REACTION_SAVED_CALLBACK_GAS,
+                            ))
+                            .on_comment_reaction_saved(
+                                pipeline.predecessor_account_id.clone(),
+                                pipeline.attached_deposit.into(),
+                                reaction,
+                            ),
+                    ))
+            });
+
+        // Return reaction deposit back to sender if failed
+        pipeline.step(result)
+    }
+
+    #[private]
+    pub fn on_comment_reaction_saved(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        reaction: String,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> PromiseOrValue<String> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to sender if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    self.record_rate_limited_action(
+                        &Settings::from(&self.settings),
+                        &env::signer_account_id(),
+                        RateLimitedAction::Comment,
+                        env::block_timestamp_ms(),
+                    );
+
+                    reaction
+                })
+                .map_err(|e| {
+                    KudosError::external_call_failure("KudosStorage::add_comment_reaction()", e)
+                }),
+        )
+    }
+}
+
+impl Contract {
+    /// Acquire a commentary message from a storage backend to verify that it could be reacted to by
+    /// a sender, who was verified as a human
+    pub(crate) fn acquire_reacted_comment_verified(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: Balance,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+        reaction: String,
+    ) -> Promise {
+        let comment_path = build_get_kudos_comment_by_id_request(
+            &env::current_account_id(),
+            &receiver_id,
+            &kudos_id,
+            &comment_id,
+        );
+
+        // Compute minimum required gas and split the remaining gas by two equal parts for
+        // storage backend subsequent calls
+        let get_comment_gas = ActionPipeline::gas_left(
+            ACQUIRE_REACTED_COMMENT_RESERVED_GAS
+                + REACTED_COMMENT_ACQUIRED_CALLBACK_GAS
+                + COMMENT_REACTION_SAVED_CALLBACK_GAS,
+        ) / 2;
+        let get_comment_callback_gas = ActionPipeline::callback_gas(
+            get_comment_gas
+                + REACTED_COMMENT_ACQUIRED_CALLBACK_GAS
+                + COMMENT_REACTION_SAVED_CALLBACK_GAS,
+        );
+
+        storage
+            .read_kudos(
+                &receiver_id,
+                &kudos_id,
+                &[KudosField::Comment(comment_id.clone())],
+                get_comment_gas,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(get_comment_callback_gas)
+                    .on_reacted_comment_acquired(
+                        predecessor_account_id,
+                        attached_deposit.into(),
+                        storage.clone(),
+                        receiver_id,
+                        kudos_id,
+                        comment_id,
+                        reaction,
+                        comment_path,
+                    ),
+            )
+    }
+}
@@ -0,0 +1,144 @@
+use super::utils::{optional_promise_result, ActionPipeline};
+use crate::consts::*;
+use crate::registry::TokenId;
+use crate::settings::Settings;
+use crate::storage::{KudosStorage, StorageBackend};
+use crate::types::{HumanityAction, Report, ReportStatus, ReportedContent};
+use crate::utils::*;
+use crate::{Contract, ContractExt, KudosError};
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::Value;
+use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseError, PromiseOrValue};
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn on_report_verified(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        storage: StorageBackend,
+        content: ReportedContent,
+        content_path: String,
+        #[callback_result] content_result: Result<Value, PromiseError>,
+    ) -> PromiseOrValue<Report> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+        let reporter_id = env::signer_account_id();
+
+        // Humanity is not verified if reporter's humanity verification was cached
+        let iah_result = optional_promise_result::<Vec<(AccountId, Vec<TokenId>)>>(1)
+            .map(|iah_result| {
+                self.verify_humanity(
+                    &reporter_id,
+                    HumanityAction::Report,
+                    iah_result,
+                    optional_promise_result(2).and_then(Result::ok),
+                )
+                .map(|_| ())
+            })
+            .unwrap_or(Ok(()));
+
+        let result = iah_result.and_then(|_| {
+            let mut content_res = content_result
+                .map_err(|e| KudosError::external_call_failure("KudosStorage::read_kudos()", e))?;
+            remove_key_from_json(&mut content_res, &content_path)
+                .ok_or(KudosError::ReportedContentNotFound)?;
+
+            self.verify_report_allowed(&content, &reporter_id)?;
+
+            let report = self.reports.get(&content).cloned().unwrap_or_default();
+            let min_number_of_reports =
+                Settings::from(&self.settings).min_number_of_reports_to_hide_content as u32;
+
+            // Content is hidden only once, when the last required report is given
+            if report.status != ReportStatus::Open
+                || report.count.saturating_add(1) < min_number_of_reports
+            {
+                return Ok(None);
+            }
+
+            let gas_left = ActionPipeline::gas_left(
+                REPORT_VERIFIED_CALLBACK_GAS + REPORTED_CONTENT_HIDDEN_CALLBACK_GAS,
+            );
+
+            storage
+                .set_content_hidden(&content, true, pipeline.attached_deposit, gas_left)
+                .map(Some)
+        });
+
+        match result {
+            Ok(None) => {
+                // Nothing is written to a storage backend, so report deposit is returned back to sender
+                Promise::new(pipeline.predecessor_account_id).transfer(pipeline.attached_deposit);
+                PromiseOrValue::Value(self.record_report(content, reporter_id, None))
+            }
+            Ok(Some(hide_content)) => hide_content
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(ActionPipeline::callback_gas(
+                            REPORTED_CONTENT_HIDDEN_CALLBACK_GAS,
+                        ))
+                        .on_reported_content_hidden(
+                            pipeline.predecessor_account_id,
+                            pipeline.attached_deposit.into(),
+                            content,
+                        ),
+                )
+                .into(),
+            // Return report deposit back to sender if failed
+            Err(e) => pipeline.fail(e).into(),
+        }
+    }
+
+    #[private]
+    pub fn on_reported_content_hidden(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        content: ReportedContent,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> PromiseOrValue<Report> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to sender if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    self.record_report(
+                        content,
+                        env::signer_account_id(),
+                        Some(ReportStatus::Hidden),
+                    )
+                })
+                .map_err(|e| {
+                    KudosError::external_call_failure("KudosStorage::set_content_hidden()", e)
+                }),
+        )
+    }
+
+    #[private]
+    pub fn on_report_resolved(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        content: ReportedContent,
+        status: ReportStatus,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> PromiseOrValue<Report> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to moderator if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    let report = self.reports.entry(content).or_default();
+                    report.status = status;
+
+                    report.clone()
+                })
+                .map_err(|e| {
+                    KudosError::external_call_failure("KudosStorage::set_content_hidden()", e)
+                }),
+        )
+    }
+}
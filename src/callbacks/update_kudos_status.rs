@@ -0,0 +1,87 @@
+use super::utils::ActionPipeline;
+use crate::consts::*;
+use crate::storage::{KudosStorage, StorageBackend};
+use crate::types::{KudosId, KudosStatus};
+use crate::utils::*;
+use crate::{Contract, ContractExt, KudosError};
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::Value;
+use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseError, PromiseOrValue};
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn on_kudos_receiver_verified(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        storage: StorageBackend,
+        kudos_id: KudosId,
+        status: KudosStatus,
+        kudos_sender_path: String,
+        #[callback_result] callback_result: Result<Value, PromiseError>,
+    ) -> Promise {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        let result = callback_result
+            .map_err(|e| KudosError::external_call_failure("KudosStorage::read_kudos()", e))
+            .and_then(|mut kudos_sender_res| {
+                // Kudos is stored under receiver's path, so it exists only if caller is a receiver
+                remove_key_from_json(&mut kudos_sender_res, &kudos_sender_path)
+                    .ok_or(KudosError::KudosNotFound)?;
+
+                let gas_left = ActionPipeline::gas_left(
+                    KUDOS_RECEIVER_VERIFIED_CALLBACK_GAS + KUDOS_STATUS_UPDATED_CALLBACK_GAS,
+                );
+
+                Ok(storage
+                    .set_kudos_status(
+                        &env::signer_account_id(),
+                        &kudos_id,
+                        &status,
+                        pipeline.attached_deposit,
+                        gas_left,
+                    )?
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(ActionPipeline::callback_gas(
+                                KUDOS_STATUS_UPDATED_CALLBACK_GAS,
+                            ))
+                            .on_kudos_status_updated(
+                                pipeline.predecessor_account_id.clone(),
+                                pipeline.attached_deposit.into(),
+                                kudos_id,
+                                status,
+                            ),
+                    ))
+            });
+
+        // Return update kudos status deposit back to sender if failed
+        pipeline.step(result)
+    }
+
+    #[private]
+    pub fn on_kudos_status_updated(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: U128,
+        kudos_id: KudosId,
+        status: KudosStatus,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) -> PromiseOrValue<KudosStatus> {
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to sender if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    self.memorise_kudos_status(&kudos_id, status.clone());
+
+                    status
+                })
+                .map_err(|e| {
+                    KudosError::external_call_failure("KudosStorage::set_kudos_status()", e)
+                }),
+        )
+    }
+}
@@ -1,13 +1,16 @@
+use super::utils::{optional_promise_result, ActionPipeline};
+use crate::collusion::extract_earliest_issued_at;
 use crate::consts::*;
-use crate::external_db::ext_db;
-use crate::registry::TokenId;
-use crate::types::KudosId;
+use crate::registry::{ext_sbtreg, Token, TokenId, SBTS_GAS};
+use crate::settings::Settings;
+use crate::storage::{KudosField, KudosStorage, StorageBackend};
+use crate::types::{HumanityAction, KudosId, KudosStatus, RateLimitedAction};
 use crate::utils::*;
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, KudosError};
 use near_sdk::json_types::U128;
 use near_sdk::json_types::U64;
 use near_sdk::serde_json::Value;
-use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseError, PromiseOrValue};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseError, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
@@ -16,65 +19,36 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
-        external_db_id: AccountId,
+        storage: StorageBackend,
         receiver_id: AccountId,
         kudos_id: KudosId,
         #[callback_result] callback_result: Result<Vec<(AccountId, Vec<TokenId>)>, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
 
-        let result = callback_result
-            .map_err(|e| format!("IAHRegistry::is_human() call failure: {e:?}"))
+        let result = self
+            .verify_humanity(
+                &env::signer_account_id(),
+                HumanityAction::Upvote,
+                callback_result,
+                optional_promise_result(1).and_then(Result::ok),
+            )
             .and_then(|tokens| {
-                if tokens.is_empty() {
-                    return Err("IAHRegistry::is_human() returns result: Not a human".to_owned());
-                }
+                let Some((issuer_id, tokens)) = tokens.into_iter().next() else {
+                    return Err(KudosError::NotHuman);
+                };
 
-                let root_id = env::current_account_id();
-                let sender_id = env::signer_account_id();
-                let upvote_kudos_req =
-                    build_upvote_kudos_request(&root_id, &sender_id, &receiver_id, &kudos_id)?;
-                let get_kudos_by_id_req =
-                    build_get_kudos_by_id_request(&root_id, &receiver_id, &kudos_id);
-
-                // Compute minimum required gas and split the remaining gas by two equal parts for
-                // NEAR Social db subsequent calls
-                let get_kudos_by_id_gas = (env::prepaid_gas()
-                    - (ACQUIRE_KUDOS_SENDER_RESERVED_GAS
-                        + KUDOS_SENDER_ACQUIRED_CALLBACK_GAS
-                        + KUDOS_UPVOTE_SAVED_CALLBACK_GAS
-                        + FAILURE_CALLBACK_GAS))
-                    / 2;
-                let get_kudos_by_id_callback_gas = get_kudos_by_id_gas
-                    + KUDOS_SENDER_ACQUIRED_CALLBACK_GAS
-                    + KUDOS_UPVOTE_SAVED_CALLBACK_GAS
-                    + FAILURE_CALLBACK_GAS;
-
-                Ok(ext_db::ext(external_db_id.clone())
-                    .with_static_gas(get_kudos_by_id_gas)
-                    .get(vec![get_kudos_by_id_req.clone()], None)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(get_kudos_by_id_callback_gas)
-                            .on_kudos_sender_acquired(
-                                predecessor_account_id.clone(),
-                                attached_deposit.into(),
-                                external_db_id,
-                                get_kudos_by_id_req,
-                                upvote_kudos_req,
-                            ),
-                    ))
+                self.acquire_kudos_sender_verified(
+                    pipeline.predecessor_account_id.clone(),
+                    pipeline.attached_deposit,
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                    Some((issuer_id, tokens)),
+                )
             });
 
-        result.unwrap_or_else(|e| {
-            Promise::new(predecessor_account_id)
-                .transfer(attached_deposit)
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(FAILURE_CALLBACK_GAS)
-                        .on_failure(e),
-                )
-        })
+        pipeline.step(result)
     }
 
     #[private]
@@ -82,51 +56,79 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
-        external_db_id: AccountId,
-        get_kudos_by_id_req: String,
-        upvote_kudos_req: Value,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
         #[callback_result] callback_result: Result<Value, PromiseError>,
     ) -> Promise {
-        let attached_deposit = attached_deposit.0;
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+        // Failure to acquire i-am-human SBTs do not prevent upvote, but such upvote
+        // will not be counted toward exchange if SBT minimum age is required.
+        // SBTs are not acquired if upvoter's humanity verification was cached.
+        let iah_sbt_issued_at = optional_promise_result::<Vec<Option<Token>>>(1)
+            .and_then(Result::ok)
+            .and_then(|tokens| extract_earliest_issued_at(&tokens));
 
-        let Err(e) = callback_result
-            .map_err(|e| format!("SocialDB::get({get_kudos_by_id_req}) call failure: {e:?}"))
+        let get_kudos_by_id_req =
+            build_get_kudos_by_id_request(&env::current_account_id(), &receiver_id, &kudos_id);
+        let result = callback_result
+            .map_err(|e| KudosError::external_call_failure("KudosStorage::read_kudos()", e))
             .and_then(|mut kudos_by_id_res| {
-                match extract_kudos_id_sender_from_response(&get_kudos_by_id_req, &mut kudos_by_id_res) {
+                match extract_kudos_id_sender_from_response(
+                    &get_kudos_by_id_req,
+                    &mut kudos_by_id_res,
+                ) {
                     Some(sender_id) if sender_id == env::signer_account_id() => {
-                        Err("User is not eligible to upvote this kudos".to_owned())
+                        return Err(KudosError::NotEligibleToUpvote);
                     }
-                    Some(_) => Ok(()),
-                    None => Err("Unable to acquire a Kudos sender account id".to_owned())
+                    Some(_) => (),
+                    None => return Err(KudosError::KudosSenderNotFound),
+                }
+
+                // Concurrent upvotes of the same kudos could pass a check made by `upvote_kudos`
+                if self.is_kudos_upvoted_by(&kudos_id, &env::signer_account_id()) {
+                    return Err(KudosError::KudosAlreadyUpvoted);
                 }
-            }) else {
-                let gas_left = env::prepaid_gas()
-                    - (KUDOS_SENDER_ACQUIRED_CALLBACK_GAS
-                        + KUDOS_UPVOTE_SAVED_CALLBACK_GAS
-                        + FAILURE_CALLBACK_GAS);
-
-                return ext_db::ext(external_db_id)
-                    .with_attached_deposit(attached_deposit)
-                    .with_static_gas(gas_left)
-                    .set(upvote_kudos_req)
+
+                let gas_left = ActionPipeline::gas_left(
+                    KUDOS_SENDER_ACQUIRED_CALLBACK_GAS + KUDOS_UPVOTE_SAVED_CALLBACK_GAS,
+                );
+                // Proposed ding is published together with an upvote which reaches a quorum
+                let status = self.ding_status_on_upvote(
+                    &Settings::from(&self.settings),
+                    &receiver_id,
+                    &kudos_id,
+                    &env::signer_account_id(),
+                );
+
+                // Storage of a memorised upvote is kept by this contract
+                Ok(storage
+                    .add_upvote(
+                        &receiver_id,
+                        &kudos_id,
+                        &env::signer_account_id(),
+                        status.as_ref(),
+                        UPVOTE_KUDOS_SOCIAL_DB_COST,
+                        gas_left,
+                    )?
                     .then(
                         Self::ext(env::current_account_id())
-                            .with_static_gas(KUDOS_UPVOTE_SAVED_CALLBACK_GAS + FAILURE_CALLBACK_GAS)
+                            .with_static_gas(ActionPipeline::callback_gas(
+                                KUDOS_UPVOTE_SAVED_CALLBACK_GAS,
+                            ))
                             .on_kudos_upvote_saved(
-                                predecessor_account_id,
-                                attached_deposit.into(),
+                                pipeline.predecessor_account_id.clone(),
+                                pipeline.attached_deposit.into(),
+                                receiver_id,
+                                kudos_id,
+                                iah_sbt_issued_at.map(U64),
+                                status,
                             ),
-                    );
-            };
+                    ))
+            });
 
         // Return upvote kudos deposit back to sender if failed
-        Promise::new(predecessor_account_id)
-            .transfer(attached_deposit)
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(FAILURE_CALLBACK_GAS)
-                    .on_failure(e),
-            )
+        pipeline.step(result)
     }
 
     #[private]
@@ -134,23 +136,153 @@ impl Contract {
         &mut self,
         predecessor_account_id: AccountId,
         attached_deposit: U128,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        iah_sbt_issued_at: Option<U64>,
+        status: Option<KudosStatus>,
         #[callback_result] callback_result: Result<(), PromiseError>,
     ) -> PromiseOrValue<U64> {
-        let attached_deposit = attached_deposit.0;
-
-        match callback_result {
-            Ok(_) => PromiseOrValue::Value(env::block_timestamp_ms().into()),
-            Err(e) => {
-                // Return deposit back to sender if NEAR SocialDb write failure
-                Promise::new(predecessor_account_id)
-                    .transfer(attached_deposit)
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .with_static_gas(FAILURE_CALLBACK_GAS)
-                            .on_failure(format!("SocialDB::set() call failure: {e:?}")),
-                    )
-                    .into()
-            }
+        let pipeline = ActionPipeline::new(predecessor_account_id, attached_deposit.0);
+
+        // Return deposit back to sender if storage backend write failure
+        pipeline.complete(
+            callback_result
+                .map(|_| {
+                    let now = env::block_timestamp_ms();
+                    let is_recorded = self.record_upvote(
+                        &Settings::from(&self.settings),
+                        &env::signer_account_id(),
+                        &receiver_id,
+                        &kudos_id,
+                        iah_sbt_issued_at.map(|ts| ts.0),
+                        now,
+                    );
+                    let publish = self.record_ding_upvote(
+                        &Settings::from(&self.settings),
+                        &kudos_id,
+                        &env::signer_account_id(),
+                        status,
+                    );
+                    if let Some((receiver_id, status)) = publish {
+                        self.publish_ding(&receiver_id, &kudos_id, status);
+                    }
+                    // Stats and season tally are counted once for an upvoter, even if an upvote
+                    // was saved again
+                    self.record_rate_limited_action(
+                        &Settings::from(&self.settings),
+                        &env::signer_account_id(),
+                        RateLimitedAction::Upvote,
+                        now,
+                    );
+                    if is_recorded {
+                        self.record_upvote_stats(&env::signer_account_id(), &receiver_id);
+                        self.tally_season_upvote(&receiver_id, now);
+                    }
+
+                    now.into()
+                })
+                .map_err(|e| KudosError::external_call_failure("KudosStorage::add_upvote()", e)),
+        )
+    }
+
+    #[private]
+    pub fn on_ding_published(
+        &mut self,
+        kudos_id: KudosId,
+        status: KudosStatus,
+        #[callback_result] callback_result: Result<(), PromiseError>,
+    ) {
+        // Ding still awaits a quorum if a status wasn't saved, so it's published by the next upvote
+        if callback_result.is_ok() && self.ding_quorums.contains_key(&kudos_id) {
+            self.memorise_kudos_status(&kudos_id, status);
+        }
+    }
+}
+
+impl Contract {
+    /// Schedule a write of [`KudosStatus`] of a proposed ding which reached a quorum by concurrent upvotes.
+    ///
+    /// Storage of a status is covered by an upvote deposit, which is computed for an upvote written together
+    /// with a status of a published ding.
+    fn publish_ding(&self, receiver_id: &AccountId, kudos_id: &KudosId, status: KudosStatus) {
+        let Ok(storage) = self.storage_backend() else {
+            return;
+        };
+        let Ok(set_status) = storage.set_kudos_status(
+            receiver_id,
+            kudos_id,
+            &status,
+            0,
+            SOCIAL_DB_REQUEST_MIN_RESERVED_GAS,
+        ) else {
+            return;
+        };
+
+        set_status.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(DING_PUBLISHED_CALLBACK_GAS)
+                .on_ding_published(kudos_id.clone(), status),
+        );
+    }
+
+    /// Acquire kudos sender from a storage backend to verify that kudos could be upvoted by a user, who was
+    /// verified as a human. User's i-am-human SBTs [`TokenId`] issued by provided issuer [`AccountId`] are
+    /// acquired additionally if provided.
+    pub(crate) fn acquire_kudos_sender_verified(
+        &mut self,
+        predecessor_account_id: AccountId,
+        attached_deposit: Balance,
+        storage: StorageBackend,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        iah_tokens: Option<(AccountId, Vec<TokenId>)>,
+    ) -> Result<Promise, KudosError> {
+        let sender_id = env::signer_account_id();
+        if self.is_sender_blocked(&receiver_id, &sender_id) {
+            return Err(KudosError::SenderBlocked);
         }
+
+        // Compute minimum required gas and split the remaining gas by two equal parts for
+        // storage backend subsequent calls
+        let get_kudos_by_id_gas = ActionPipeline::gas_left(
+            ACQUIRE_KUDOS_SENDER_RESERVED_GAS
+                + SBTS_GAS
+                + KUDOS_SENDER_ACQUIRED_CALLBACK_GAS
+                + KUDOS_UPVOTE_SAVED_CALLBACK_GAS,
+        ) / 2;
+        let get_kudos_by_id_callback_gas = ActionPipeline::callback_gas(
+            get_kudos_by_id_gas
+                + KUDOS_SENDER_ACQUIRED_CALLBACK_GAS
+                + KUDOS_UPVOTE_SAVED_CALLBACK_GAS,
+        );
+
+        let get_kudos_by_id = storage.read_kudos(
+            &receiver_id,
+            &kudos_id,
+            &[KudosField::Info],
+            get_kudos_by_id_gas,
+        );
+        // Upvoter's i-am-human SBTs are acquired to memorise when they were issued, unless
+        // upvoter's humanity verification was cached
+        let get_kudos_by_id = match iah_tokens {
+            Some((issuer_id, tokens)) => get_kudos_by_id.and(
+                ext_sbtreg::ext(self.iah_registry.clone())
+                    .with_static_gas(SBTS_GAS)
+                    .sbts(issuer_id, tokens),
+            ),
+            None => get_kudos_by_id,
+        };
+
+        Ok(get_kudos_by_id.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(get_kudos_by_id_callback_gas)
+                .on_kudos_sender_acquired(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                ),
+        ))
     }
 }
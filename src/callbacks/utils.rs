@@ -1,53 +1,301 @@
-use crate::{utils::*, KudosKind};
+use crate::consts::FAILURE_CALLBACK_GAS;
+use crate::{
+    utils::*, CommentId, Contract, EncodedCommentary, KudosError, KudosKind, KudosKindInfo,
+};
+use near_sdk::json_types::U64;
+use near_sdk::serde::de::DeserializeOwned;
 use near_sdk::serde_json::{self, Value};
-use near_sdk::{AccountId, PromiseError};
-use std::collections::HashMap;
+use near_sdk::{
+    env, AccountId, Balance, Gas, Promise, PromiseError, PromiseOrValue, PromiseResult,
+};
+use std::collections::{BTreeMap, HashMap};
 
-pub fn parse_kudos_and_verify_if_allowed_to_exchange(
+/// Parse kudos JSON acquired from a storage backend and verify if it has sufficient amount of upvotes
+/// to be exchanged for ProofOfKudos SBT.
+///
+/// Exchange rules are defined by kind information [`KudosKindInfo`] returned by `kind_info(kind)`.
+/// Only upvotes for which `is_upvote_counted(upvoter_id, kudos_sender_id, kudos_created_at)` returns
+/// `true` are counted toward the minimum required number of upvotes.
+pub fn parse_kudos_and_verify_if_allowed_to_exchange<F, G>(
     res: Result<Value, PromiseError>,
     kudos_upvotes_path: String,
     kudos_kind_path: String,
+    kudos_sender_path: String,
+    kudos_created_at_path: String,
     required_min_number_of_upvotes: usize,
-) -> Result<(), String> {
-    let mut kudos_json = res.map_err(|e| {
-        format!("SocialDB::get({kudos_upvotes_path}/*,{kudos_kind_path}) call failure: {e:?}")
-    })?;
+    kind_info: G,
+    is_upvote_counted: F,
+) -> Result<(), KudosError>
+where
+    F: Fn(&AccountId, &AccountId, u64) -> bool,
+    G: Fn(&KudosKind) -> Option<KudosKindInfo>,
+{
+    let mut kudos_json =
+        res.map_err(|e| KudosError::external_call_failure("KudosStorage::read_kudos()", e))?;
 
     let kudos_kind = match remove_key_from_json(&mut kudos_json, &kudos_kind_path) {
         Some(kudos_kind_raw) => serde_json::from_value::<KudosKind>(kudos_kind_raw.clone())
-            .map_err(|e| format!("Failed to parse kudos kind type `{kudos_kind_raw:?}`: {e:?}"))?,
+            .map_err(|e| KudosError::InvalidKudosData {
+                details: format!("Failed to parse kudos kind type `{kudos_kind_raw:?}`: {e:?}"),
+            })?,
         None => KudosKind::Kudos,
     };
 
-    if kudos_kind == KudosKind::Ding {
-        return Err("Dings can't be exchanged".to_owned());
+    let kind_info = kind_info(&kudos_kind).ok_or(KudosError::KudosKindNotFound)?;
+    if !kind_info.exchangeable {
+        // Dings keep their own error code, which is used by clients
+        return Err(match kudos_kind {
+            KudosKind::Ding => KudosError::DingsNotExchangeable,
+            kind => KudosError::KudosKindNotExchangeable {
+                kind: kind.to_string(),
+            },
+        });
+    }
+
+    if !kind_info.needs_upvotes {
+        return Ok(());
     }
 
     let upvotes_raw = remove_key_from_json(&mut kudos_json, &kudos_upvotes_path)
-        .ok_or_else(|| format!("No upvotes found for kudos: {kudos_json:?}"))?;
+        .ok_or(KudosError::NoUpvotesFound)?;
 
     let upvoters = serde_json::from_value::<HashMap<AccountId, Value>>(upvotes_raw.clone())
-        .map_err(|e| format!("Failed to parse kudos upvotes data `{upvotes_raw:?}`: {e:?}"))?;
+        .map_err(|e| KudosError::InvalidKudosData {
+            details: format!("Failed to parse kudos upvotes data `{upvotes_raw:?}`: {e:?}"),
+        })?;
+
+    let min_upvotes_not_reached_err = || KudosError::MinUpvotesNotReached {
+        required: required_min_number_of_upvotes as u32,
+    };
+
+    if upvoters.len() < required_min_number_of_upvotes {
+        return Err(min_upvotes_not_reached_err());
+    }
+
+    let kudos_sender_id = remove_key_from_json(&mut kudos_json, &kudos_sender_path)
+        .and_then(|val| serde_json::from_value::<AccountId>(val).ok())
+        .ok_or(KudosError::KudosSenderNotFound)?;
+
+    let kudos_created_at = remove_key_from_json(&mut kudos_json, &kudos_created_at_path)
+        .and_then(|val| serde_json::from_value::<U64>(val).ok())
+        .ok_or(KudosError::KudosCreatedAtNotFound)?;
 
-    let number_of_upvotes = upvoters.keys().len();
+    let number_of_upvotes = upvoters
+        .keys()
+        .filter(|&upvoter_id| is_upvote_counted(upvoter_id, &kudos_sender_id, kudos_created_at.0))
+        .count();
 
     if number_of_upvotes < required_min_number_of_upvotes {
-        Err(format!(
-            "Minimum required number ({}) of upvotes has not been reached",
-            required_min_number_of_upvotes
-        ))
+        Err(min_upvotes_not_reached_err())
     } else {
         Ok(())
     }
 }
 
+/// Return JSON deserialized result of a promise by index if such promise exists.
+///
+/// Used by callbacks which are called after a promise, which could be skipped, e.g. for users
+/// with cached humanity verification result.
+pub fn optional_promise_result<T: DeserializeOwned>(index: u64) -> Option<Result<T, PromiseError>> {
+    if env::promise_results_count() <= index {
+        return None;
+    }
+
+    Some(match env::promise_result(index) {
+        PromiseResult::Successful(data) => {
+            serde_json::from_slice::<T>(&data).map_err(|_| PromiseError::Failed)
+        }
+        PromiseResult::NotReady => Err(PromiseError::NotReady),
+        PromiseResult::Failed => Err(PromiseError::Failed),
+    })
+}
+
+/// Split a migration cursor into the last copied top-level key and the last copied kudos id of it,
+/// which is `None` if the key was copied entirely
+fn split_migration_cursor(cursor: Option<&str>) -> (Option<&str>, Option<&str>) {
+    match cursor.map(|cursor| cursor.split_once('/')) {
+        Some(Some((key, kudos_id))) => (Some(key), Some(kudos_id)),
+        Some(None) => (cursor, None),
+        None => (None, None),
+    }
+}
+
+/// Return sorted top-level keys of a migrated section acquired from NEAR social db, which follow
+/// the cursor or were copied partially, limited to a number of keys
+pub(crate) fn next_migration_batch(keys: Value, cursor: Option<&str>, limit: usize) -> Vec<String> {
+    let Value::Object(keys) = keys else {
+        return vec![];
+    };
+    let (cursor_key, cursor_kudos_id) = split_migration_cursor(cursor);
+
+    let mut keys = keys
+        .into_iter()
+        .map(|(key, _)| key)
+        // `None` cursor is less than any key, so all keys are taken for the first batch
+        .filter(|key| {
+            Some(key.as_str()) > cursor_key
+                || (cursor_kudos_id.is_some() && Some(key.as_str()) == cursor_key)
+        })
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys.truncate(limit);
+
+    keys
+}
+
+/// Return sorted entries `{key}/{kudos_id}` of top-level keys of a migrated section acquired from
+/// NEAR social db, which follow the cursor, limited to a number of entries. Also returns a cursor which
+/// should be saved once entries are copied, it's `{key}` if the last entry completes a key, or the last
+/// top-level key of a batch if all entries of a batch are taken.
+pub(crate) fn next_migration_entries(
+    keys: Value,
+    cursor: Option<&str>,
+    last_key: &str,
+    limit: usize,
+) -> (Vec<String>, String) {
+    let Value::Object(keys) = keys else {
+        return (vec![], last_key.to_owned());
+    };
+    let (cursor_key, cursor_kudos_id) = split_migration_cursor(cursor);
+
+    let mut entries = keys
+        .into_iter()
+        .flat_map(|(key, kudos_ids)| {
+            let kudos_ids = match kudos_ids {
+                Value::Object(kudos_ids) => kudos_ids.into_iter().map(|(id, _)| id).collect(),
+                _ => vec![],
+            };
+
+            kudos_ids.into_iter().map(move |id| (key.clone(), id))
+        })
+        .filter(|(key, id)| {
+            Some(key.as_str()) > cursor_key
+                || (Some(key.as_str()) == cursor_key
+                    && cursor_kudos_id.is_some_and(|cursor_id| id.as_str() > cursor_id))
+        })
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    let next_cursor = match entries.get(limit.saturating_sub(1)) {
+        // Key is copied partially if the next entry belongs to the same key
+        Some((key, id))
+            if entries
+                .get(limit)
+                .is_some_and(|(next_key, _)| next_key == key) =>
+        {
+            format!("{key}/{id}")
+        }
+        Some((key, _)) => key.clone(),
+        // All entries of a batch are taken, so every top-level key of it is copied entirely
+        None => last_key.to_owned(),
+    };
+    entries.truncate(limit);
+
+    (
+        entries
+            .into_iter()
+            .map(|(key, id)| format!("{key}/{id}"))
+            .collect(),
+        next_cursor,
+    )
+}
+
+/// Return depth of a reply to a parent commentary message [`CommentId`] by decoding parent ids of
+/// commentary messages of kudos up to a top-level one, which depth is zero.
+///
+/// Fails if a parent is not a commentary message of the same kudos or a reply is deeper than allowed.
+pub(crate) fn comment_thread_depth(
+    comments: &BTreeMap<CommentId, EncodedCommentary>,
+    parent_comment_id: &CommentId,
+    max_depth: u8,
+) -> Result<u8, KudosError> {
+    let mut comment_id = parent_comment_id.clone();
+    let mut depth = 0u8;
+
+    // Walk is limited by maximum depth, so it never loops over corrupted data
+    loop {
+        depth = depth.saturating_add(1);
+        if depth > max_depth {
+            return Err(KudosError::CommentThreadTooDeep { max_depth });
+        }
+
+        match comments
+            .get(&comment_id)
+            .ok_or(KudosError::ParentCommentNotFound)?
+            .parent_comment_id()
+        {
+            Some(parent_comment_id) => comment_id = parent_comment_id,
+            None => return Ok(depth),
+        }
+    }
+}
+
+/// Multi-step action performed by a user, who attached a deposit to pay for it.
+///
+/// Every step of an action is executed within a callback and either schedules the next step,
+/// completes an action with a value or fails. If any step fails, attached deposit is returned back
+/// to a user and a final failure callback panics with an error message.
+pub struct ActionPipeline {
+    pub predecessor_account_id: AccountId,
+    pub attached_deposit: Balance,
+}
+
+impl ActionPipeline {
+    pub fn new(predecessor_account_id: AccountId, attached_deposit: Balance) -> Self {
+        Self {
+            predecessor_account_id,
+            attached_deposit,
+        }
+    }
+
+    /// Return [`Gas`] left for an external call of the current step, excluding gas reserved for
+    /// the remaining steps and a final failure callback
+    pub fn gas_left(reserved_gas: Gas) -> Gas {
+        env::prepaid_gas() - (reserved_gas + FAILURE_CALLBACK_GAS)
+    }
+
+    /// Return [`Gas`] which should be attached to a callback step, so it could fail gracefully
+    pub fn callback_gas(step_gas: Gas) -> Gas {
+        step_gas + FAILURE_CALLBACK_GAS
+    }
+
+    /// Proceed with the next step of an action or fail if the current step has failed
+    pub fn step<E: Into<KudosError>>(self, result: Result<Promise, E>) -> Promise {
+        result.unwrap_or_else(|e| self.fail(e))
+    }
+
+    /// Complete an action with a value or fail if the last step has failed
+    pub fn complete<T, E: Into<KudosError>>(self, result: Result<T, E>) -> PromiseOrValue<T> {
+        match result {
+            Ok(value) => PromiseOrValue::Value(value),
+            Err(e) => self.fail(e).into(),
+        }
+    }
+
+    /// Return attached deposit back to a user and panic with an error message
+    pub fn fail<E: Into<KudosError>>(self, error: E) -> Promise {
+        Promise::new(self.predecessor_account_id)
+            .transfer(self.attached_deposit)
+            .then(
+                Contract::ext(env::current_account_id())
+                    .with_static_gas(FAILURE_CALLBACK_GAS)
+                    .on_failure(error.into()),
+            )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::{build_kudos_kind_path, build_kudos_upvotes_path};
+    use crate::utils::{
+        build_get_kudos_by_id_request, build_kudos_created_at_path, build_kudos_kind_path,
+        build_kudos_sender_path, build_kudos_upvotes_path,
+        extract_kudos_encoded_comments_from_response,
+    };
     use crate::{IncrementalUniqueId, KudosId};
     use near_sdk::serde_json::json;
-    use near_sdk::test_utils::accounts;
+    use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+    use near_sdk::testing_env;
 
     #[test]
     fn test_parse_kudos_and_verify_upvotes() {
@@ -56,6 +304,8 @@ mod tests {
         let receiver_id = accounts(0);
         let kudos_upvotes_path = build_kudos_upvotes_path(&root_id, &receiver_id, &kudos_id);
         let kudos_kind_path = build_kudos_kind_path(&root_id, &receiver_id, &kudos_id);
+        let kudos_sender_path = build_kudos_sender_path(&root_id, &receiver_id, &kudos_id);
+        let kudos_created_at_path = build_kudos_created_at_path(&root_id, &receiver_id, &kudos_id);
 
         struct TestCase<'a> {
             name: &'a str,
@@ -125,17 +375,97 @@ mod tests {
                       }
                     }
                 })),
-                output: "Failed to parse kudos kind type `String(\"unknown\")`: Error(\"unknown variant `unknown`, expected `k` or `d`\", line: 0, column: 0)",
+                output: "Failed to parse kudos kind type `String(\"unknown\")`: Error(\"Invalid kudos kind `unknown`, expected a single lowercase letter\", line: 0, column: 0)",
+            },
+            TestCase {
+                name: "Unregistered kudos kind",
+                input: Ok(json!({
+                    "kudos.near": {
+                      "kudos": {
+                        "alice": {
+                          "1": {
+                            "kind": "m",
+                            "upvotes": {}
+                          }
+                        }
+                      }
+                    }
+                })),
+                output: "Kudos kind is not registered",
             },
             TestCase {
                 name: "Invalid response",
                 input: Ok(json!({})),
-                output: "No upvotes found for kudos: Object {}",
+                output: "No upvotes found for kudos",
             },
             TestCase {
                 name: "Promise error",
                 input: Err(near_sdk::PromiseError::Failed),
-                output: "SocialDB::get(kudos.near/kudos/alice/1/upvotes/*,kudos.near/kudos/alice/1/kind) call failure: Failed",
+                output: "KudosStorage::read_kudos() call failure: Failed",
+            },
+            TestCase {
+                name: "Missing kudos sender",
+                input: Ok(json!({
+                    "kudos.near": {
+                      "kudos": {
+                        "alice": {
+                          "1": {
+                            "kind": "k",
+                            "created_at": "1234567890",
+                            "upvotes": {
+                              "charlie": "",
+                              "danny": "",
+                              "eugene": ""
+                            }
+                          }
+                        }
+                      }
+                    }
+                })),
+                output: "Unable to acquire a Kudos sender account id",
+            },
+            TestCase {
+                name: "Missing kudos creation timestamp",
+                input: Ok(json!({
+                    "kudos.near": {
+                      "kudos": {
+                        "alice": {
+                          "1": {
+                            "kind": "k",
+                            "sender_id": "bob",
+                            "upvotes": {
+                              "charlie": "",
+                              "danny": "",
+                              "eugene": ""
+                            }
+                          }
+                        }
+                      }
+                    }
+                })),
+                output: "Unable to acquire a Kudos creation timestamp",
+            },
+            TestCase {
+                name: "Upvotes not counted",
+                input: Ok(json!({
+                    "kudos.near": {
+                      "kudos": {
+                        "alice": {
+                          "1": {
+                            "kind": "k",
+                            "sender_id": "bob",
+                            "created_at": "1234567890",
+                            "upvotes": {
+                              "bob": "",
+                              "danny": "",
+                              "eugene": ""
+                            }
+                          }
+                        }
+                      }
+                    }
+                })),
+                output: "Minimum required number (3) of upvotes has not been reached",
             },
         ];
 
@@ -145,14 +475,231 @@ mod tests {
                     test_case.input,
                     kudos_upvotes_path.clone(),
                     kudos_kind_path.clone(),
-                    3
+                    kudos_sender_path.clone(),
+                    kudos_created_at_path.clone(),
+                    3,
+                    KudosKind::builtin_info,
+                    |upvoter_id, kudos_sender_id, _| upvoter_id != kudos_sender_id
                 )
                 .unwrap_err()
-                .as_str(),
+                .message(),
                 test_case.output,
                 "Test case `{} failure`",
                 test_case.name
             );
         }
+
+        assert!(parse_kudos_and_verify_if_allowed_to_exchange(
+            Ok(json!({
+                "kudos.near": {
+                  "kudos": {
+                    "alice": {
+                      "1": {
+                        "kind": "k",
+                        "sender_id": "bob",
+                        "created_at": "1234567890",
+                        "upvotes": {
+                          "charlie": "",
+                          "danny": "",
+                          "eugene": ""
+                        }
+                      }
+                    }
+                  }
+                }
+            })),
+            kudos_upvotes_path.clone(),
+            kudos_kind_path.clone(),
+            kudos_sender_path.clone(),
+            kudos_created_at_path.clone(),
+            3,
+            KudosKind::builtin_info,
+            |upvoter_id, kudos_sender_id, _| upvoter_id != kudos_sender_id
+        )
+        .is_ok());
+
+        // Registered kind which doesn't need upvotes to be exchanged
+        assert!(parse_kudos_and_verify_if_allowed_to_exchange(
+            Ok(json!({
+                "kudos.near": {
+                  "kudos": {
+                    "alice": {
+                      "1": {
+                        "kind": "m",
+                        "upvotes": {}
+                      }
+                    }
+                  }
+                }
+            })),
+            kudos_upvotes_path,
+            kudos_kind_path,
+            kudos_sender_path,
+            kudos_created_at_path,
+            3,
+            |kind| {
+                (kind == &KudosKind::Custom(b'm')).then(|| KudosKindInfo {
+                    name: "Mentorship".to_owned(),
+                    icon_cid: None,
+                    exchangeable: true,
+                    allowed_in_batch: true,
+                    needs_upvotes: false,
+                })
+            },
+            |upvoter_id, kudos_sender_id, _| upvoter_id != kudos_sender_id
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_next_migration_batch() {
+        let keys = json!({
+            "charlie.near": true,
+            "alice.near": true,
+            "bob.near": true,
+            "danny.near": true,
+        });
+
+        assert_eq!(
+            next_migration_batch(keys.clone(), None, 2),
+            vec!["alice.near".to_owned(), "bob.near".to_owned()]
+        );
+        assert_eq!(
+            next_migration_batch(keys.clone(), Some("bob.near"), 5),
+            vec!["charlie.near".to_owned(), "danny.near".to_owned()]
+        );
+        assert!(next_migration_batch(keys.clone(), Some("danny.near"), 5).is_empty());
+        assert!(next_migration_batch(json!(null), None, 5).is_empty());
+        // Partially copied key is taken again
+        assert_eq!(
+            next_migration_batch(keys, Some("bob.near/2"), 2),
+            vec!["bob.near".to_owned(), "charlie.near".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_next_migration_entries() {
+        let keys = json!({
+            "bob.near": { "3": true, "2": true, "4": true },
+            "alice.near": { "1": true },
+            "charlie.near": {},
+        });
+
+        // Key is copied partially if the limit is reached in the middle of it
+        assert_eq!(
+            next_migration_entries(keys.clone(), None, "charlie.near", 2),
+            (
+                vec!["alice.near/1".to_owned(), "bob.near/2".to_owned()],
+                "bob.near/2".to_owned()
+            )
+        );
+        assert_eq!(
+            next_migration_entries(keys.clone(), Some("bob.near/2"), "charlie.near", 2),
+            (
+                vec!["bob.near/3".to_owned(), "bob.near/4".to_owned()],
+                "bob.near".to_owned()
+            )
+        );
+        // All entries of a batch are taken
+        assert_eq!(
+            next_migration_entries(keys.clone(), Some("alice.near"), "charlie.near", 5),
+            (
+                vec![
+                    "bob.near/2".to_owned(),
+                    "bob.near/3".to_owned(),
+                    "bob.near/4".to_owned()
+                ],
+                "charlie.near".to_owned()
+            )
+        );
+        assert_eq!(
+            next_migration_entries(keys, Some("bob.near"), "charlie.near", 5),
+            (vec![], "charlie.near".to_owned())
+        );
+        assert_eq!(
+            next_migration_entries(json!(null), None, "charlie.near", 5),
+            (vec![], "charlie.near".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_comment_thread_depth() {
+        let root_id = AccountId::new_unchecked("kudos.near".to_owned());
+        let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+        let get_kudos_by_id_req = build_get_kudos_by_id_request(&root_id, &accounts(0), &kudos_id);
+        let comment = |parent_comment_id: Option<u64>| {
+            EncodedCommentary::try_from(&crate::Commentary {
+                message: &Value::String("commentary test".to_owned()),
+                sender_id: &accounts(1),
+                timestamp: U64(1234567890),
+                parent_comment_id: parent_comment_id.map(CommentId::new_unchecked).as_ref(),
+            })
+            .unwrap()
+        };
+
+        // Thread of commentary messages 2 <- 3 <- 4, where 5 replies to missing commentary message
+        let comments = extract_kudos_encoded_comments_from_response(
+            &get_kudos_by_id_req,
+            &mut json!({
+                "kudos.near": {
+                    "kudos": {
+                        "alice": {
+                            "1": {
+                                "comments": {
+                                    "2": comment(None),
+                                    "3": comment(Some(2)),
+                                    "4": comment(Some(3)),
+                                    "5": comment(Some(6)),
+                                }
+                            }
+                        }
+                    }
+                }
+            }),
+        );
+        assert_eq!(comments.len(), 4);
+
+        assert_eq!(
+            comment_thread_depth(&comments, &CommentId::new_unchecked(2), 3),
+            Ok(1)
+        );
+        assert_eq!(
+            comment_thread_depth(&comments, &CommentId::new_unchecked(4), 3),
+            Ok(3)
+        );
+        assert_eq!(
+            comment_thread_depth(&comments, &CommentId::new_unchecked(4), 2),
+            Err(KudosError::CommentThreadTooDeep { max_depth: 2 })
+        );
+        assert_eq!(
+            comment_thread_depth(&comments, &CommentId::new_unchecked(2), 0),
+            Err(KudosError::CommentThreadTooDeep { max_depth: 0 })
+        );
+        assert_eq!(
+            comment_thread_depth(&comments, &CommentId::new_unchecked(5), 3),
+            Err(KudosError::ParentCommentNotFound)
+        );
+        assert_eq!(
+            comment_thread_depth(&comments, &CommentId::new_unchecked(7), 3),
+            Err(KudosError::ParentCommentNotFound)
+        );
+    }
+
+    #[test]
+    fn test_action_pipeline_refund() {
+        testing_env!(VMContextBuilder::new().build());
+
+        assert!(matches!(
+            ActionPipeline::new(accounts(1), 10).complete(Ok::<_, KudosError>(1)),
+            PromiseOrValue::Value(1)
+        ));
+        assert!(get_created_receipts().is_empty());
+
+        // Deposit is returned back to a user if action failed
+        assert!(matches!(
+            ActionPipeline::new(accounts(1), 10).complete(Err::<u8, _>(KudosError::KudosNotFound)),
+            PromiseOrValue::Promise(_)
+        ));
+        assert_eq!(get_created_receipts()[0].receiver_id, accounts(1));
     }
 }
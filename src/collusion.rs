@@ -0,0 +1,116 @@
+use crate::registry::Token;
+use crate::settings::Settings;
+use crate::types::{KudosId, PeriodUpvote};
+use crate::Contract;
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Memorise an upvote given by upvoter to a receiver's kudos with its position [`PeriodUpvote`] among
+    /// upvotes given to the same receiver within a current period and the earliest timestamp when upvoter's
+    /// i-am-human SBT was issued at.
+    ///
+    /// Storage is paid by upvoter as a part of [`UPVOTE_KUDOS_COST`](crate::UPVOTE_KUDOS_COST).
+    ///
+    /// Returns `false` and keeps a memorised upvote unchanged if upvoter already upvoted this kudos.
+    pub(crate) fn record_upvote(
+        &mut self,
+        settings: &Settings,
+        upvoter_id: &AccountId,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        iah_sbt_issued_at: Option<u64>,
+        now: u64,
+    ) -> bool {
+        let key = (kudos_id.clone(), upvoter_id.clone());
+        if self.upvote_periods.contains_key(&key) {
+            return false;
+        }
+
+        let period_start = settings.reciprocal_upvotes_period_start(now);
+
+        let given = self
+            .upvotes_by_pair
+            .entry((upvoter_id.clone(), receiver_id.clone(), period_start))
+            .or_default();
+        *given = given.saturating_add(1);
+        let position = *given;
+
+        self.upvote_periods.insert(
+            key,
+            PeriodUpvote {
+                period_start,
+                position,
+            },
+        );
+
+        if let Some(issued_at) = iah_sbt_issued_at {
+            self.iah_sbt_issued_at.insert(upvoter_id.clone(), issued_at);
+        }
+
+        true
+    }
+
+    /// Return [`bool`] which represents if kudos was already upvoted by upvoter
+    pub(crate) fn is_kudos_upvoted_by(&self, kudos_id: &KudosId, upvoter_id: &AccountId) -> bool {
+        self.upvote_periods
+            .contains_key(&(kudos_id.clone(), upvoter_id.clone()))
+    }
+
+    /// Return [`bool`] which represents if an upvote given by upvoter to a receiver's kudos should be
+    /// counted toward exchange for ProofOfKudos SBT.
+    ///
+    /// Upvote is not counted if:
+    /// - it was given by kudos sender (if enabled)
+    /// - receiver and upvoter upvoted each other kudos within a period when the upvote was given and
+    ///   upvoter already gave allowed maximum of upvotes to receiver's kudos within that period before
+    /// - upvoter's i-am-human SBT was issued less than a minimum required time before kudos was created
+    pub(crate) fn is_upvote_counted(
+        &self,
+        settings: &Settings,
+        upvoter_id: &AccountId,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        kudos_sender_id: &AccountId,
+        kudos_created_at: u64,
+    ) -> bool {
+        if settings.ignore_kudos_sender_upvotes && upvoter_id == kudos_sender_id {
+            return false;
+        }
+
+        // Upvotes given before their periods were memorised are counted
+        if let Some(upvote) = self
+            .upvote_periods
+            .get(&(kudos_id.clone(), upvoter_id.clone()))
+        {
+            let is_reciprocal = self
+                .upvotes_by_pair
+                .get(&(receiver_id.clone(), upvoter_id.clone(), upvote.period_start))
+                .is_some_and(|&received| received > 0);
+
+            if is_reciprocal && upvote.position > settings.max_reciprocal_upvotes_per_period as u32
+            {
+                return false;
+            }
+        }
+
+        if settings.min_upvoter_iah_sbt_age > 0 {
+            return match self.iah_sbt_issued_at.get(upvoter_id) {
+                Some(issued_at) => issued_at
+                    .checked_add(settings.min_upvoter_iah_sbt_age)
+                    .map(|ts| ts <= kudos_created_at)
+                    .unwrap_or_default(),
+                None => false,
+            };
+        }
+
+        true
+    }
+}
+
+/// Return the earliest timestamp in milliseconds when one of the provided i-am-human SBTs was issued at
+pub(crate) fn extract_earliest_issued_at(tokens: &[Option<Token>]) -> Option<u64> {
+    tokens
+        .iter()
+        .filter_map(|token| token.as_ref().and_then(|t| t.metadata.issued_at))
+        .min()
+}
@@ -2,8 +2,12 @@ use near_sdk::env::STORAGE_PRICE_PER_BYTE;
 use near_sdk::{Balance, Gas, StorageUsage};
 
 pub(crate) const U64_STORAGE: StorageUsage = 8;
+pub(crate) const U32_STORAGE: StorageUsage = 4;
 pub(crate) const U8_STORAGE: StorageUsage = 1;
 
+/// [`AccountId`](near_sdk::AccountId) of maximum length (64 characters) serialized with its length as u32
+pub(crate) const ACCOUNT_ID_MAX_STORAGE: StorageUsage = U32_STORAGE + 64;
+
 /// Every contract storage key/value entry always uses 40 bytes when stored via `env::storage_write`
 /// - key len as u64,
 /// - key ptr as u64,
@@ -42,31 +46,247 @@ pub const EXCHANGE_KUDOS_COST: Balance =
 /// This value was pre-computed by using maximum (64 characters) account id length for IAH Registry and this contracts.
 pub const SOCIAL_DB_GRANT_WRITE_PERMISSION_COST: Balance = 3_100_000_000_000_000_000_000;
 
+/// Prefix of a collection of children of an object at NEAR social db, which is a node prefix [1 byte],
+/// a node id [`u32`] and a collection prefix [1 byte]
+const SOCIAL_DB_CHILDREN_PREFIX: StorageUsage = U8_STORAGE + U32_STORAGE + U8_STORAGE;
+
+/// Required storage to write a new key of an object to NEAR social db excluding a key itself and its value.
+/// Children of every object are kept in `UnorderedMap`, so a key is written to three entries:
+/// - an index [`u64`] keyed by a key serialized with its length as u32
+/// - a key serialized with its length as u32 keyed by an index [`u64`]
+/// - a value keyed by an index [`u64`]
+const SOCIAL_DB_KEY_STORAGE: StorageUsage = 3 * (STORAGE_ENTRY + SOCIAL_DB_CHILDREN_PREFIX)
+    + U64_STORAGE
+    + 2 * U32_STORAGE
+    + 2 * U64_STORAGE;
+
+/// Required storage to write a string value of a key to NEAR social db excluding a value itself, which is
+/// an enum tag [1 byte], a value length as u32 and a block height [`u64`] when a value was written
+const SOCIAL_DB_VALUE_STORAGE: StorageUsage = U8_STORAGE + U32_STORAGE + U64_STORAGE;
+
+/// Required storage to write a new object as a value of a key to NEAR social db, which is an enum tag [1 byte]
+/// and a node id [`u32`] of a key value, and a new node keyed by a node prefix [1 byte] and a node id [`u32`].
+/// A node is a node id [`u32`], a block height [`u64`] and `UnorderedMap` of children with three prefixes
+/// serialized with their lengths as u32 and lengths [`u64`] of two vectors.
+const SOCIAL_DB_OBJECT_STORAGE: StorageUsage = U8_STORAGE
+    + U32_STORAGE
+    + STORAGE_ENTRY
+    + U8_STORAGE
+    + U32_STORAGE
+    + U32_STORAGE
+    + U64_STORAGE
+    + 3 * (U32_STORAGE + SOCIAL_DB_CHILDREN_PREFIX)
+    + 2 * U64_STORAGE;
+
 /// Deposit required to give kudos to user.
 ///
 /// The computed deposit amount is based on a case when user with maximum account name length (64 characters)
 /// grants kudos with maximum provided commentary text length (1000 escaped ascii characters),
-/// icon CID for ipfs and maximum number of allowed hashtags (10) with a hashtag of maximum
+/// icon CID for ipfs, maximum number of allowed hashtags (10) with a hashtag of maximum
 /// text length (limited to 32 characters, and allows to use only alphanumeric ascii characters, underscores and gyphens)
-/// to a user with maximum account name length (64 characters). The exact value of this computation is 0.0961 Ⓝ and
-/// it is rounded to 0.1 Ⓝ. This value should be recomputed if the above restrications will be changed.
-pub const GIVE_KUDOS_COST: Balance = 100_000_000_000_000_000_000_000; // 0.1 Ⓝ (0.09802)
+/// and maximum number of mentioned users (5) with maximum account name length (64 characters)
+/// to a user with maximum account name length (64 characters). Receiver and mentioned users are notified
+/// by near.social notifications written to "index/notify". The exact value of this computation is 0.1255 Ⓝ
+/// and it is rounded to 0.13 Ⓝ. This value should be recomputed if the above
+/// restrications will be changed.
+pub const GIVE_KUDOS_COST: Balance = 130_000_000_000_000_000_000_000; // 0.13 Ⓝ (0.1255)
+
+/// [`Hashtag`](crate::Hashtag) of maximum length (32 characters) serialized with its length as u32
+const HASHTAG_MAX_STORAGE: StorageUsage = U32_STORAGE + 32;
 
-/// Deposit required to leave a commentary message for kudos
+/// Number of periods of hashtag usage kept within [`HASHTAG_TRENDING_MAX_WINDOW`]
+const HASHTAG_USAGE_MAX_PERIODS: StorageUsage =
+    (HASHTAG_TRENDING_MAX_WINDOW / HASHTAG_USAGE_PERIOD) as StorageUsage + 1;
+
+/// Required storage to count usage of a hashtag in this smart contract storage, which is used to read top and
+/// trending hashtags
+///
+/// It is based on a case when a hashtag of maximum length (32 characters) is used for the first time:
+/// - a hashtag keyed by [`u32`] index of [`UnorderedMap`](near_sdk::store::UnorderedMap) of usage statistics
+/// - usage statistics keyed by a hashtag, which are a total [`u64`] counter, counters [`u64`], [`u32`] of
+///   every kept period and [`u32`] index
+/// - a position of a hashtag in a ranking of top hashtags, which is a tree node keyed by [`u64`] index and
+///   a key of two [`u64`] counters and a hashtag mapped to nothing
+pub const HASHTAG_USAGE_STORAGE: StorageUsage =
+    (STORAGE_ENTRY + ENUM_STORAGE_KEY + U32_STORAGE + U8_STORAGE + HASHTAG_MAX_STORAGE)
+        + (STORAGE_ENTRY
+            + ENUM_STORAGE_KEY
+            + HASHTAG_MAX_STORAGE
+            + U64_STORAGE
+            + U32_STORAGE
+            + HASHTAG_USAGE_MAX_PERIODS * (U64_STORAGE + U32_STORAGE)
+            + U32_STORAGE)
+        + (STORAGE_ENTRY
+            + 2 * ENUM_STORAGE_KEY
+            + U64_STORAGE
+            + (U64_STORAGE
+                + 2 * U64_STORAGE
+                + HASHTAG_MAX_STORAGE
+                + 2 * (U8_STORAGE + U64_STORAGE)
+                + U64_STORAGE))
+        + (STORAGE_ENTRY + 2 * ENUM_STORAGE_KEY + 2 * U64_STORAGE + HASHTAG_MAX_STORAGE);
+
+/// Deposit required for every hashtag of kudos in addition to [`GIVE_KUDOS_COST`], which pays for storage
+/// of hashtag usage statistics counted by this smart contract (0.00777 Ⓝ)
+pub const HASHTAG_USAGE_COST: Balance = HASHTAG_USAGE_STORAGE as Balance * STORAGE_PRICE_PER_BYTE;
+
+/// Maximum length of an attachment index written as a key, which is [`u8`] in decimal notation
+const KUDOS_ATTACHMENT_INDEX_MAX_LENGTH: StorageUsage = 3;
+
+/// Required storage to write an attachment of kudos to NEAR social db
+///
+/// It is based on a case when an attachment has a CID of maximum length (64 characters), MIME type of maximum
+/// length ([`KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH`] characters) and a caption of maximum length
+/// ([`KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH`] characters). Every attachment is written as an object of these fields
+/// under "attachments/{index}":
+/// - "attachments" object of kudos, which is counted for every attachment
+/// - an object of an attachment keyed by its index
+/// - "cid", "mime_type" and "caption" values of an attachment
+///
+/// This value should be recomputed if the above restrications will be changed.
+pub const KUDOS_ATTACHMENT_STORAGE: StorageUsage = (SOCIAL_DB_KEY_STORAGE
+    + 2 * "attachments".len() as StorageUsage
+    + SOCIAL_DB_OBJECT_STORAGE)
+    + (SOCIAL_DB_KEY_STORAGE + 2 * KUDOS_ATTACHMENT_INDEX_MAX_LENGTH + SOCIAL_DB_OBJECT_STORAGE)
+    + (SOCIAL_DB_KEY_STORAGE + 2 * "cid".len() as StorageUsage + SOCIAL_DB_VALUE_STORAGE + 64)
+    + (SOCIAL_DB_KEY_STORAGE
+        + 2 * "mime_type".len() as StorageUsage
+        + SOCIAL_DB_VALUE_STORAGE
+        + KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH as StorageUsage)
+    + (SOCIAL_DB_KEY_STORAGE
+        + 2 * "caption".len() as StorageUsage
+        + SOCIAL_DB_VALUE_STORAGE
+        + KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH as StorageUsage);
+
+/// Deposit required for every attachment of kudos in addition to [`GIVE_KUDOS_COST`] (0.01399 Ⓝ)
+pub const KUDOS_ATTACHMENT_COST: Balance =
+    KUDOS_ATTACHMENT_STORAGE as Balance * STORAGE_PRICE_PER_BYTE;
+
+/// Maximum length of a MIME type of kudos attachment, e.g. "image/png"
+pub const KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH: usize = 64;
+
+/// Maximum length of a caption of kudos attachment
+pub const KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH: usize = 100;
+
+/// Deposit required to leave a commentary message for kudos encoded by [`CommentaryEncoding::V1`](crate::CommentaryEncoding::V1)
 ///
 /// The computed deposit amount is based on a case when user with maximum account name length (64 characters)
 /// leaves a commentary message text with maximum length (1000 escaped ascii characters)
-/// to a user with maximum account name length (64 characters). The exact value of this computation when
-/// no parent commentary id provided is 0.01653 Ⓝ and it is rounded to 0.017 Ⓝ. This value should be recomputed
+/// to a user with maximum account name length (64 characters). Receiver and a sender of a parent commentary
+/// message are notified by near.social notifications written to "index/notify". The exact value of this
+/// computation is 0.02267 Ⓝ and it is rounded to 0.024 Ⓝ. This value should be recomputed
 /// if the above restrications will be changed.
-pub const LEAVE_COMMENT_COST: Balance = 17_000_000_000_000_000_000_000; // 0.017 Ⓝ (0.01653 when no parent commentary id)
+pub const LEAVE_COMMENT_COST: Balance = 24_000_000_000_000_000_000_000; // 0.024 Ⓝ (0.02267)
+
+/// Deposit required to leave a commentary message for kudos encoded by [`CommentaryEncoding::V2`](crate::CommentaryEncoding::V2)
+///
+/// The computed deposit amount is based on the same case as [`LEAVE_COMMENT_COST`]. A commentary message
+/// of about 1130 bytes is stored without base64 encoding, which takes about 1500 bytes, so the stored value
+/// is shorter by about 370 bytes (0.0037 Ⓝ). The exact value of this computation is 0.01897 Ⓝ and it is
+/// rounded to 0.02 Ⓝ. This value should be recomputed if the above restrications will be changed.
+pub const LEAVE_COMMENT_V2_COST: Balance = 20_000_000_000_000_000_000_000; // 0.02 Ⓝ (0.01897)
+
+/// Required storage to memorise an upvote in this smart contract storage, which is used to detect reciprocal
+/// upvotes and upvoters with recently issued i-am-human SBT
+///
+/// It is based on a case when an upvoter with maximum account name length (64 characters) upvotes kudos of
+/// a receiver with maximum account name length for the first time within a period and upvoter's i-am-human SBT
+/// issue date is memorised for the first time:
+/// - a number of upvotes given to the receiver within a period, [`u32`] keyed by both accounts and a period
+///   start [`u64`]
+/// - a position of the upvote within a period, [`u64`] and [`u32`] keyed by upvoted [`KudosId`](crate::KudosId)
+///   [`u64`] and upvoter's account
+/// - a timestamp [`u64`] when upvoter's i-am-human SBT was issued, keyed by upvoter's account
+pub const UPVOTE_KUDOS_STORAGE: StorageUsage =
+    (STORAGE_ENTRY + ENUM_STORAGE_KEY + 2 * ACCOUNT_ID_MAX_STORAGE + U64_STORAGE + U32_STORAGE)
+        + (STORAGE_ENTRY
+            + ENUM_STORAGE_KEY
+            + U64_STORAGE
+            + ACCOUNT_ID_MAX_STORAGE
+            + U64_STORAGE
+            + U32_STORAGE)
+        + (STORAGE_ENTRY + ENUM_STORAGE_KEY + ACCOUNT_ID_MAX_STORAGE + U64_STORAGE);
+
+/// Deposit required to upvote kudos, which is written to NEAR social db
+///
+/// The computed deposit amount is based on a case when user with maximum account name length (64 characters)
+/// upvotes kudos, and receiver is notified by near.social notification written to "index/notify".
+/// The exact value of this computation is 0.00638 Ⓝ and it is rounded to 0.007 Ⓝ.
+/// This value should be recomputed if the above restrications will be changed.
+pub const UPVOTE_KUDOS_SOCIAL_DB_COST: Balance = 7_000_000_000_000_000_000_000; // 0.007 Ⓝ (0.00638)
 
 /// Deposit required to upvote kudos
 ///
+/// This value includes a deposit required to write an upvote to NEAR social db and a storage amount
+/// required to memorise an upvote in this smart contract storage (0.00435 Ⓝ).
+pub const UPVOTE_KUDOS_COST: Balance =
+    UPVOTE_KUDOS_SOCIAL_DB_COST + UPVOTE_KUDOS_STORAGE as Balance * STORAGE_PRICE_PER_BYTE; // 0.01135 Ⓝ
+
+/// Maximum length of [`CommentId`](crate::CommentId) written as a key, which is [`u64`] in decimal notation
+const COMMENT_ID_MAX_LENGTH: StorageUsage = 20;
+
+/// Required storage to react to a commentary message of kudos, which is written to NEAR social db
+///
+/// It is based on a case when user with maximum account name length (64 characters) is the first to react
+/// with a reaction of maximum length ([`COMMENT_REACTION_MAX_LENGTH`] bytes) to a commentary message:
+/// - "comments_reactions" object of kudos
+/// - an object of reactions keyed by [`CommentId`](crate::CommentId)
+/// - a reaction keyed by a sender account
+///
+/// Nobody is notified, so nothing else is written. This value should be recomputed if the above
+/// restrications will be changed.
+pub const REACT_TO_COMMENT_STORAGE: StorageUsage = (SOCIAL_DB_KEY_STORAGE
+    + 2 * "comments_reactions".len() as StorageUsage
+    + SOCIAL_DB_OBJECT_STORAGE)
+    + (SOCIAL_DB_KEY_STORAGE + 2 * COMMENT_ID_MAX_LENGTH + SOCIAL_DB_OBJECT_STORAGE)
+    + (SOCIAL_DB_KEY_STORAGE
+        + 2 * 64
+        + SOCIAL_DB_VALUE_STORAGE
+        + COMMENT_REACTION_MAX_LENGTH as StorageUsage);
+
+/// Deposit required to react to a commentary message of kudos (0.00959 Ⓝ)
+pub const REACT_TO_COMMENT_COST: Balance =
+    REACT_TO_COMMENT_STORAGE as Balance * STORAGE_PRICE_PER_BYTE;
+
+/// Maximum length in bytes of a reaction to a commentary message, e.g. an emoji
+pub const COMMENT_REACTION_MAX_LENGTH: usize = 16;
+
+/// Deposit required to update status of received kudos, e.g. accept, decline or hide it
+///
 /// The computed deposit amount is based on a case when user with maximum account name length (64 characters)
-/// upvotes kudos. The exact value of this computation is 0.00311 Ⓝ and it is rounded to 0.004 Ⓝ.
+/// updates status of received kudos. Stored status text is shorter than upvoter's account name, so this value
+/// doesn't exceed deposit required to upvote kudos and it is rounded to the same 0.004 Ⓝ.
+/// This value should be recomputed if the above restrications will be changed.
+pub const UPDATE_KUDOS_STATUS_COST: Balance = 4_000_000_000_000_000_000_000; // 0.004 Ⓝ
+
+/// Deposit required to block a sender
+///
+/// The computed deposit amount is based on a case when receiver with maximum account name length (64 characters)
+/// blocks the first sender with maximum account name length, which also requires to store an empty blocklist of
+/// the receiver. It takes less than 500 bytes of contract storage and it is rounded to 0.005 Ⓝ.
+/// The same amount is returned back when sender is unblocked.
 /// This value should be recomputed if the above restrications will be changed.
-pub const UPVOTE_KUDOS_COST: Balance = 4_000_000_000_000_000_000_000; // 0.004 Ⓝ (0.00311)
+pub const BLOCK_SENDER_COST: Balance = 5_000_000_000_000_000_000_000; // 0.005 Ⓝ
+
+/// Deposit required to report kudos or commentary message
+///
+/// The computed deposit amount is based on a case when the last required report hides content of a receiver
+/// with maximum account name length (64 characters), so a moderation flag is stored next to the kudos.
+/// Stored flag is shorter than upvoter's account name, so this value doesn't exceed deposit required to
+/// upvote kudos and it is rounded to the same 0.004 Ⓝ.
+/// The deposit is returned back if a report doesn't hide content, so nothing is written to a storage backend.
+/// This value should be recomputed if the above restrications will be changed.
+pub const REPORT_CONTENT_COST: Balance = 4_000_000_000_000_000_000_000; // 0.004 Ⓝ
+
+/// Deposit required to uphold or dismiss a report, which updates a moderation flag stored next to the kudos
+pub const MODERATE_REPORT_COST: Balance = 4_000_000_000_000_000_000_000; // 0.004 Ⓝ
+
+/// Default number of blocked senders returned by [`view_blocked_senders`](kudos_contract::public::Contract::view_blocked_senders)
+pub const BLOCKED_SENDERS_DEFAULT_LIMIT: u32 = 50;
+
+/// Default number of entries, e.g. kudos of a receiver or kudos tagged by a hashtag, copied by a single step of
+/// [`migrate_external_db`](kudos_contract::contract::Contract::migrate_external_db)
+pub const MIGRATION_BATCH_DEFAULT_LIMIT: u32 = 20;
 
 /// Gas reserved for final failure callback which panics if one of the callback fails.
 pub const FAILURE_CALLBACK_GAS: Gas = Gas(5 * Gas::ONE_TERA.0);
@@ -82,8 +302,11 @@ pub const GIVE_KUDOS_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
 pub const ACQUIRE_KUDOS_SENDER_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
 /// Gas required for a [`on_kudos_sender_acquired`](kudos_contract::callbacks::upvote_kudos::ContractExt::on_kudos_sender_acquired) callback
 pub const KUDOS_SENDER_ACQUIRED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
-/// Gas required for a [`on_kudos_upvote_saved`](kudos_contract::callbacks::upvote_kudos::ContractExt::on_kudos_upvote_saved) callback
-pub const KUDOS_UPVOTE_SAVED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_kudos_upvote_saved`](kudos_contract::callbacks::upvote_kudos::ContractExt::on_kudos_upvote_saved) callback,
+/// which includes gas to publish a proposed ding if concurrent upvotes reached a quorum
+pub const KUDOS_UPVOTE_SAVED_CALLBACK_GAS: Gas = Gas(25 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_ding_published`](kudos_contract::callbacks::upvote_kudos::ContractExt::on_ding_published) callback
+pub const DING_PUBLISHED_CALLBACK_GAS: Gas = Gas(5 * Gas::ONE_TERA.0);
 /// Gas reserved to a public method [`upvote_kudos`](kudos_contract::public::Contract::upvote_kudos)
 pub const UPVOTE_KUDOS_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
 
@@ -96,6 +319,15 @@ pub const KUDOS_COMMENT_SAVED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
 /// Gas reserved to a public method [`leave_comment`](kudos_contract::public::Contract::leave_comment)
 pub const LEAVE_COMMENT_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
 
+/// Gas required for a [`on_reacted_comment_acquired`](kudos_contract::callbacks::react_to_comment::ContractExt::on_reacted_comment_acquired) callback
+pub const REACTED_COMMENT_ACQUIRED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_comment_reaction_saved`](kudos_contract::callbacks::react_to_comment::ContractExt::on_comment_reaction_saved) callback
+pub const COMMENT_REACTION_SAVED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas required for a [`acquire_reacted_comment`](kudos_contract::callbacks::react_to_comment::ContractExt::acquire_reacted_comment) callback
+pub const ACQUIRE_REACTED_COMMENT_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas reserved to a public method [`react_to_comment`](kudos_contract::public::Contract::react_to_comment)
+pub const REACT_TO_COMMENT_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+
 /// Gas required for a [`acquire_number_of_upvotes`](kudos_contract::callbacks::exchange_kudos_for_sbt::ContractExt::acquire_number_of_upvotes) callback
 pub const ACQUIRE_NUMBER_OF_UPVOTES_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
 /// Gas required for a [`on_kudos_upvotes_acquired`](kudos_contract::callbacks::exchange_kudos_for_sbt::ContractExt::on_kudos_upvotes_acquired) callback
@@ -107,6 +339,71 @@ pub const PROOF_OF_KUDOS_SBT_MINT_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
 /// Gas reserved to a public method [`exchange_kudos_for_sbt`](kudos_contract::public::Contract::exchange_kudos_for_sbt)
 pub const EXCHANGE_KUDOS_FOR_SBT_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
 
+/// Gas required for a [`on_kudos_receiver_verified`](kudos_contract::callbacks::update_kudos_status::ContractExt::on_kudos_receiver_verified) callback
+pub const KUDOS_RECEIVER_VERIFIED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_kudos_status_updated`](kudos_contract::callbacks::update_kudos_status::ContractExt::on_kudos_status_updated) callback
+pub const KUDOS_STATUS_UPDATED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas reserved to public methods which update kudos status, e.g. [`hide_kudos`](kudos_contract::public::Contract::hide_kudos)
+pub const UPDATE_KUDOS_STATUS_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+
+/// Gas required for a [`on_report_verified`](kudos_contract::callbacks::report_content::ContractExt::on_report_verified) callback
+pub const REPORT_VERIFIED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_reported_content_hidden`](kudos_contract::callbacks::report_content::ContractExt::on_reported_content_hidden) callback
+pub const REPORTED_CONTENT_HIDDEN_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas reserved to public methods which report content, e.g. [`report_kudos`](kudos_contract::public::Contract::report_kudos)
+pub const REPORT_CONTENT_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_report_resolved`](kudos_contract::callbacks::report_content::ContractExt::on_report_resolved) callback
+pub const REPORT_RESOLVED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas reserved to public methods which resolve reports, e.g. [`uphold_report`](kudos_contract::public::Contract::uphold_report)
+pub const MODERATE_REPORT_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+
+/// Gas required for a [`on_migration_keys_acquired`](kudos_contract::callbacks::migrate_external_db::ContractExt::on_migration_keys_acquired) callback
+pub const MIGRATION_KEYS_ACQUIRED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_migration_entries_acquired`](kudos_contract::callbacks::migrate_external_db::ContractExt::on_migration_entries_acquired) callback
+pub const MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_migration_batch_acquired`](kudos_contract::callbacks::migrate_external_db::ContractExt::on_migration_batch_acquired) callback
+pub const MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+/// Gas required for a [`on_migration_batch_saved`](kudos_contract::callbacks::migrate_external_db::ContractExt::on_migration_batch_saved) callback
+pub const MIGRATION_BATCH_SAVED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas reserved to a public method [`migrate_external_db`](kudos_contract::contract::Contract::migrate_external_db)
+pub const MIGRATE_EXTERNAL_DB_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+
+/// Gas required for a [`on_season_sbts_minted`](kudos_contract::callbacks::close_season::ContractExt::on_season_sbts_minted) callback
+pub const SEASON_SBTS_MINTED_CALLBACK_GAS: Gas = Gas(10 * Gas::ONE_TERA.0);
+/// Gas reserved to a public method [`close_season`](kudos_contract::contract::Contract::close_season)
+pub const CLOSE_SEASON_RESERVED_GAS: Gas = Gas(15 * Gas::ONE_TERA.0);
+
+/// Duration in milliseconds of a period (1 day) used to count hashtags usage for trending hashtags
+pub const HASHTAG_USAGE_PERIOD: u64 = 86_400_000;
+
+/// Maximum window in milliseconds (30 days) within which trending hashtags could be counted
+pub const HASHTAG_TRENDING_MAX_WINDOW: u64 = 30 * HASHTAG_USAGE_PERIOD;
+
+/// Maximum number of hashtags which usage is counted. Once it's reached, the least used hashtag is forgotten
+/// to count a new one, unless it was used within [`HASHTAG_TRENDING_MAX_WINDOW`].
+pub const HASHTAGS_MAX_TRACKED: u32 = 1_000;
+
+/// Default number of hashtags returned by view methods of top and trending hashtags
+pub const TOP_HASHTAGS_DEFAULT_LIMIT: u16 = 10;
+
+/// Maximum length of a text representation of a CID
+pub const CID_MAX_LENGTH: usize = 64;
+
+/// Maximum length of a description of a curated hashtag
+pub const CURATED_HASHTAG_DESCRIPTION_MAX_LENGTH: usize = 256;
+
+/// Default number of curated hashtags returned by a view method
+pub const CURATED_HASHTAGS_DEFAULT_LIMIT: u32 = 50;
+
+/// Default number of users returned by a leaderboard view method
+pub const LEADERBOARD_DEFAULT_LIMIT: u32 = 10;
+
+/// Default number of seasons returned by a view method
+pub const SEASONS_DEFAULT_LIMIT: u32 = 50;
+
+/// Default number of receivers returned by a view method of a season ranking
+pub const SEASON_TOP_DEFAULT_LIMIT: u16 = 10;
+
 /// Gas required minimum for `get` and `set` methods of NEAR social db smart contract.
 ///
 /// All remainder gas will be passed additionally for these calls.
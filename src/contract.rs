@@ -1,14 +1,27 @@
+use crate::consts::*;
 use crate::external_db::ext_db;
 use crate::misc::RunningState;
+use crate::registry::{ext_sbtreg, TokenMetadata};
 use crate::settings::{Settings, SettingsView, VSettings};
-use crate::types::{KudosId, StorageKey};
-use crate::utils::build_initial_json_for_socialdb;
-use crate::IncrementalUniqueId;
+use crate::storage::{OnChainStorage, SocialDbStorage, StorageBackend, VStoredKudos};
+use crate::types::{
+    AccountStats, CommentThread, CuratedHashtag, DingQuorum, ExternalDbMigration, Hashtag,
+    HashtagPosition, HashtagStats, HumanityCacheEntry, KudosId, KudosKind, KudosKindInfo,
+    KudosKindView, KudosStatus, LeaderboardPosition, PeriodUpvote, RateLimitedAction, Report,
+    ReportedContent, Season, SeasonId, SeasonPosition, SeasonSbt, SeasonStanding, SeasonTally,
+    SeasonView, SlidingWindowCounter, StorageKey, WrappedCid,
+};
+use crate::utils::{
+    build_initial_json_for_socialdb, build_migration_section_keys_request,
+    display_deposit_requirement_in_near, display_gas_requirement_in_tgas,
+};
+use crate::{CommentId, IncrementalUniqueId, KudosError};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::store::LookupSet;
+use near_sdk::collections::TreeMap;
+use near_sdk::store::{LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
 use near_sdk::{
     env, near_bindgen, require, AccountId, Balance, PanicOnDefault, Promise, PromiseError,
-    ONE_YOCTO,
+    PromiseOrValue, ONE_YOCTO,
 };
 
 #[near_bindgen]
@@ -32,6 +45,83 @@ pub struct Contract {
     /// [`LookupSet`] of unique [`KudosId`] to memorise exchanged kudos for ProofOfKudos SBT.
     /// Used to guarantee upvotes kudos to be exchanged only once.
     pub(crate) exchanged_kudos: LookupSet<KudosId>,
+    /// [`LookupMap`] of a number of upvotes given by an upvoter (first [`AccountId`]) to kudos of a receiver
+    /// (second [`AccountId`]) within a period started at a timestamp in milliseconds.
+    /// Used to detect reciprocal upvotes between the same pair of users.
+    pub(crate) upvotes_by_pair: LookupMap<(AccountId, AccountId, u64), u32>,
+    /// [`LookupMap`] of timestamps in milliseconds when upvoters were issued their i-am-human SBT.
+    /// Used to ignore upvotes of recently verified users when exchanging kudos for ProofOfKudos SBT.
+    pub(crate) iah_sbt_issued_at: LookupMap<AccountId, u64>,
+    /// [`LookupSet`] of receivers [`AccountId`] who opted in to accept dings before they become public
+    pub(crate) dings_acceptance_required: LookupSet<AccountId>,
+    /// [`LookupMap`] of receivers [`AccountId`] to their [`UnorderedSet`] of blocked senders [`AccountId`]
+    pub(crate) blocked_senders: LookupMap<AccountId, UnorderedSet<AccountId>>,
+    /// [`LookupMap`] of [`KudosStatus`] set by receivers for their kudos. Kudos without status are public.
+    pub(crate) kudos_status: LookupMap<KudosId, KudosStatus>,
+    /// [`LookupMap`] of [`Report`] for content [`ReportedContent`] reported by users
+    pub(crate) reports: LookupMap<ReportedContent, Report>,
+    /// [`LookupSet`] of users [`AccountId`] who reported content [`ReportedContent`].
+    /// Used to guarantee content to be reported by the same user only once.
+    pub(crate) reporters: LookupSet<(ReportedContent, AccountId)>,
+    /// [`LookupSet`] of moderators [`AccountId`] allowed to uphold or dismiss reports
+    pub(crate) moderators: LookupSet<AccountId>,
+    /// [`LookupMap`] of [`SlidingWindowCounter`] of actions [`RateLimitedAction`] performed by users [`AccountId`].
+    /// Used to limit number of kudos, dings, upvotes and commentary messages given by the same user within a window.
+    pub(crate) rate_limits: LookupMap<(AccountId, RateLimitedAction), SlidingWindowCounter>,
+    /// [`LookupMap`] of cached humanity verification results [`HumanityCacheEntry`] for users [`AccountId`].
+    /// Used to skip i-am-human-registry calls for recently verified users.
+    pub(crate) humanity_cache: LookupMap<AccountId, HumanityCacheEntry>,
+    /// Current humanity cache epoch, incrementing it invalidates all cached humanity verification results
+    pub(crate) humanity_cache_epoch: u32,
+    /// Whether kudos are stored in the state of this contract instead of NEAR social db,
+    /// should be set by calling `use_on_chain_storage` method.
+    pub(crate) on_chain_storage: bool,
+    /// [`LookupMap`] of versioned kudos [`VStoredKudos`] given to receivers [`AccountId`], used only if
+    /// kudos are stored on-chain
+    pub(crate) stored_kudos: LookupMap<(AccountId, KudosId), VStoredKudos>,
+    /// Progress of kudos data migration [`ExternalDbMigration`] to a new NEAR social db,
+    /// should be started by calling `start_external_db_migration` method.
+    pub(crate) external_db_migration: Option<ExternalDbMigration>,
+    /// [`UnorderedMap`] of usage statistics [`HashtagStats`] of canonical hashtags [`Hashtag`], limited to
+    /// [`HASHTAGS_MAX_TRACKED`] hashtags. Used to read top and trending hashtags.
+    pub(crate) hashtags: UnorderedMap<Hashtag, HashtagStats>,
+    /// [`UnorderedMap`] of official canonical hashtags [`Hashtag`] curated by an owner or moderators
+    pub(crate) curated_hashtags: UnorderedMap<Hashtag, CuratedHashtag>,
+    /// [`UnorderedMap`] of kinds of kudos [`KudosKind`] registered by an owner/admin with their information
+    /// [`KudosKindInfo`]. Built-in kinds use default information unless overridden.
+    pub(crate) kudos_kinds: UnorderedMap<KudosKind, KudosKindInfo>,
+    /// [`LookupMap`] of upvotes [`DingQuorum`] of proposed dings [`KudosId`] which await a quorum
+    /// to become public
+    pub(crate) ding_quorums: LookupMap<KudosId, DingQuorum>,
+    /// [`UnorderedMap`] of activity counters [`AccountStats`] of users [`AccountId`].
+    /// Used to read user's statistics and a leaderboard of top kudos receivers.
+    pub(crate) account_stats: UnorderedMap<AccountId, AccountStats>,
+    /// [`Vector`] of seasons [`Season`] defined by an owner/admin, indexed by [`SeasonId`]
+    pub(crate) seasons: Vector<Season>,
+    /// [`LookupMap`] of seasons [`SeasonId`] to their [`UnorderedMap`] of tallies [`SeasonTally`] of receivers [`AccountId`].
+    /// Used to rank receivers of a season.
+    pub(crate) season_tallies: LookupMap<SeasonId, UnorderedMap<AccountId, SeasonTally>>,
+    /// [`LookupMap`] of ranked snapshots [`SeasonStanding`] frozen when seasons [`SeasonId`] were closed
+    pub(crate) season_snapshots: LookupMap<SeasonId, Vec<SeasonStanding>>,
+    /// [`LookupMap`] of commentary messages [`CommentId`] to their position in a thread [`CommentThread`].
+    /// Used by clients to rebuild threads of commentary messages.
+    pub(crate) comment_threads: LookupMap<CommentId, CommentThread>,
+    /// [`LookupMap`] of upvotes given by upvoters [`AccountId`] to kudos [`KudosId`] to their position
+    /// [`PeriodUpvote`] among upvotes given to the same receiver within a period.
+    /// Used to cap reciprocal upvotes counted toward exchange for ProofOfKudos SBT.
+    pub(crate) upvote_periods: LookupMap<(KudosId, AccountId), PeriodUpvote>,
+    /// [`TreeMap`] of users ranked by [`LeaderboardPosition`], updated together with [`AccountStats`].
+    /// Used to page a leaderboard of top kudos receivers without sorting all users.
+    pub(crate) leaderboard: TreeMap<LeaderboardPosition, ()>,
+    /// [`TreeMap`] of hashtags ranked by [`HashtagPosition`], updated together with [`HashtagStats`].
+    /// Used to read top hashtags without sorting all hashtags.
+    pub(crate) hashtag_ranking: TreeMap<HashtagPosition, ()>,
+    /// [`LookupMap`] of seasons [`SeasonId`] to their [`TreeMap`] of receivers ranked by [`SeasonPosition`],
+    /// updated together with [`SeasonTally`]. Used to freeze a snapshot of a season without sorting all receivers.
+    pub(crate) season_rankings: LookupMap<SeasonId, TreeMap<SeasonPosition, ()>>,
+    /// [`SeasonId`] of the earliest season which has not ended yet, seasons follow each other in order
+    /// of creation. Used to find an active season without iterating all seasons.
+    pub(crate) current_season_id: SeasonId,
 }
 
 #[near_bindgen]
@@ -48,6 +138,34 @@ impl Contract {
             iah_registry,
             settings: Settings::default().into(),
             exchanged_kudos: LookupSet::new(StorageKey::Kudos),
+            upvotes_by_pair: LookupMap::new(StorageKey::UpvotesByPair),
+            iah_sbt_issued_at: LookupMap::new(StorageKey::IahSbtIssuedAt),
+            dings_acceptance_required: LookupSet::new(StorageKey::DingsAcceptanceRequired),
+            blocked_senders: LookupMap::new(StorageKey::BlockedSenders),
+            kudos_status: LookupMap::new(StorageKey::KudosStatus),
+            reports: LookupMap::new(StorageKey::Reports),
+            reporters: LookupSet::new(StorageKey::Reporters),
+            moderators: LookupSet::new(StorageKey::Moderators),
+            rate_limits: LookupMap::new(StorageKey::RateLimits),
+            humanity_cache: LookupMap::new(StorageKey::HumanityCache),
+            humanity_cache_epoch: 0,
+            on_chain_storage: false,
+            stored_kudos: LookupMap::new(StorageKey::StoredKudos),
+            external_db_migration: None,
+            hashtags: UnorderedMap::new(StorageKey::Hashtags),
+            curated_hashtags: UnorderedMap::new(StorageKey::CuratedHashtags),
+            kudos_kinds: UnorderedMap::new(StorageKey::KudosKinds),
+            ding_quorums: LookupMap::new(StorageKey::DingQuorums),
+            account_stats: UnorderedMap::new(StorageKey::AccountStats),
+            seasons: Vector::new(StorageKey::Seasons),
+            season_tallies: LookupMap::new(StorageKey::SeasonTallies),
+            season_snapshots: LookupMap::new(StorageKey::SeasonSnapshots),
+            comment_threads: LookupMap::new(StorageKey::CommentThreads),
+            upvote_periods: LookupMap::new(StorageKey::UpvotePeriods),
+            leaderboard: TreeMap::new(StorageKey::Leaderboard),
+            hashtag_ranking: TreeMap::new(StorageKey::HashtagRanking),
+            season_rankings: LookupMap::new(StorageKey::SeasonRankings),
+            current_season_id: 0,
         }
     }
 
@@ -55,10 +173,19 @@ impl Contract {
     /// to exchange kudos for ProofOfKudos SBT. Restricted to be used only by an owner/admin of this contract.
     #[payable]
     #[handle_result]
-    pub fn update_iah_registry(&mut self, iah_registry: AccountId) -> Result<Promise, String> {
+    pub fn update_iah_registry(&mut self, iah_registry: AccountId) -> Result<Promise, KudosError> {
         self.assert_owner();
 
-        let external_db_id = self.external_db_id()?.clone();
+        let Some(external_db_id) = self.external_db_id.clone() else {
+            // Write permission is not required if kudos are stored on-chain
+            self.storage_backend()?;
+            self.replace_iah_registry(iah_registry);
+
+            let refund =
+                Promise::new(env::predecessor_account_id()).transfer(env::attached_deposit());
+
+            return Ok(refund);
+        };
 
         self.grant_write_permission(external_db_id, iah_registry, env::attached_deposit())
     }
@@ -67,14 +194,15 @@ impl Contract {
     /// Restricted to be used only by an owner/admin of this contract.
     #[payable]
     #[handle_result]
-    pub fn set_external_db(&mut self, external_db_id: AccountId) -> Result<Promise, &'static str> {
+    pub fn set_external_db(&mut self, external_db_id: AccountId) -> Result<Promise, KudosError> {
         self.assert_owner();
-        // Do not allow to change SocialDB address, because there is no data migration possible.
-        // It should be initialized only once.
+        // Do not allow to change SocialDB address, data should be migrated by calling
+        // `start_external_db_migration` method instead. It should be initialized only once.
         require!(
             self.external_db_id.is_none(),
             "External database already set"
         );
+        require!(!self.on_chain_storage, "On-chain storage already used");
 
         let root_id = env::current_account_id();
         let initial_json = build_initial_json_for_socialdb(&root_id)?;
@@ -89,6 +217,192 @@ impl Contract {
             .then(Self::ext(env::current_account_id()).on_ext_db_init(external_db_id, ONE_YOCTO)))
     }
 
+    /// Allows to store kudos in the state of this contract instead of NEAR social db, e.g. for small
+    /// deployments or testing. Restricted to be used only by an owner/admin of this contract.
+    pub fn use_on_chain_storage(&mut self) {
+        self.assert_owner();
+        require!(
+            self.external_db_id.is_none(),
+            "External database already set"
+        );
+
+        self.on_chain_storage = true;
+    }
+
+    /// Starts migration of kudos data from the current NEAR social db smart contract to a new one
+    /// by [`AccountId`]. Attached deposit is used to register this contract at a new NEAR social db.
+    /// All actions which require a storage backend are not available until migration is finished.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    #[handle_result]
+    pub fn start_external_db_migration(
+        &mut self,
+        new_external_db_id: AccountId,
+    ) -> Result<Promise, KudosError> {
+        self.assert_owner();
+
+        let external_db_id = self
+            .external_db_id
+            .as_ref()
+            .ok_or(KudosError::ExternalDbNotSet)?;
+        require!(
+            external_db_id != &new_external_db_id,
+            "New external database should differ from the current one"
+        );
+        if self.external_db_migration.is_some() {
+            return Err(KudosError::ExternalDbMigrationInProgress);
+        }
+
+        let initial_json = build_initial_json_for_socialdb(&env::current_account_id())?;
+
+        Ok(ext_db::ext(new_external_db_id.clone())
+            .with_attached_deposit(env::attached_deposit())
+            .set(initial_json)
+            .then(Self::ext(env::current_account_id()).on_migration_db_init(new_external_db_id)))
+    }
+
+    /// Copies up to `limit` entries, e.g. kudos of receivers or kudos tagged by hashtags, from the current
+    /// NEAR social db to a new one and memorises the last copied entry, so the next call continues after it.
+    /// Storage at a new NEAR social db is paid from the storage balance of this contract, which should be
+    /// deposited in advance by calling `storage_deposit` method of a new NEAR social db.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[handle_result]
+    pub fn migrate_external_db(&mut self, limit: Option<u32>) -> Result<Promise, KudosError> {
+        self.assert_owner();
+
+        let migration = self
+            .external_db_migration
+            .as_ref()
+            .ok_or(KudosError::ExternalDbMigrationNotStarted)?;
+        let Some(section) = migration.section else {
+            env::panic_str("All data is already copied, migration should be finished");
+        };
+        let external_db_id = self
+            .external_db_id
+            .clone()
+            .ok_or(KudosError::ExternalDbNotSet)?;
+
+        let minimum_gas_requirement = MIGRATE_EXTERNAL_DB_RESERVED_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + MIGRATION_KEYS_ACQUIRED_CALLBACK_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + MIGRATION_BATCH_SAVED_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        // Compute minimum required gas and split the remaining gas by four equal parts for
+        // NEAR social db subsequent calls
+        let get_keys_gas = (env::prepaid_gas()
+            - (env::used_gas()
+                + MIGRATE_EXTERNAL_DB_RESERVED_GAS
+                + MIGRATION_KEYS_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+                + MIGRATION_BATCH_SAVED_CALLBACK_GAS))
+            / 4;
+        let get_keys_callback_gas = get_keys_gas * 3
+            + MIGRATION_KEYS_ACQUIRED_CALLBACK_GAS
+            + MIGRATION_ENTRIES_ACQUIRED_CALLBACK_GAS
+            + MIGRATION_BATCH_ACQUIRED_CALLBACK_GAS
+            + MIGRATION_BATCH_SAVED_CALLBACK_GAS;
+
+        Ok(ext_db::ext(external_db_id.clone())
+            .with_static_gas(get_keys_gas)
+            .keys(
+                vec![build_migration_section_keys_request(
+                    &env::current_account_id(),
+                    section,
+                )],
+                None,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(get_keys_callback_gas)
+                    .on_migration_keys_acquired(
+                        external_db_id,
+                        section,
+                        limit.unwrap_or(MIGRATION_BATCH_DEFAULT_LIMIT),
+                    ),
+            ))
+    }
+
+    /// Moves the cursor of external db migration past an entry which can't be copied, e.g. one which
+    /// exceeds gas limit of a single step, so the next call of `migrate_external_db` continues after it.
+    /// The whole top-level `key` is skipped if `kudos_id` is not provided. Skipped entries should be
+    /// copied manually. Restricted to be used only by an owner/admin of this contract.
+    #[handle_result]
+    pub fn skip_external_db_migration_entry(
+        &mut self,
+        key: String,
+        kudos_id: Option<KudosId>,
+    ) -> Result<ExternalDbMigration, KudosError> {
+        self.assert_owner();
+
+        let migration = self
+            .external_db_migration
+            .as_mut()
+            .ok_or(KudosError::ExternalDbMigrationNotStarted)?;
+        require!(migration.section.is_some(), "All data is already copied");
+        require!(!key.is_empty() && !key.contains('/'), "Invalid key");
+
+        migration.cursor = Some(match kudos_id {
+            Some(kudos_id) => format!("{key}/{kudos_id}"),
+            None => key,
+        });
+
+        Ok(migration.clone())
+    }
+
+    /// Aborts external db migration, so the current NEAR social db remains in use and actions which
+    /// require a storage backend become available again. Data already copied to a new NEAR social db
+    /// is not removed. Restricted to be used only by an owner/admin of this contract.
+    #[handle_result]
+    pub fn abort_external_db_migration(&mut self) -> Result<(), KudosError> {
+        self.assert_owner();
+
+        self.external_db_migration
+            .take()
+            .ok_or(KudosError::ExternalDbMigrationNotStarted)?;
+
+        Ok(())
+    }
+
+    /// Finishes migration when all data is copied. Grants write permission to i-am-human-registry
+    /// smart contract at a new NEAR social db and switches external db to it.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    #[handle_result]
+    pub fn finish_external_db_migration(&mut self) -> Result<Promise, KudosError> {
+        self.assert_owner();
+
+        let migration = self
+            .external_db_migration
+            .as_ref()
+            .ok_or(KudosError::ExternalDbMigrationNotStarted)?;
+        require!(migration.section.is_none(), "Not all data is copied yet");
+        let new_external_db_id = migration.new_external_db_id.clone();
+
+        Ok(ext_db::ext(new_external_db_id.clone())
+            .with_attached_deposit(env::attached_deposit())
+            .grant_write_permission(
+                Some(self.iah_registry.clone()),
+                None,
+                vec![format!("{}", env::current_account_id())],
+            )
+            .then(Self::ext(env::current_account_id()).on_external_db_migrated(new_external_db_id)))
+    }
+
+    /// Public view method to read progress of external db migration [`ExternalDbMigration`]
+    pub fn view_external_db_migration(&self) -> Option<ExternalDbMigration> {
+        self.external_db_migration.clone()
+    }
+
     /// Public view method to read current settings [`SettingsView`] of this contract
     pub fn view_settings(&self) -> SettingsView {
         Settings::from(&self.settings).into()
@@ -103,6 +417,225 @@ impl Contract {
         self.settings = self.settings.apply_changes(settings_json);
     }
 
+    /// Adds a moderator [`AccountId`] allowed to uphold or dismiss reports.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    pub fn add_moderator(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        self.moderators.insert(account_id);
+    }
+
+    /// Removes a moderator [`AccountId`].
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    pub fn remove_moderator(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        self.moderators.remove(&account_id);
+    }
+
+    /// Registers a kind of kudos [`KudosKind`] with its information [`KudosKindInfo`] or updates an existing one,
+    /// e.g. to change exchange rules of built-in kinds. Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    #[handle_result]
+    pub fn set_kudos_kind(
+        &mut self,
+        kind: KudosKind,
+        info: KudosKindInfo,
+    ) -> Result<(), KudosError> {
+        self.assert_owner();
+
+        if let Some(icon_cid) = info.icon_cid.as_deref() {
+            Settings::from(&self.settings).validate_cid(&WrappedCid::new(icon_cid)?)?;
+        }
+        self.kudos_kinds.insert(kind, info);
+
+        Ok(())
+    }
+
+    /// Removes a registered kind of kudos [`KudosKind`], built-in kinds are restored to default information.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    #[handle_result]
+    pub fn remove_kudos_kind(&mut self, kind: KudosKind) -> Result<(), KudosError> {
+        self.assert_owner();
+
+        self.kudos_kinds
+            .remove(&kind)
+            .map(|_| ())
+            .ok_or(KudosError::KudosKindNotFound)
+    }
+
+    /// Public view method to read built-in and registered kinds of kudos [`KudosKindView`]
+    pub fn view_kudos_kinds(&self) -> Vec<KudosKindView> {
+        [KudosKind::Kudos, KudosKind::Ding]
+            .into_iter()
+            .chain(
+                self.kudos_kinds
+                    .keys()
+                    .filter(|kind| kind.builtin_info().is_none())
+                    .cloned(),
+            )
+            .filter_map(|kind| {
+                self.kudos_kind_info(&kind)
+                    .map(|info| KudosKindView { kind, info })
+            })
+            .collect()
+    }
+
+    /// Adds a season which starts and ends at provided timestamps in milliseconds, seasons should follow
+    /// each other, so a season should not start before the last added season ends.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    #[handle_result]
+    pub fn add_season(&mut self, start_at: u64, end_at: u64) -> Result<SeasonId, KudosError> {
+        self.assert_owner();
+
+        if start_at >= end_at
+            || self
+                .seasons
+                .len()
+                .checked_sub(1)
+                .and_then(|season_id| self.seasons.get(season_id))
+                .is_some_and(|season| start_at < season.end_at)
+        {
+            return Err(KudosError::InvalidSeasonPeriod);
+        }
+
+        self.seasons.push(Season {
+            start_at,
+            end_at,
+            closed: false,
+        });
+
+        Ok(self.seasons.len() - 1)
+    }
+
+    /// Closes an ended season and freezes a ranked snapshot of its top receivers. Optionally mints
+    /// a special SBT [`SeasonSbt`] to the top receivers by i-am-human-registry, which requires an exact
+    /// deposit to mint every SBT. Season is reopened if SBTs failed to mint.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    #[handle_result]
+    pub fn close_season(
+        &mut self,
+        season_id: SeasonId,
+        sbt: Option<SeasonSbt>,
+    ) -> Result<PromiseOrValue<Vec<SeasonStanding>>, KudosError> {
+        self.assert_owner();
+
+        let now = env::block_timestamp_ms();
+        let season = self.season(season_id)?;
+        if season.closed {
+            return Err(KudosError::SeasonAlreadyClosed);
+        }
+        if now < season.end_at {
+            return Err(KudosError::SeasonNotEnded);
+        }
+
+        let snapshot_size = Settings::from(&self.settings).season_snapshot_size as usize;
+        let snapshot = self.freeze_season(season_id, snapshot_size);
+
+        let Some(sbt) = sbt else {
+            return Ok(PromiseOrValue::Value(snapshot));
+        };
+
+        let token_spec = snapshot
+            .iter()
+            .take(sbt.recipients as usize)
+            .map(|standing| {
+                let metadata = TokenMetadata {
+                    class: sbt.class,
+                    issued_at: Some(now),
+                    expires_at: None,
+                    reference: None,
+                    reference_hash: None,
+                };
+
+                (standing.account_id.clone(), vec![metadata])
+            })
+            .collect::<Vec<_>>();
+
+        let mint_cost = PROOF_OF_KUDOS_SBT_MINT_COST * token_spec.len() as Balance;
+        require!(
+            env::attached_deposit() == mint_cost,
+            &display_deposit_requirement_in_near(mint_cost)
+        );
+
+        if token_spec.is_empty() {
+            return Ok(PromiseOrValue::Value(snapshot));
+        }
+
+        let minimum_gas_requirement = CLOSE_SEASON_RESERVED_GAS
+            + PROOF_OF_KUDOS_SBT_MINT_GAS
+            + SEASON_SBTS_MINTED_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        let sbt_mint_gas = env::prepaid_gas()
+            - (env::used_gas()
+                + CLOSE_SEASON_RESERVED_GAS
+                + SEASON_SBTS_MINTED_CALLBACK_GAS
+                + FAILURE_CALLBACK_GAS);
+
+        Ok(ext_sbtreg::ext(self.iah_registry.clone())
+            .with_attached_deposit(mint_cost)
+            .with_static_gas(sbt_mint_gas)
+            .sbt_mint(token_spec)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(SEASON_SBTS_MINTED_CALLBACK_GAS + FAILURE_CALLBACK_GAS)
+                    .on_season_sbts_minted(
+                        env::predecessor_account_id(),
+                        mint_cost.into(),
+                        season_id,
+                    ),
+            )
+            .into())
+    }
+
+    /// Public view method to read seasons [`SeasonView`] in order of creation.
+    /// Returns up to `limit` seasons starting from `from_index`.
+    pub fn view_seasons(&self, from_index: Option<u32>, limit: Option<u32>) -> Vec<SeasonView> {
+        self.seasons
+            .iter()
+            .enumerate()
+            .skip(from_index.unwrap_or_default() as usize)
+            .take(limit.unwrap_or(SEASONS_DEFAULT_LIMIT) as usize)
+            .map(|(season_id, season)| SeasonView {
+                season_id: season_id as SeasonId,
+                season: season.clone(),
+            })
+            .collect()
+    }
+
+    /// Public view method to read the top receivers [`SeasonStanding`] of a season. Rankings of closed seasons
+    /// are frozen, while rankings of other seasons are computed on the fly.
+    #[handle_result]
+    pub fn view_season_top(
+        &self,
+        season_id: SeasonId,
+        limit: Option<u16>,
+    ) -> Result<Vec<SeasonStanding>, KudosError> {
+        self.season_top(
+            season_id,
+            limit.unwrap_or(SEASON_TOP_DEFAULT_LIMIT) as usize,
+        )
+    }
+
+    /// Invalidates cached humanity verification result for a user by [`AccountId`] or for everyone if not provided.
+    /// Restricted to be used only by an owner/admin of this contract.
+    #[payable]
+    pub fn invalidate_humanity_cache(&mut self, account_id: Option<AccountId>) {
+        self.assert_owner();
+
+        self.invalidate_cached_humanity(account_id.as_ref());
+    }
+
     /// Internal helper method to grant write permission to IAH Registry
     ///
     /// Write permissions required to be able to use `IAHRegistry::is_human_call`, because SocialDB checks for a predecessor_id.
@@ -114,7 +647,7 @@ impl Contract {
         external_db_id: AccountId,
         iah_registry: AccountId,
         deposit: Balance,
-    ) -> Result<Promise, String> {
+    ) -> Result<Promise, KudosError> {
         Ok(ext_db::ext(external_db_id.clone())
             .with_attached_deposit(deposit)
             .grant_write_permission(
@@ -135,8 +668,8 @@ impl Contract {
         external_db_id: AccountId,
         deposit: Balance,
         #[callback_result] callback_result: Result<(), PromiseError>,
-    ) -> Result<Promise, String> {
-        callback_result.map_err(|e| format!("SocialDB::set() call failure: {:?}", e))?;
+    ) -> Result<Promise, KudosError> {
+        callback_result.map_err(|e| KudosError::external_call_failure("SocialDB::set()", e))?;
 
         self.grant_write_permission(external_db_id, self.iah_registry.clone(), deposit)
     }
@@ -148,15 +681,15 @@ impl Contract {
         external_db_id: AccountId,
         iah_registry: AccountId,
         #[callback_result] callback_result: Result<(), PromiseError>,
-    ) -> Result<(), String> {
+    ) -> Result<(), KudosError> {
         callback_result.map_err(|e| {
-            format!(
-                "SocialDB::grant_write_permission(`{iah_registry}`) call failure: {:?}",
-                e
+            KudosError::external_call_failure(
+                format!("SocialDB::grant_write_permission(`{iah_registry}`)"),
+                e,
             )
         })?;
 
-        self.iah_registry = iah_registry;
+        self.replace_iah_registry(iah_registry);
         self.external_db_id = Some(external_db_id);
 
         Ok(())
@@ -182,8 +715,36 @@ impl Contract {
         account_id == &self.owner_id
     }
 
-    /// Return [`AccountId`] of NEAR social db smart contract used by this contract or an error if not set
-    pub(crate) fn external_db_id(&self) -> Result<&AccountId, &'static str> {
-        self.external_db_id.as_ref().ok_or("External db is not set")
+    /// Return [`bool`] which represents if a sender [`AccountId`] is blocked by a receiver [`AccountId`]
+    pub(crate) fn is_sender_blocked(&self, receiver_id: &AccountId, sender_id: &AccountId) -> bool {
+        self.blocked_senders
+            .get(receiver_id)
+            .is_some_and(|senders| senders.contains(sender_id))
+    }
+
+    /// Return [`StorageBackend`] used by this contract to store kudos or an error if not set
+    pub(crate) fn storage_backend(&self) -> Result<StorageBackend, KudosError> {
+        if self.external_db_migration.is_some() {
+            return Err(KudosError::ExternalDbMigrationInProgress);
+        }
+
+        match self.external_db_id.as_ref() {
+            Some(db_id) => Ok(StorageBackend::SocialDb(SocialDbStorage::new(
+                db_id.clone(),
+                Settings::from(&self.settings).social_db_layout,
+            ))),
+            None if self.on_chain_storage => Ok(StorageBackend::OnChain(OnChainStorage)),
+            None => Err(KudosError::ExternalDbNotSet),
+        }
+    }
+
+    /// Replace [`AccountId`] of i-am-human-registry smart contract
+    fn replace_iah_registry(&mut self, iah_registry: AccountId) {
+        // Humanity verification results cached from the previous registry are not valid anymore
+        if self.iah_registry != iah_registry {
+            self.invalidate_cached_humanity(None);
+        }
+
+        self.iah_registry = iah_registry;
     }
 }
@@ -0,0 +1,98 @@
+use crate::settings::Settings;
+use crate::types::{DingQuorum, KudosId, KudosKind, KudosStatus};
+use crate::Contract;
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Return [`KudosStatus`] of a ding which becomes public, it stays pending until accepted by
+    /// a receiver [`AccountId`] if it was required
+    pub(crate) fn published_ding_status(&self, receiver_id: &AccountId) -> KudosStatus {
+        if self.dings_acceptance_required.contains(receiver_id) {
+            KudosStatus::Pending
+        } else {
+            KudosStatus::Accepted
+        }
+    }
+
+    /// Return [`KudosStatus`] of a proposed ding which should be published by an upvote of a user
+    /// [`AccountId`], if this upvote reaches a quorum
+    pub(crate) fn ding_status_on_upvote(
+        &self,
+        settings: &Settings,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        upvoter_id: &AccountId,
+    ) -> Option<KudosStatus> {
+        self.ding_quorums
+            .get(kudos_id)
+            .filter(|quorum| &quorum.receiver_id == receiver_id)
+            .filter(|quorum| {
+                quorum.is_reached_by(upvoter_id, settings.ding_upvotes_quorum as usize)
+            })
+            .map(|_| self.published_ding_status(receiver_id))
+    }
+
+    /// Memorise a saved upvote of a user [`AccountId`] toward a quorum of a proposed ding, or publish
+    /// a ding with provided [`KudosStatus`] if a quorum was reached.
+    ///
+    /// Concurrent upvotes could reach a quorum together while none of them expected to reach it, so
+    /// a quorum is verified again once an upvote is memorised. Returns a receiver [`AccountId`] and
+    /// [`KudosStatus`] of a ding which should be published in such case.
+    pub(crate) fn record_ding_upvote(
+        &mut self,
+        settings: &Settings,
+        kudos_id: &KudosId,
+        upvoter_id: &AccountId,
+        status: Option<KudosStatus>,
+    ) -> Option<(AccountId, KudosStatus)> {
+        if let Some(status) = status {
+            self.memorise_kudos_status(kudos_id, status);
+            return None;
+        }
+
+        let quorum = self.ding_quorums.get_mut(kudos_id)?;
+        quorum.add_upvoter(upvoter_id);
+
+        (quorum.upvoters.len() >= settings.ding_upvotes_quorum as usize)
+            .then(|| quorum.receiver_id.clone())
+            .map(|receiver_id| {
+                let status = self.published_ding_status(&receiver_id);
+
+                (receiver_id, status)
+            })
+    }
+
+    /// Memorise a proposed ding given by a sender to a receiver [`AccountId`], which awaits a quorum of upvotes
+    pub(crate) fn propose_ding(
+        &mut self,
+        kudos_id: KudosId,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+    ) {
+        self.ding_quorums.insert(
+            kudos_id.clone(),
+            DingQuorum {
+                sender_id,
+                receiver_id,
+                upvoters: vec![],
+            },
+        );
+        self.kudos_status.insert(kudos_id, KudosStatus::Proposed);
+    }
+
+    /// Memorise updated [`KudosStatus`] of kudos, a proposed ding no longer awaits a quorum and is counted
+    /// if it was published
+    pub(crate) fn memorise_kudos_status(&mut self, kudos_id: &KudosId, status: KudosStatus) {
+        if let Some(quorum) = self.ding_quorums.remove(kudos_id) {
+            if matches!(status, KudosStatus::Pending | KudosStatus::Accepted) {
+                self.record_kudos_stats(&quorum.sender_id, &quorum.receiver_id, &KudosKind::Ding);
+            }
+        }
+
+        if status.is_public() {
+            self.kudos_status.remove(kudos_id);
+        } else {
+            self.kudos_status.insert(kudos_id.clone(), status);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::types::{HumanityAction, RateLimitedAction};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, Value};
+use near_sdk::{FunctionError, PromiseError};
+use std::fmt::Display;
+
+/// Errors returned by public methods and callbacks of this contract.
+///
+/// Contract panics with a JSON object which contains a stable error `code`, a human readable `message`
+/// and optional error `details`, e.g.
+/// ```json
+/// {
+///   "code": "RATE_LIMIT_EXCEEDED",
+///   "details": {
+///     "action": "ding"
+///   },
+///   "message": "Rate limit exceeded"
+/// }
+/// ```
+///
+/// ATTENTION: Error codes are used by clients, so existing variants should never be renamed or removed
+#[derive(Serialize, Deserialize, Clone, PartialEq, FunctionError)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(
+    crate = "near_sdk::serde",
+    tag = "code",
+    content = "details",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum KudosError {
+    ExternalDbNotSet,
+    MessageMaxLengthExceeded,
+    HashtagsLimitExceeded,
+    HashtagMaxLengthExceeded,
+    InvalidHashtag,
+    CidTooLong,
+    InvalidCid,
+    SenderBlocked,
+    SenderAlreadyBlocked,
+    SenderNotBlocked,
+    NotEligibleToUpvote,
+    NotHuman,
+    HumanityPolicyNotSatisfied { action: HumanityAction },
+    RateLimitExceeded { action: RateLimitedAction },
+    KudosNotFound,
+    KudosNotPublic,
+    KudosNotPending,
+    KudosAlreadyHidden,
+    KudosAlreadyExchanged,
+    KudosSenderNotFound,
+    KudosCreatedAtNotFound,
+    ParentCommentNotFound,
+    DingsNotExchangeable,
+    NoUpvotesFound,
+    MinUpvotesNotReached { required: u32 },
+    InvalidKudosData { details: String },
+    ContentAlreadyModerated,
+    ContentAlreadyReported,
+    ReportedContentNotFound,
+    ReportNotFound,
+    ReportAlreadyResolved,
+    PokSbtExpirationOverflow,
+    EmptyMintResponse,
+    ExternalCallFailure { method: String, error: String },
+    SerializationFailure,
+    ExternalDbMigrationInProgress,
+    ExternalDbMigrationNotStarted,
+    InvalidMessageMarkup,
+    InvalidMention { mention: String },
+    MentionsLimitExceeded,
+    HashtagNotCurated { hashtag: String },
+    CuratedHashtagRequired,
+    CuratedHashtagNotFound,
+    DescriptionMaxLengthExceeded,
+    InvalidKudosKind,
+    KudosKindNotFound,
+    KudosKindNotExchangeable { kind: String },
+    DingJustificationTooShort { min_length: u16 },
+    KudosNotProposed,
+    InvalidSeasonPeriod,
+    SeasonNotFound,
+    SeasonNotEnded,
+    SeasonAlreadyClosed,
+    CommentThreadTooDeep { max_depth: u8 },
+    InvalidReaction,
+    CommentNotFound,
+    AttachmentsLimitExceeded,
+    InvalidAttachmentMimeType,
+    AttachmentCaptionMaxLengthExceeded,
+    CidNotAllowed,
+    KudosAlreadyUpvoted,
+}
+
+impl KudosError {
+    /// Create [`KudosError::ExternalCallFailure`] for a failed external call of a method
+    pub fn external_call_failure(method: impl Into<String>, error: PromiseError) -> Self {
+        Self::ExternalCallFailure {
+            method: method.into(),
+            error: format!("{error:?}"),
+        }
+    }
+
+    /// Return human readable message of this error
+    pub fn message(&self) -> String {
+        match self {
+            Self::ExternalDbNotSet => "External db is not set".to_owned(),
+            Self::MessageMaxLengthExceeded => "Message max length exceeded".to_owned(),
+            Self::HashtagsLimitExceeded => {
+                "Maximum number of hashtags per Kudos exceeded".to_owned()
+            }
+            Self::HashtagMaxLengthExceeded => "Hashtag max text length exceeded".to_owned(),
+            Self::InvalidHashtag => {
+                "Only alphanumeric characters, underscores and gyphens are allowed for hashtag"
+                    .to_owned()
+            }
+            Self::CidTooLong => "Cid is too long".to_owned(),
+            Self::InvalidCid => "Not a valid Cid".to_owned(),
+            Self::SenderBlocked => "Sender is blocked by receiver".to_owned(),
+            Self::SenderAlreadyBlocked => "Sender is already blocked".to_owned(),
+            Self::SenderNotBlocked => "Sender is not blocked".to_owned(),
+            Self::NotEligibleToUpvote => "User is not eligible to upvote this kudos".to_owned(),
+            Self::NotHuman => "IAHRegistry::is_human() returns result: Not a human".to_owned(),
+            Self::HumanityPolicyNotSatisfied { .. } => {
+                "User does not satisfy humanity policy".to_owned()
+            }
+            Self::RateLimitExceeded { .. } => "Rate limit exceeded".to_owned(),
+            Self::KudosNotFound => "Kudos not found".to_owned(),
+            Self::KudosNotPublic => "Kudos is not public".to_owned(),
+            Self::KudosNotPending => "Kudos is not pending".to_owned(),
+            Self::KudosAlreadyHidden => "Kudos is already hidden".to_owned(),
+            Self::KudosAlreadyExchanged => "Kudos is already exchanged".to_owned(),
+            Self::KudosSenderNotFound => "Unable to acquire a Kudos sender account id".to_owned(),
+            Self::KudosCreatedAtNotFound => {
+                "Unable to acquire a Kudos creation timestamp".to_owned()
+            }
+            Self::ParentCommentNotFound => "Unable to verify parent commentary id".to_owned(),
+            Self::DingsNotExchangeable => "Dings can't be exchanged".to_owned(),
+            Self::NoUpvotesFound => "No upvotes found for kudos".to_owned(),
+            Self::MinUpvotesNotReached { required } => {
+                format!("Minimum required number ({required}) of upvotes has not been reached")
+            }
+            Self::InvalidKudosData { details } => details.clone(),
+            Self::ContentAlreadyModerated => "Content is already moderated".to_owned(),
+            Self::ContentAlreadyReported => "Content is already reported by user".to_owned(),
+            Self::ReportedContentNotFound => "Reported content not found".to_owned(),
+            Self::ReportNotFound => "Report not found".to_owned(),
+            Self::ReportAlreadyResolved => "Report is already resolved".to_owned(),
+            Self::PokSbtExpirationOverflow => {
+                "ProofOfKudos SBT expiration date overflow".to_owned()
+            }
+            Self::EmptyMintResponse => {
+                "IAHRegistry::sbt_mint() responses with an empty tokens array".to_owned()
+            }
+            Self::ExternalCallFailure { method, error } => {
+                format!("{method} call failure: {error}")
+            }
+            Self::SerializationFailure => "Internal serialization error".to_owned(),
+            Self::ExternalDbMigrationInProgress => {
+                "External db migration is in progress".to_owned()
+            }
+            Self::ExternalDbMigrationNotStarted => {
+                "External db migration is not started".to_owned()
+            }
+            Self::InvalidMessageMarkup => {
+                "Only emphasis, strikethrough, inline code and http(s) links are allowed in message"
+                    .to_owned()
+            }
+            Self::InvalidMention { mention } => {
+                format!("Mention `@{mention}` is not a valid account")
+            }
+            Self::MentionsLimitExceeded => {
+                "Maximum number of mentions per Kudos exceeded".to_owned()
+            }
+            Self::HashtagNotCurated { hashtag } => {
+                format!("Hashtag `{hashtag}` is not an official curated hashtag")
+            }
+            Self::CuratedHashtagRequired => {
+                "At least one official curated hashtag is required".to_owned()
+            }
+            Self::CuratedHashtagNotFound => "Curated hashtag not found".to_owned(),
+            Self::DescriptionMaxLengthExceeded => "Description max length exceeded".to_owned(),
+            Self::InvalidKudosKind => {
+                "Only a single lowercase letter is allowed for kudos kind".to_owned()
+            }
+            Self::KudosKindNotFound => "Kudos kind is not registered".to_owned(),
+            Self::KudosKindNotExchangeable { kind } => {
+                format!("Kudos of kind `{kind}` can't be exchanged")
+            }
+            Self::DingJustificationTooShort { min_length } => {
+                format!("Ding should be justified by a message of at least {min_length} characters")
+            }
+            Self::KudosNotProposed => "Kudos is not awaiting a quorum".to_owned(),
+            Self::InvalidSeasonPeriod => {
+                "Season should end after its start and not overlap other seasons".to_owned()
+            }
+            Self::SeasonNotFound => "Season not found".to_owned(),
+            Self::SeasonNotEnded => "Season has not ended yet".to_owned(),
+            Self::SeasonAlreadyClosed => "Season is already closed".to_owned(),
+            Self::CommentThreadTooDeep { max_depth } => {
+                format!("Replies are allowed up to the depth of {max_depth} in a thread")
+            }
+            Self::InvalidReaction => "Reaction is not allowed".to_owned(),
+            Self::CommentNotFound => "Commentary message not found".to_owned(),
+            Self::AttachmentsLimitExceeded => "Attachments limit exceeded".to_owned(),
+            Self::InvalidAttachmentMimeType => {
+                "Attachment MIME type should be in `type/subtype` format".to_owned()
+            }
+            Self::AttachmentCaptionMaxLengthExceeded => {
+                "Attachment caption max length exceeded".to_owned()
+            }
+            Self::CidNotAllowed => "Cid codec or multihash is not allowed".to_owned(),
+            Self::KudosAlreadyUpvoted => "Kudos is already upvoted by user".to_owned(),
+        }
+    }
+}
+
+impl Display for KudosError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut json = serde_json::to_value(self).map_err(|_| std::fmt::Error)?;
+        if let Some(obj) = json.as_object_mut() {
+            obj.insert("message".to_owned(), Value::String(self.message()));
+        }
+
+        Display::fmt(&json, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            KudosError::KudosNotPublic.to_string(),
+            r#"{"code":"KUDOS_NOT_PUBLIC","message":"Kudos is not public"}"#
+        );
+        assert_eq!(
+            KudosError::RateLimitExceeded {
+                action: RateLimitedAction::Ding
+            }
+            .to_string(),
+            r#"{"code":"RATE_LIMIT_EXCEEDED","details":{"action":"ding"},"message":"Rate limit exceeded"}"#
+        );
+        assert_eq!(
+            KudosError::external_call_failure("SocialDB::set()", PromiseError::Failed).to_string(),
+            r#"{"code":"EXTERNAL_CALL_FAILURE","details":{"error":"Failed","method":"SocialDB::set()"},"message":"SocialDB::set() call failure: Failed"}"#
+        );
+    }
+}
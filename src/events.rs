@@ -0,0 +1,89 @@
+use crate::types::{KudosId, WrappedCid};
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::{env, AccountId};
+
+/// Standard name of events logged by this contract
+const EVENT_STANDARD: &str = "kudos";
+
+/// Version of events logged by this contract
+const EVENT_VERSION: &str = "1.0.0";
+
+/// Event logged by this contract in NEP-297 format, e.g.
+/// `EVENT_JSON:{"standard":"kudos","version":"1.0.0","event":"cids_accepted","data":[...]}`
+#[derive(Serialize)]
+#[serde(
+    crate = "near_sdk::serde",
+    tag = "event",
+    content = "data",
+    rename_all = "snake_case"
+)]
+pub(crate) enum KudosEvent<'a> {
+    /// CIDs of an icon and attachments of kudos accepted by this contract. Used by ipfs gateways
+    /// to pin content referred by saved kudos.
+    CidsAccepted(Vec<CidsAccepted<'a>>),
+}
+
+/// CIDs [`WrappedCid`] of an icon and attachments of kudos saved to a storage backend
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct CidsAccepted<'a> {
+    pub receiver_id: &'a AccountId,
+    pub kudos_id: &'a KudosId,
+    pub cids: &'a [WrappedCid],
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a KudosEvent<'a>,
+}
+
+impl KudosEvent<'_> {
+    /// Log this event, nothing is logged if it couldn't be serialized
+    pub(crate) fn emit(&self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_VERSION,
+            event: self,
+        };
+
+        if let Ok(json) = serde_json::to_string(&log) {
+            env::log_str(&format!("EVENT_JSON:{json}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CidsAccepted, KudosEvent};
+    use crate::{IncrementalUniqueId, KudosId, WrappedCid};
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_emit_cids_accepted() {
+        testing_env!(VMContextBuilder::new().build());
+
+        let cids = [
+            WrappedCid::new("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi").unwrap(),
+            WrappedCid::new("QmRCWSYgaRyXNvsMuY2iLbMqQqHUwbdWuaWkjG4SqUaLQr").unwrap(),
+        ];
+        KudosEvent::CidsAccepted(vec![CidsAccepted {
+            receiver_id: &accounts(2),
+            kudos_id: &KudosId::from(IncrementalUniqueId::default().next()),
+            cids: &cids,
+        }])
+        .emit();
+
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"kudos","version":"1.0.0","event":"cids_accepted","data":[{"receiver_id":"charlie","kudos_id":"1","cids":["bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi","QmRCWSYgaRyXNvsMuY2iLbMqQqHUwbdWuaWkjG4SqUaLQr"]}]}"#
+            ]
+        );
+    }
+}
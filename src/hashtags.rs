@@ -0,0 +1,108 @@
+use crate::types::{Hashtag, HashtagPosition, HashtagUsage};
+use crate::{Contract, HASHTAGS_MAX_TRACKED, HASHTAG_TRENDING_MAX_WINDOW};
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Return [`bool`] which represents if canonical [`Hashtag`] is curated and not expired at provided timestamp
+    pub(crate) fn is_hashtag_curated(&self, hashtag: &Hashtag, now: u64) -> bool {
+        self.curated_hashtags
+            .get(hashtag)
+            .map(|curated| curated.is_active(now))
+            .unwrap_or(false)
+    }
+
+    /// Return [`bool`] which represents if [`AccountId`] is allowed to manage curated hashtags
+    pub(crate) fn is_hashtag_curator(&self, account_id: &AccountId) -> bool {
+        self.is_owner(account_id) || self.is_moderator(account_id)
+    }
+
+    /// Memorise usage of canonical hashtags [`Hashtag`] by kudos given at provided timestamp.
+    ///
+    /// Storage of usage statistics is paid by a deposit attached by a sender of kudos.
+    pub(crate) fn record_hashtags_usage(&mut self, hashtags: &[Hashtag], now: u64) {
+        for hashtag in hashtags {
+            let position = self
+                .hashtags
+                .get(hashtag)
+                .map(|stats| HashtagPosition::new(hashtag, stats));
+            if position.is_none() && !self.forget_least_used_hashtag(now) {
+                continue;
+            }
+
+            let stats = self.hashtags.entry(hashtag.clone()).or_default();
+            stats.inc(now);
+            let updated_position = HashtagPosition::new(hashtag, stats);
+
+            if let Some(position) = position {
+                self.hashtag_ranking.remove(&position);
+            }
+            self.hashtag_ranking.insert(&updated_position, &());
+        }
+    }
+
+    /// Return [`bool`] which represents if usage of a new hashtag could be counted. Once a maximum number of
+    /// hashtags is counted, the least used hashtag is forgotten unless it was used within a trending window.
+    fn forget_least_used_hashtag(&mut self, now: u64) -> bool {
+        if self.hashtags.len() < HASHTAGS_MAX_TRACKED {
+            return true;
+        }
+
+        let Some(position) = self.hashtag_ranking.max() else {
+            return false;
+        };
+        let is_used_recently = self
+            .hashtags
+            .get(&position.hashtag)
+            .map(|stats| stats.count_within(HASHTAG_TRENDING_MAX_WINDOW, now) > 0)
+            .unwrap_or(false);
+        if is_used_recently {
+            return false;
+        }
+
+        self.hashtags.remove(&position.hashtag);
+        self.hashtag_ranking.remove(&position);
+
+        true
+    }
+
+    /// Return the most used hashtags [`HashtagUsage`] of all time
+    pub(crate) fn top_hashtags(&self, limit: usize) -> Vec<HashtagUsage> {
+        self.hashtag_ranking
+            .iter()
+            .take(limit)
+            .map(|(position, _)| HashtagUsage {
+                count: position.count(),
+                hashtag: position.hashtag,
+            })
+            .collect()
+    }
+
+    /// Return the most used hashtags [`HashtagUsage`] within a window in milliseconds before provided
+    /// timestamp, hashtags without kudos within a window are skipped. All counted hashtags are ranked,
+    /// which is bounded by [`HASHTAGS_MAX_TRACKED`].
+    pub(crate) fn trending_hashtags(
+        &self,
+        window: u64,
+        now: u64,
+        limit: usize,
+    ) -> Vec<HashtagUsage> {
+        let mut trending_hashtags = self
+            .hashtags
+            .iter()
+            .map(|(hashtag, stats)| HashtagUsage {
+                hashtag: hashtag.clone(),
+                count: stats.count_within(window, now),
+            })
+            .filter(|usage| usage.count > 0)
+            .collect::<Vec<_>>();
+
+        trending_hashtags.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.hashtag.cmp(&b.hashtag))
+        });
+        trending_hashtags.truncate(limit);
+
+        trending_hashtags
+    }
+}
@@ -0,0 +1,164 @@
+use crate::registry::{ext_sbtreg, OwnedToken, TokenId, IS_HUMAN_GAS, SBT_TOKENS_BY_OWNER_GAS};
+use crate::settings::Settings;
+use crate::types::{HumanityAction, HumanityCacheEntry};
+use crate::{Contract, KudosError};
+use near_sdk::{env, AccountId, Promise, PromiseError};
+
+impl Contract {
+    /// Return [`bool`] which represents if user [`AccountId`] was recently verified as a human,
+    /// the verification result is still valid and satisfies humanity policy for provided action [`HumanityAction`]
+    pub(crate) fn is_human_cached(
+        &self,
+        account_id: &AccountId,
+        action: HumanityAction,
+        now: u64,
+    ) -> bool {
+        self.humanity_cache.get(account_id).is_some_and(|entry| {
+            entry.epoch == self.humanity_cache_epoch
+                && entry.verified_until > now
+                && Settings::from(&self.settings)
+                    .humanity_policy
+                    .requirement(action)
+                    .is_satisfied_by(
+                        entry
+                            .tokens
+                            .iter()
+                            .map(|(issuer_id, number)| (issuer_id, *number as usize)),
+                    )
+        })
+    }
+
+    /// Request i-am-human registry to verify user [`AccountId`] as a human and to return user's SBTs
+    /// [`OwnedToken`] by a joint call, so verification result could be cached until SBTs expire.
+    /// Requires [`VERIFY_HUMANITY_GAS`](crate::registry::VERIFY_HUMANITY_GAS).
+    pub(crate) fn is_human_with_sbts(&self, account_id: AccountId) -> Promise {
+        ext_sbtreg::ext(self.iah_registry.clone())
+            .with_static_gas(IS_HUMAN_GAS)
+            .is_human(account_id.clone())
+            .and(
+                ext_sbtreg::ext(self.iah_registry.clone())
+                    .with_static_gas(SBT_TOKENS_BY_OWNER_GAS)
+                    .sbt_tokens_by_owner(account_id, None, None, None, None),
+            )
+    }
+
+    /// Verify i-am-human registry response for user [`AccountId`] against humanity policy for provided
+    /// action [`HumanityAction`] and cache verification result. Returns user's i-am-human SBTs [`TokenId`]
+    /// issued by accepted issuers.
+    ///
+    /// Verification result is cached only if user's SBTs [`OwnedToken`] were acquired, so it doesn't
+    /// outlive the earliest expiration time of SBTs returned by i-am-human registry.
+    pub(crate) fn verify_humanity(
+        &mut self,
+        account_id: &AccountId,
+        action: HumanityAction,
+        is_human_result: Result<Vec<(AccountId, Vec<TokenId>)>, PromiseError>,
+        iah_sbts: Option<Vec<(AccountId, Vec<OwnedToken>)>>,
+    ) -> Result<Vec<(AccountId, Vec<TokenId>)>, KudosError> {
+        let tokens = is_human_result
+            .map_err(|e| KudosError::external_call_failure("IAHRegistry::is_human()", e))?;
+
+        if tokens.is_empty() {
+            return Err(KudosError::NotHuman);
+        }
+
+        let settings = Settings::from(&self.settings);
+        if let Some(iah_sbts) = iah_sbts {
+            self.cache_humanity(
+                &settings,
+                account_id,
+                env::block_timestamp_ms(),
+                &tokens,
+                extract_earliest_expires_at(&tokens, &iah_sbts),
+            );
+        }
+
+        let requirement = settings.humanity_policy.requirement(action);
+        if !requirement.is_satisfied_by(
+            tokens
+                .iter()
+                .map(|(issuer_id, token_ids)| (issuer_id, token_ids.len())),
+        ) {
+            return Err(KudosError::HumanityPolicyNotSatisfied { action });
+        }
+
+        Ok(tokens
+            .into_iter()
+            .filter(|(issuer_id, _)| requirement.accepts(issuer_id))
+            .collect())
+    }
+
+    /// Memorise that user [`AccountId`] was verified as a human with provided i-am-human SBTs [`TokenId`].
+    /// Verification result is valid for a configured time, but not longer than the earliest SBT
+    /// expiration time if provided.
+    pub(crate) fn cache_humanity(
+        &mut self,
+        settings: &Settings,
+        account_id: &AccountId,
+        now: u64,
+        tokens: &[(AccountId, Vec<TokenId>)],
+        iah_sbt_expires_at: Option<u64>,
+    ) {
+        let verified_until = now
+            .saturating_add(settings.humanity_cache_ttl)
+            .min(iah_sbt_expires_at.unwrap_or(u64::MAX));
+        if verified_until <= now {
+            return;
+        }
+
+        self.humanity_cache.insert(
+            account_id.clone(),
+            HumanityCacheEntry {
+                epoch: self.humanity_cache_epoch,
+                verified_until,
+                tokens: tokens
+                    .iter()
+                    .map(|(issuer_id, token_ids)| (issuer_id.clone(), token_ids.len() as u32))
+                    .collect(),
+            },
+        );
+    }
+
+    /// Remove cached humanity verification result of user [`AccountId`] if it's not valid anymore, because
+    /// it has expired or was invalidated for everyone. Returns [`bool`] which represents if it was removed.
+    pub(crate) fn prune_cached_humanity(&mut self, account_id: &AccountId, now: u64) -> bool {
+        let is_stale = self.humanity_cache.get(account_id).is_some_and(|entry| {
+            entry.epoch != self.humanity_cache_epoch || entry.verified_until <= now
+        });
+
+        if is_stale {
+            self.humanity_cache.remove(account_id);
+        }
+
+        is_stale
+    }
+
+    /// Invalidate cached humanity verification result for a single user [`AccountId`] or for everyone
+    pub(crate) fn invalidate_cached_humanity(&mut self, account_id: Option<&AccountId>) {
+        match account_id {
+            Some(account_id) => {
+                self.humanity_cache.remove(account_id);
+            }
+            None => self.humanity_cache_epoch = self.humanity_cache_epoch.wrapping_add(1),
+        }
+    }
+}
+
+/// Return the earliest timestamp in milliseconds when one of user's i-am-human SBTs [`TokenId`], which were
+/// used to verify user, expires at. SBTs [`OwnedToken`] of the same user are looked up by issuer and token id.
+pub(crate) fn extract_earliest_expires_at(
+    tokens: &[(AccountId, Vec<TokenId>)],
+    iah_sbts: &[(AccountId, Vec<OwnedToken>)],
+) -> Option<u64> {
+    iah_sbts
+        .iter()
+        .filter_map(|(issuer_id, owned_tokens)| {
+            let (_, token_ids) = tokens.iter().find(|(id, _)| id == issuer_id)?;
+            owned_tokens
+                .iter()
+                .filter(|owned_token| token_ids.contains(&owned_token.token))
+                .filter_map(|owned_token| owned_token.metadata.expires_at)
+                .min()
+        })
+        .min()
+}
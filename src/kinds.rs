@@ -0,0 +1,13 @@
+use crate::types::{KudosKind, KudosKindInfo};
+use crate::Contract;
+
+impl Contract {
+    /// Return information [`KudosKindInfo`] of a kind of kudos [`KudosKind`] registered by an owner/admin,
+    /// or default information of a built-in kind
+    pub(crate) fn kudos_kind_info(&self, kind: &KudosKind) -> Option<KudosKindInfo> {
+        self.kudos_kinds
+            .get(kind)
+            .cloned()
+            .or_else(|| kind.builtin_info())
+    }
+}
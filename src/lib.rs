@@ -1,11 +1,24 @@
 mod callbacks;
+mod collusion;
 mod consts;
 mod contract;
+mod dings;
+mod error;
+mod events;
 mod external_db;
+mod hashtags;
+mod humanity;
+mod kinds;
+mod migrate;
 mod misc;
+mod moderation;
 mod public;
+mod rate_limit;
 pub mod registry;
+mod seasons;
 mod settings;
+mod stats;
+mod storage;
 #[cfg(test)]
 pub mod tests;
 mod types;
@@ -13,4 +26,5 @@ pub mod utils;
 
 pub use consts::*;
 pub use contract::*;
+pub use error::*;
 pub use types::*;
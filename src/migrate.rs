@@ -0,0 +1,84 @@
+use crate::misc::RunningState;
+use crate::settings::{Settings, VSettings};
+use crate::types::{KudosId, StorageKey};
+use crate::{Contract, ContractExt, IncrementalUniqueId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::TreeMap;
+use near_sdk::store::{LookupMap, LookupSet, UnorderedMap, Vector};
+use near_sdk::{env, near_bindgen, AccountId};
+
+/// State of the initially deployed version of this contract.
+///
+/// Keep it unchanged, new state should be added to [`Contract`] and initialized by [`Contract::migrate`]
+/// together with other state of the same feature.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV0 {
+    pub(crate) owner_id: AccountId,
+    pub(crate) running_state: RunningState,
+    pub(crate) last_incremental_id: IncrementalUniqueId,
+    pub(crate) external_db_id: Option<AccountId>,
+    pub(crate) iah_registry: AccountId,
+    pub(crate) settings: VSettings,
+    pub(crate) exchanged_kudos: LookupSet<KudosId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Migrates state of the initially deployed version [`ContractV0`] of this contract to the current one.
+    /// Settings are converted to the current version with default values of new settings, and new collections
+    /// are initialized empty. Restricted to be called only by this contract itself, e.g. within a deployment.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old_state: ContractV0 =
+            env::state_read().unwrap_or_else(|| env::panic_str("Contract state not found"));
+
+        Self {
+            owner_id: old_state.owner_id,
+            running_state: old_state.running_state,
+            last_incremental_id: old_state.last_incremental_id,
+            external_db_id: old_state.external_db_id,
+            iah_registry: old_state.iah_registry,
+            settings: Settings::from(&old_state.settings).into(),
+            exchanged_kudos: old_state.exchanged_kudos,
+            // Anti-collusion rules of upvotes
+            upvotes_by_pair: LookupMap::new(StorageKey::UpvotesByPair),
+            iah_sbt_issued_at: LookupMap::new(StorageKey::IahSbtIssuedAt),
+            upvote_periods: LookupMap::new(StorageKey::UpvotePeriods),
+            // Receiver consent and blocklist
+            dings_acceptance_required: LookupSet::new(StorageKey::DingsAcceptanceRequired),
+            blocked_senders: LookupMap::new(StorageKey::BlockedSenders),
+            kudos_status: LookupMap::new(StorageKey::KudosStatus),
+            // Content reporting and moderation
+            reports: LookupMap::new(StorageKey::Reports),
+            reporters: LookupSet::new(StorageKey::Reporters),
+            moderators: LookupSet::new(StorageKey::Moderators),
+            // Rate limits and humanity cache
+            rate_limits: LookupMap::new(StorageKey::RateLimits),
+            humanity_cache: LookupMap::new(StorageKey::HumanityCache),
+            humanity_cache_epoch: 0,
+            // Storage backends and external db migration
+            on_chain_storage: false,
+            stored_kudos: LookupMap::new(StorageKey::StoredKudos),
+            external_db_migration: None,
+            // Hashtags
+            hashtags: UnorderedMap::new(StorageKey::Hashtags),
+            curated_hashtags: UnorderedMap::new(StorageKey::CuratedHashtags),
+            hashtag_ranking: TreeMap::new(StorageKey::HashtagRanking),
+            // Kudos kinds and dings
+            kudos_kinds: UnorderedMap::new(StorageKey::KudosKinds),
+            ding_quorums: LookupMap::new(StorageKey::DingQuorums),
+            // Account stats and leaderboard
+            account_stats: UnorderedMap::new(StorageKey::AccountStats),
+            leaderboard: TreeMap::new(StorageKey::Leaderboard),
+            // Seasons
+            seasons: Vector::new(StorageKey::Seasons),
+            season_tallies: LookupMap::new(StorageKey::SeasonTallies),
+            season_snapshots: LookupMap::new(StorageKey::SeasonSnapshots),
+            season_rankings: LookupMap::new(StorageKey::SeasonRankings),
+            current_season_id: 0,
+            // Comment threads
+            comment_threads: LookupMap::new(StorageKey::CommentThreads),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::types::{Report, ReportStatus, ReportedContent};
+use crate::{Contract, KudosError};
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Verify that content [`ReportedContent`] is allowed to be reported by a reporter [`AccountId`]
+    pub(crate) fn verify_report_allowed(
+        &self,
+        content: &ReportedContent,
+        reporter_id: &AccountId,
+    ) -> Result<(), KudosError> {
+        if self
+            .reports
+            .get(content)
+            .is_some_and(|report| report.status.is_resolved())
+        {
+            return Err(KudosError::ContentAlreadyModerated);
+        }
+
+        if self
+            .reporters
+            .contains(&(content.clone(), reporter_id.clone()))
+        {
+            return Err(KudosError::ContentAlreadyReported);
+        }
+
+        Ok(())
+    }
+
+    /// Memorise a report of content [`ReportedContent`] by a reporter [`AccountId`] and
+    /// update its [`ReportStatus`] if provided
+    pub(crate) fn record_report(
+        &mut self,
+        content: ReportedContent,
+        reporter_id: AccountId,
+        status: Option<ReportStatus>,
+    ) -> Report {
+        let is_new_reporter = self.reporters.insert((content.clone(), reporter_id));
+        let report = self.reports.entry(content).or_default();
+
+        if is_new_reporter {
+            report.count = report.count.saturating_add(1);
+        }
+
+        if let Some(status) = status {
+            report.status = status;
+        }
+
+        report.clone()
+    }
+
+    /// Return [`bool`] which represents if reported content [`ReportedContent`] is hidden by reports or
+    /// by moderator
+    pub(crate) fn is_content_hidden(&self, content: &ReportedContent) -> bool {
+        self.reports
+            .get(content)
+            .is_some_and(|report| report.status.is_hidden())
+    }
+
+    /// Return [`bool`] which represents if [`AccountId`] is a moderator of this contract or not
+    pub(crate) fn is_moderator(&self, account_id: &AccountId) -> bool {
+        self.moderators.contains(account_id)
+    }
+}
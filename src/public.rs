@@ -1,10 +1,17 @@
-use crate::registry::{ext_sbtreg, IS_HUMAN_GAS};
+use crate::registry::{SBTS_GAS, VERIFY_HUMANITY_GAS};
 use crate::settings::Settings;
-use crate::types::{Commentary, KudosId, KudosKind, WrappedCid};
+use crate::storage::{KudosField, KudosStorage};
+use crate::types::{
+    AccountStats, AccountStatsView, CommentThreadView, Commentary, CuratedHashtag,
+    CuratedHashtagView, Hashtag, HashtagUsage, HumanityAction, KudosAttachment, KudosId, KudosKind,
+    KudosMedia, KudosStatus, RateLimitedAction, Report, ReportStatus, ReportedContent, StorageKey,
+    WrappedCid,
+};
+use crate::utils::*;
 use crate::{consts::*, CommentId, EncodedCommentary};
-use crate::{utils::*, GIVE_KUDOS_COST};
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, KudosError};
 use near_sdk::serde_json::Value;
+use near_sdk::store::UnorderedSet;
 use near_sdk::{env, near_bindgen, require, AccountId, Promise};
 
 #[near_bindgen]
@@ -14,11 +21,11 @@ impl Contract {
     /// only if it has sufficient amount of upvotes. Calls `sbt_mint` of i-am-human-registry contract.
     #[payable]
     #[handle_result]
-    pub fn exchange_kudos_for_sbt(&mut self, kudos_id: KudosId) -> Result<Promise, &'static str> {
+    pub fn exchange_kudos_for_sbt(&mut self, kudos_id: KudosId) -> Result<Promise, KudosError> {
         self.assert_contract_running();
 
         let minimum_gas_requirement = EXCHANGE_KUDOS_FOR_SBT_RESERVED_GAS
-            + IS_HUMAN_GAS
+            + VERIFY_HUMANITY_GAS
             + ACQUIRE_NUMBER_OF_UPVOTES_RESERVED_GAS
             + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
             + KUDOS_UPVOTES_ACQUIRED_CALLBACK_GAS
@@ -37,28 +44,54 @@ impl Contract {
         );
 
         if self.exchanged_kudos.contains(&kudos_id) {
-            return Err("Kudos is already exchanged");
+            return Err(KudosError::KudosAlreadyExchanged);
+        }
+
+        // Kudos which are not accepted by receiver or hidden by moderation can't be exchanged
+        let is_hidden = self.is_content_hidden(&ReportedContent {
+            receiver_id: env::signer_account_id(),
+            kudos_id: kudos_id.clone(),
+            comment_id: None,
+        });
+        if is_hidden
+            || self
+                .kudos_status
+                .get(&kudos_id)
+                .is_some_and(|status| !status.is_public())
+        {
+            return Err(KudosError::KudosNotPublic);
         }
 
         let predecessor_account_id = env::predecessor_account_id();
-        let external_db_id = self.external_db_id()?.clone();
+        let storage = self.storage_backend()?;
+
+        // Skip humanity verification if caller was recently verified
+        if self.is_human_cached(
+            &env::signer_account_id(),
+            HumanityAction::Exchange,
+            env::block_timestamp_ms(),
+        ) {
+            return Ok(self.acquire_number_of_upvotes_verified(
+                predecessor_account_id,
+                attached_deposit,
+                storage,
+                kudos_id,
+            ));
+        }
 
         let gas_remaining = env::prepaid_gas()
-            - (env::used_gas() + IS_HUMAN_GAS + EXCHANGE_KUDOS_FOR_SBT_RESERVED_GAS);
+            - (env::used_gas() + VERIFY_HUMANITY_GAS + EXCHANGE_KUDOS_FOR_SBT_RESERVED_GAS);
 
-        Ok(ext_sbtreg::ext(self.iah_registry.clone())
-            .with_static_gas(IS_HUMAN_GAS)
-            .is_human(env::signer_account_id())
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(gas_remaining)
-                    .acquire_number_of_upvotes(
-                        predecessor_account_id,
-                        attached_deposit.into(),
-                        external_db_id,
-                        kudos_id,
-                    ),
-            ))
+        Ok(self.is_human_with_sbts(env::signer_account_id()).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(gas_remaining)
+                .acquire_number_of_upvotes(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    kudos_id,
+                ),
+        ))
     }
 
     /// Allows caller to leave a commentary message [`String`] to a kudos associated with [`KudosId`]
@@ -72,14 +105,14 @@ impl Contract {
         kudos_id: KudosId,
         parent_comment_id: Option<CommentId>,
         message: String,
-    ) -> Result<Promise, String> {
+    ) -> Result<Promise, KudosError> {
         self.assert_contract_running();
 
         let predecessor_account_id = env::predecessor_account_id();
         let sender_id = env::signer_account_id();
 
         let minimum_gas_requirement = LEAVE_COMMENT_RESERVED_GAS
-            + IS_HUMAN_GAS
+            + VERIFY_HUMANITY_GAS
             + ACQUIRE_KUDOS_INFO_RESERVED_GAS
             + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
             + KUDOS_INFO_ACQUIRED_CALLBACK_GAS
@@ -91,42 +124,169 @@ impl Contract {
             display_gas_requirement_in_tgas(minimum_gas_requirement)
         );
 
+        let settings = Settings::from(&self.settings);
+        let leave_comment_cost = settings.commentary_encoding.leave_comment_cost();
         let attached_deposit = env::attached_deposit();
         require!(
-            attached_deposit == LEAVE_COMMENT_COST,
-            &display_deposit_requirement_in_near(LEAVE_COMMENT_COST)
+            attached_deposit == leave_comment_cost,
+            &display_deposit_requirement_in_near(leave_comment_cost)
         );
 
-        if message.len() > Settings::from(&self.settings).commentary_message_max_length  as usize {
-            return Err("Message max length exceeded".to_string());
+        if self.is_sender_blocked(&receiver_id, &sender_id) {
+            return Err(KudosError::SenderBlocked);
         }
-        let external_db_id = self.external_db_id()?.clone();
-        let comment = EncodedCommentary::try_from(&Commentary {
-            sender_id: &sender_id,
-            message: &Value::String(message),
-            timestamp: env::block_timestamp_ms().into(),
-            parent_comment_id: parent_comment_id.as_ref(),
-        })?;
 
-        let gas_remaining =
-            env::prepaid_gas() - (env::used_gas() + IS_HUMAN_GAS + LEAVE_COMMENT_RESERVED_GAS);
+        if message.len() > settings.commentary_message_max_length as usize {
+            return Err(KudosError::MessageMaxLengthExceeded);
+        }
 
-        Ok(ext_sbtreg::ext(self.iah_registry.clone())
-            .with_static_gas(IS_HUMAN_GAS)
-            .is_human(env::signer_account_id())
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(gas_remaining)
-                    .acquire_kudos_info(
-                        predecessor_account_id,
-                        attached_deposit.into(),
-                        external_db_id,
-                        receiver_id,
-                        kudos_id,
-                        parent_comment_id,
-                        comment,
-                    ),
-            ))
+        self.verify_rate_limit(
+            &settings,
+            &sender_id,
+            RateLimitedAction::Comment,
+            env::block_timestamp_ms(),
+        )?;
+
+        let storage = self.storage_backend()?;
+        let comment = EncodedCommentary::encode(
+            &Commentary {
+                sender_id: &sender_id,
+                message: &Value::String(message),
+                timestamp: env::block_timestamp_ms().into(),
+                parent_comment_id: parent_comment_id.as_ref(),
+            },
+            settings.commentary_encoding,
+        )?;
+
+        // Skip humanity verification if caller was recently verified
+        if self.is_human_cached(
+            &sender_id,
+            HumanityAction::Comment,
+            env::block_timestamp_ms(),
+        ) {
+            return self.acquire_kudos_info_verified(
+                predecessor_account_id,
+                attached_deposit,
+                storage,
+                receiver_id,
+                kudos_id,
+                parent_comment_id,
+                comment,
+            );
+        }
+
+        let gas_remaining = env::prepaid_gas()
+            - (env::used_gas() + VERIFY_HUMANITY_GAS + LEAVE_COMMENT_RESERVED_GAS);
+
+        Ok(self.is_human_with_sbts(env::signer_account_id()).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(gas_remaining)
+                .acquire_kudos_info(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                    parent_comment_id,
+                    comment,
+                ),
+        ))
+    }
+
+    /// Allows caller to react to a commentary message associated with [`CommentId`] of kudos associated
+    /// with [`KudosId`] for a user by [`AccountId`]. Reaction should belong to a set of reactions configured
+    /// in settings, a previous reaction of caller to the same commentary message is replaced.
+    /// Caller should have a valid i-am-human SBT, reactions are rate limited together with commentary messages.
+    #[payable]
+    #[handle_result]
+    pub fn react_to_comment(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+        reaction: String,
+    ) -> Result<Promise, KudosError> {
+        self.assert_contract_running();
+
+        let minimum_gas_requirement = REACT_TO_COMMENT_RESERVED_GAS
+            + VERIFY_HUMANITY_GAS
+            + ACQUIRE_REACTED_COMMENT_RESERVED_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + REACTED_COMMENT_ACQUIRED_CALLBACK_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + COMMENT_REACTION_SAVED_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        let attached_deposit = env::attached_deposit();
+        require!(
+            attached_deposit == REACT_TO_COMMENT_COST,
+            &display_deposit_requirement_in_near(REACT_TO_COMMENT_COST)
+        );
+
+        let predecessor_account_id = env::predecessor_account_id();
+        let sender_id = env::signer_account_id();
+
+        if self.is_sender_blocked(&receiver_id, &sender_id) {
+            return Err(KudosError::SenderBlocked);
+        }
+
+        if self
+            .kudos_status
+            .get(&kudos_id)
+            .is_some_and(|status| !status.is_upvotable())
+        {
+            return Err(KudosError::KudosNotPublic);
+        }
+
+        let settings = Settings::from(&self.settings);
+        settings.validate_reaction(&reaction)?;
+
+        self.verify_rate_limit(
+            &settings,
+            &sender_id,
+            RateLimitedAction::Comment,
+            env::block_timestamp_ms(),
+        )?;
+
+        let storage = self.storage_backend()?;
+
+        // Skip humanity verification if caller was recently verified
+        if self.is_human_cached(
+            &sender_id,
+            HumanityAction::Comment,
+            env::block_timestamp_ms(),
+        ) {
+            return Ok(self.acquire_reacted_comment_verified(
+                predecessor_account_id,
+                attached_deposit,
+                storage,
+                receiver_id,
+                kudos_id,
+                comment_id,
+                reaction,
+            ));
+        }
+
+        let gas_remaining = env::prepaid_gas()
+            - (env::used_gas() + VERIFY_HUMANITY_GAS + REACT_TO_COMMENT_RESERVED_GAS);
+
+        Ok(self.is_human_with_sbts(sender_id).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(gas_remaining)
+                .acquire_reacted_comment(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                    comment_id,
+                    reaction,
+                ),
+        ))
     }
 
     /// Allows caller to upvote kudos associated with [`KudosId`] for a user by [`AccountId`].
@@ -137,7 +297,7 @@ impl Contract {
         &mut self,
         receiver_id: AccountId,
         kudos_id: KudosId,
-    ) -> Result<Promise, &'static str> {
+    ) -> Result<Promise, KudosError> {
         self.assert_contract_running();
 
         let predecessor_account_id = env::predecessor_account_id();
@@ -148,8 +308,9 @@ impl Contract {
         );
 
         let minimum_gas_requirement = UPVOTE_KUDOS_RESERVED_GAS
-            + IS_HUMAN_GAS
+            + VERIFY_HUMANITY_GAS
             + ACQUIRE_KUDOS_SENDER_RESERVED_GAS
+            + SBTS_GAS
             + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
             + KUDOS_SENDER_ACQUIRED_CALLBACK_GAS
             + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
@@ -166,29 +327,70 @@ impl Contract {
             &display_deposit_requirement_in_near(UPVOTE_KUDOS_COST)
         );
 
-        let external_db_id = self.external_db_id()?.clone();
-        let gas_remaining =
-            env::prepaid_gas() - (env::used_gas() + IS_HUMAN_GAS + UPVOTE_KUDOS_RESERVED_GAS);
+        if self.is_sender_blocked(&receiver_id, &sender_id) {
+            return Err(KudosError::SenderBlocked);
+        }
 
-        Ok(ext_sbtreg::ext(self.iah_registry.clone())
-            .with_static_gas(IS_HUMAN_GAS)
-            .is_human(env::signer_account_id())
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(gas_remaining)
-                    .acquire_kudos_sender(
-                        predecessor_account_id,
-                        attached_deposit.into(),
-                        external_db_id,
-                        receiver_id,
-                        kudos_id,
-                    ),
-            ))
+        if self
+            .kudos_status
+            .get(&kudos_id)
+            .is_some_and(|status| !status.is_upvotable())
+        {
+            return Err(KudosError::KudosNotPublic);
+        }
+
+        if self.is_kudos_upvoted_by(&kudos_id, &sender_id) {
+            return Err(KudosError::KudosAlreadyUpvoted);
+        }
+
+        self.verify_rate_limit(
+            &Settings::from(&self.settings),
+            &sender_id,
+            RateLimitedAction::Upvote,
+            env::block_timestamp_ms(),
+        )?;
+
+        let storage = self.storage_backend()?;
+
+        // Skip humanity verification if caller was recently verified and the time when caller's
+        // i-am-human SBT was issued is already known
+        if self.is_human_cached(
+            &sender_id,
+            HumanityAction::Upvote,
+            env::block_timestamp_ms(),
+        ) && self.iah_sbt_issued_at.contains_key(&sender_id)
+        {
+            return self.acquire_kudos_sender_verified(
+                predecessor_account_id,
+                attached_deposit,
+                storage,
+                receiver_id,
+                kudos_id,
+                None,
+            );
+        }
+
+        let gas_remaining = env::prepaid_gas()
+            - (env::used_gas() + VERIFY_HUMANITY_GAS + UPVOTE_KUDOS_RESERVED_GAS);
+
+        Ok(self.is_human_with_sbts(env::signer_account_id()).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(gas_remaining)
+                .acquire_kudos_sender(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    receiver_id,
+                    kudos_id,
+                ),
+        ))
     }
 
     /// Allows caller to give kudos for a user by [`AccountId`].
     /// Caller should have a valid i-am-human SBT and can't give kudos to himself.
     /// Hashtags is an array of [`String`] for which only alphanumeric characters, underscores and gyphens are allowed to use.
+    /// Message may use a markdown subset and mention users, e.g. `@alex.near`, see [`KudosMessage`](crate::KudosMessage).
+    /// Attachments [`KudosAttachment`] require an additional deposit for every attachment.
     #[payable]
     #[handle_result]
     pub fn give_kudos(
@@ -198,7 +400,8 @@ impl Contract {
         message: String,
         icon_cid: Option<WrappedCid>,
         hashtags: Option<Vec<String>>,
-    ) -> Result<Promise, &'static str> {
+        attachments: Option<Vec<KudosAttachment>>,
+    ) -> Result<Promise, KudosError> {
         self.assert_contract_running();
 
         let predecessor_account_id = env::predecessor_account_id();
@@ -209,7 +412,7 @@ impl Contract {
         );
 
         let minimum_gas_requirement = GIVE_KUDOS_RESERVED_GAS
-            + IS_HUMAN_GAS
+            + VERIFY_HUMANITY_GAS
             + SAVE_KUDOS_RESERVED_GAS
             + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
             + KUDOS_SAVED_CALLBACK_GAS
@@ -219,39 +422,719 @@ impl Contract {
             display_gas_requirement_in_tgas(minimum_gas_requirement)
         );
 
+        let attachments = attachments.unwrap_or_default();
+        let give_kudos_cost = give_kudos_cost(
+            attachments.len(),
+            hashtags.as_ref().map(Vec::len).unwrap_or_default(),
+        );
         let attached_deposit = env::attached_deposit();
         require!(
-            attached_deposit == GIVE_KUDOS_COST,
-            &display_deposit_requirement_in_near(GIVE_KUDOS_COST)
+            attached_deposit == give_kudos_cost,
+            &display_deposit_requirement_in_near(give_kudos_cost)
         );
 
+        if self.is_sender_blocked(&receiver_id, &sender_id) {
+            return Err(KudosError::SenderBlocked);
+        }
+
         let settings = Settings::from(&self.settings);
         let kind = kind.unwrap_or_default();
-        let hashtags = settings.validate_hashtags(hashtags.as_deref())?;
-        if message.len() > Settings::from(&self.settings).commentary_message_max_length  as usize {
-            return Err("Message max length exceeded");
+        if self.kudos_kind_info(&kind).is_none() {
+            return Err(KudosError::KudosKindNotFound);
+        }
+        let hashtags = settings.validate_hashtags(hashtags.as_deref(), |hashtag| {
+            self.is_hashtag_curated(hashtag, env::block_timestamp_ms())
+        })?;
+        let message = settings.validate_message(&kind, message)?;
+        if let Some(icon_cid) = icon_cid.as_ref() {
+            settings.validate_cid(icon_cid)?;
         }
+        settings.validate_attachments(&attachments)?;
+        let media = KudosMedia {
+            icon_cid,
+            attachments,
+        };
 
-        let external_db_id = self.external_db_id()?.clone();
+        self.verify_rate_limit(
+            &settings,
+            &sender_id,
+            RateLimitedAction::from(&kind),
+            env::block_timestamp_ms(),
+        )?;
+
+        let storage = self.storage_backend()?;
+
+        // Skip humanity verification if caller was recently verified
+        if self.is_human_cached(
+            &sender_id,
+            HumanityAction::from(&kind),
+            env::block_timestamp_ms(),
+        ) {
+            return self.save_kudos_verified(
+                predecessor_account_id,
+                attached_deposit,
+                storage,
+                receiver_id,
+                kind,
+                message,
+                media,
+                hashtags,
+            );
+        }
 
         let gas_remaining =
-            env::prepaid_gas() - (env::used_gas() + IS_HUMAN_GAS + GIVE_KUDOS_RESERVED_GAS);
+            env::prepaid_gas() - (env::used_gas() + VERIFY_HUMANITY_GAS + GIVE_KUDOS_RESERVED_GAS);
+
+        Ok(self.is_human_with_sbts(sender_id).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(gas_remaining)
+                .save_kudos(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    receiver_id,
+                    kind,
+                    message,
+                    media,
+                    hashtags,
+                ),
+        ))
+    }
+
+    /// Allows caller to require dings given to him to be accepted before they become public.
+    /// Dings given while acceptance is required stay pending until caller accepts or declines them.
+    pub fn require_dings_acceptance(&mut self, enabled: bool) {
+        self.assert_contract_running();
+
+        let receiver_id = env::signer_account_id();
+
+        if enabled {
+            self.dings_acceptance_required.insert(receiver_id);
+        } else {
+            self.dings_acceptance_required.remove(&receiver_id);
+        }
+    }
+
+    /// Allows caller to block a sender by [`AccountId`]. Blocked sender can't give kudos or dings to
+    /// caller, can't upvote or leave commentary messages to caller's kudos.
+    /// Requires a storage deposit which is returned back when sender is unblocked.
+    #[payable]
+    #[handle_result]
+    pub fn block_sender(&mut self, sender_id: AccountId) -> Result<(), KudosError> {
+        self.assert_contract_running();
+
+        let receiver_id = env::signer_account_id();
+        require!(receiver_id != sender_id, "User can't block himself");
+
+        require!(
+            env::attached_deposit() == BLOCK_SENDER_COST,
+            &display_deposit_requirement_in_near(BLOCK_SENDER_COST)
+        );
+
+        let blocked_senders = self
+            .blocked_senders
+            .entry(receiver_id.clone())
+            .or_insert_with(|| {
+                UnorderedSet::new(StorageKey::BlockedSendersOf {
+                    receiver_id_hash: env::sha256_array(receiver_id.as_bytes()),
+                })
+            });
+
+        if !blocked_senders.insert(sender_id) {
+            return Err(KudosError::SenderAlreadyBlocked);
+        }
+
+        Ok(())
+    }
+
+    /// Allows caller to unblock previously blocked sender by [`AccountId`].
+    /// Storage deposit paid for blocking is returned back to the receiver whose blocklist was updated,
+    /// the same signer account which blocked the sender.
+    #[handle_result]
+    pub fn unblock_sender(&mut self, sender_id: AccountId) -> Result<Promise, KudosError> {
+        self.assert_contract_running();
+
+        let receiver_id = env::signer_account_id();
+
+        let Some(blocked_senders) = self.blocked_senders.get_mut(&receiver_id) else {
+            return Err(KudosError::SenderNotBlocked);
+        };
+
+        if !blocked_senders.remove(&sender_id) {
+            return Err(KudosError::SenderNotBlocked);
+        }
+
+        if blocked_senders.is_empty() {
+            self.blocked_senders.remove(&receiver_id);
+        }
+
+        Ok(Promise::new(receiver_id).transfer(BLOCK_SENDER_COST))
+    }
+
+    /// Public view method to read senders [`AccountId`] blocked by a receiver [`AccountId`].
+    /// Returns up to `limit` senders starting from `from_index`.
+    pub fn view_blocked_senders(
+        &self,
+        receiver_id: AccountId,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AccountId> {
+        self.blocked_senders
+            .get(&receiver_id)
+            .map(|senders| {
+                senders
+                    .iter()
+                    .skip(from_index.unwrap_or_default() as usize)
+                    .take(limit.unwrap_or(BLOCKED_SENDERS_DEFAULT_LIMIT) as usize)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Allows caller to accept a pending ding associated with [`KudosId`] given to him, so it becomes public.
+    #[payable]
+    #[handle_result]
+    pub fn accept_kudos(&mut self, kudos_id: KudosId) -> Result<Promise, KudosError> {
+        if self.kudos_status.get(&kudos_id) != Some(&KudosStatus::Pending) {
+            return Err(KudosError::KudosNotPending);
+        }
+
+        self.update_kudos_status(kudos_id, KudosStatus::Accepted)
+    }
+
+    /// Allows caller to decline a pending ding associated with [`KudosId`] given to him, so it never becomes public.
+    #[payable]
+    #[handle_result]
+    pub fn decline_kudos(&mut self, kudos_id: KudosId) -> Result<Promise, KudosError> {
+        if self.kudos_status.get(&kudos_id) != Some(&KudosStatus::Pending) {
+            return Err(KudosError::KudosNotPending);
+        }
+
+        self.update_kudos_status(kudos_id, KudosStatus::Declined)
+    }
+
+    /// Allows caller to hide kudos or ding associated with [`KudosId`] given to him.
+    /// Hidden kudos can't be upvoted.
+    #[payable]
+    #[handle_result]
+    pub fn hide_kudos(&mut self, kudos_id: KudosId) -> Result<Promise, KudosError> {
+        if self.kudos_status.get(&kudos_id) == Some(&KudosStatus::Hidden) {
+            return Err(KudosError::KudosAlreadyHidden);
+        }
+
+        self.update_kudos_status(kudos_id, KudosStatus::Hidden)
+    }
+
+    /// Allows moderator to approve a proposed ding associated with [`KudosId`] before a quorum of upvotes
+    /// is reached, so it becomes public, or pending if its receiver requires dings to be accepted.
+    #[payable]
+    #[handle_result]
+    pub fn approve_ding(&mut self, kudos_id: KudosId) -> Result<Promise, KudosError> {
+        self.assert_contract_running();
+
+        let predecessor_account_id = env::predecessor_account_id();
+        require!(self.is_moderator(&predecessor_account_id), "Not allowed");
+
+        let minimum_gas_requirement = UPDATE_KUDOS_STATUS_RESERVED_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + KUDOS_STATUS_UPDATED_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        let attached_deposit = env::attached_deposit();
+        require!(
+            attached_deposit == UPDATE_KUDOS_STATUS_COST,
+            &display_deposit_requirement_in_near(UPDATE_KUDOS_STATUS_COST)
+        );
+
+        let receiver_id = self
+            .ding_quorums
+            .get(&kudos_id)
+            .map(|quorum| quorum.receiver_id.clone())
+            .ok_or(KudosError::KudosNotProposed)?;
+        let status = self.published_ding_status(&receiver_id);
+        let storage = self.storage_backend()?;
+
+        let set_status_gas = env::prepaid_gas()
+            - (env::used_gas()
+                + UPDATE_KUDOS_STATUS_RESERVED_GAS
+                + KUDOS_STATUS_UPDATED_CALLBACK_GAS
+                + FAILURE_CALLBACK_GAS);
+
+        Ok(storage
+            .set_kudos_status(
+                &receiver_id,
+                &kudos_id,
+                &status,
+                attached_deposit,
+                set_status_gas,
+            )?
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(KUDOS_STATUS_UPDATED_CALLBACK_GAS + FAILURE_CALLBACK_GAS)
+                    .on_kudos_status_updated(
+                        predecessor_account_id,
+                        attached_deposit.into(),
+                        kudos_id,
+                        status,
+                    ),
+            ))
+    }
+
+    /// Allows caller to report kudos associated with [`KudosId`] given to a user by [`AccountId`] as abusive.
+    /// Caller should have a valid i-am-human SBT and can report the same kudos only once.
+    /// Kudos is hidden once a minimum required number of reports is reached.
+    #[payable]
+    #[handle_result]
+    pub fn report_kudos(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+    ) -> Result<Promise, KudosError> {
+        self.report_content(ReportedContent {
+            receiver_id,
+            kudos_id,
+            comment_id: None,
+        })
+    }
+
+    /// Allows caller to report commentary message associated with [`CommentId`] left to kudos associated
+    /// with [`KudosId`] given to a user by [`AccountId`] as abusive. Caller should have a valid i-am-human SBT
+    /// and can report the same commentary message only once. Commentary message is hidden once a minimum
+    /// required number of reports is reached.
+    #[payable]
+    #[handle_result]
+    pub fn report_comment(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+    ) -> Result<Promise, KudosError> {
+        self.report_content(ReportedContent {
+            receiver_id,
+            kudos_id,
+            comment_id: Some(comment_id),
+        })
+    }
+
+    /// Allows moderator to uphold a report of kudos or commentary message, so reported content is hidden.
+    #[payable]
+    #[handle_result]
+    pub fn uphold_report(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: Option<CommentId>,
+    ) -> Result<Promise, KudosError> {
+        self.resolve_report(
+            ReportedContent {
+                receiver_id,
+                kudos_id,
+                comment_id,
+            },
+            ReportStatus::Upheld,
+        )
+    }
+
+    /// Allows moderator to dismiss a report of kudos or commentary message, so reported content is visible.
+    #[payable]
+    #[handle_result]
+    pub fn dismiss_report(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: Option<CommentId>,
+    ) -> Result<Promise, KudosError> {
+        self.resolve_report(
+            ReportedContent {
+                receiver_id,
+                kudos_id,
+                comment_id,
+            },
+            ReportStatus::Dismissed,
+        )
+    }
+
+    /// Public view method to read a number of actions [`RateLimitedAction`] which user by [`AccountId`]
+    /// is still allowed to perform within the current window
+    pub fn view_remaining_quota(&self, account_id: AccountId, action: RateLimitedAction) -> u32 {
+        self.remaining_quota(
+            &Settings::from(&self.settings),
+            &account_id,
+            action,
+            env::block_timestamp_ms(),
+        )
+    }
+
+    /// Removes rate limit counters of users by [`AccountId`] which don't limit them anymore, so their
+    /// storage is released. Could be called by anyone, returns a number of removed counters.
+    pub fn prune_rate_limits(&mut self, account_ids: Vec<AccountId>) -> u32 {
+        self.assert_contract_running();
+
+        let settings = Settings::from(&self.settings);
+        let now = env::block_timestamp_ms();
+
+        account_ids
+            .iter()
+            .map(|account_id| self.prune_rate_limits_of(&settings, account_id, now))
+            .sum()
+    }
+
+    /// Removes cached humanity verification results of users by [`AccountId`] which are not valid anymore,
+    /// so their storage is released. Could be called by anyone, returns a number of removed results.
+    pub fn prune_humanity_cache(&mut self, account_ids: Vec<AccountId>) -> u32 {
+        self.assert_contract_running();
+
+        let now = env::block_timestamp_ms();
+
+        account_ids
+            .iter()
+            .filter(|account_id| self.prune_cached_humanity(account_id, now))
+            .count() as u32
+    }
+
+    /// Public view method to read the most used hashtags [`HashtagUsage`] of all time
+    pub fn view_top_hashtags(&self, limit: Option<u16>) -> Vec<HashtagUsage> {
+        self.top_hashtags(limit.unwrap_or(TOP_HASHTAGS_DEFAULT_LIMIT) as usize)
+    }
+
+    /// Public view method to read the most used hashtags [`HashtagUsage`] within a window in milliseconds
+    /// before now. Window is limited to [`HASHTAG_TRENDING_MAX_WINDOW`] and rounded to whole days.
+    pub fn view_trending_hashtags(&self, window: u64, limit: Option<u16>) -> Vec<HashtagUsage> {
+        let window = window.min(HASHTAG_TRENDING_MAX_WINDOW);
+        let now = env::block_timestamp_ms();
+
+        self.trending_hashtags(
+            window,
+            now,
+            limit.unwrap_or(TOP_HASHTAGS_DEFAULT_LIMIT) as usize,
+        )
+    }
+
+    /// Allows an owner or moderator to add or update an official curated hashtag with a description and
+    /// optional expiration timestamp in milliseconds. Hashtag is stored in canonical form.
+    #[payable]
+    #[handle_result]
+    pub fn add_curated_hashtag(
+        &mut self,
+        hashtag: String,
+        description: String,
+        expires_at: Option<u64>,
+    ) -> Result<(), KudosError> {
+        require!(
+            self.is_hashtag_curator(&env::predecessor_account_id()),
+            "Not allowed"
+        );
+
+        if description.len() > CURATED_HASHTAG_DESCRIPTION_MAX_LENGTH {
+            return Err(KudosError::DescriptionMaxLengthExceeded);
+        }
+
+        let settings = Settings::from(&self.settings);
+        let hashtag = Hashtag::new(&hashtag, settings.hashtag_text_max_length as usize)?
+            .canonicalize(settings.hashtag_separator);
+        self.curated_hashtags.insert(
+            hashtag,
+            CuratedHashtag {
+                description,
+                expires_at,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Allows an owner or moderator to remove an official curated hashtag
+    #[payable]
+    #[handle_result]
+    pub fn remove_curated_hashtag(&mut self, hashtag: String) -> Result<(), KudosError> {
+        require!(
+            self.is_hashtag_curator(&env::predecessor_account_id()),
+            "Not allowed"
+        );
+
+        let settings = Settings::from(&self.settings);
+        let hashtag = Hashtag::new(&hashtag, settings.hashtag_text_max_length as usize)?
+            .canonicalize(settings.hashtag_separator);
+        self.curated_hashtags
+            .remove(&hashtag)
+            .map(|_| ())
+            .ok_or(KudosError::CuratedHashtagNotFound)
+    }
+
+    /// Public view method to read official curated hashtags [`CuratedHashtagView`], including expired ones.
+    /// Returns up to `limit` hashtags starting from `from_index`.
+    pub fn view_curated_hashtags(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<CuratedHashtagView> {
+        self.curated_hashtags
+            .iter()
+            .skip(from_index.unwrap_or_default() as usize)
+            .take(limit.unwrap_or(CURATED_HASHTAGS_DEFAULT_LIMIT) as usize)
+            .map(|(hashtag, info)| CuratedHashtagView {
+                hashtag: hashtag.clone(),
+                info: info.clone(),
+            })
+            .collect()
+    }
+
+    /// Public view method to read activity counters [`AccountStats`] of a user, counters are zeroed
+    /// if user has no activity yet
+    pub fn account_stats(&self, account_id: AccountId) -> AccountStats {
+        self.account_stats
+            .get(&account_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Public view method to read a leaderboard of users [`AccountStatsView`] ordered by a number of
+    /// received kudos. Returns up to `limit` users starting from `from_index`.
+    pub fn view_leaderboard(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AccountStatsView> {
+        self.top_receivers(
+            from_index.unwrap_or_default() as usize,
+            limit.unwrap_or(LEADERBOARD_DEFAULT_LIMIT) as usize,
+        )
+    }
+
+    /// Public view method to read positions of commentary messages in their threads [`CommentThreadView`],
+    /// so clients could rebuild threads. Commentary messages left before threads were indexed are skipped.
+    pub fn view_comment_threads(&self, comment_ids: Vec<CommentId>) -> Vec<CommentThreadView> {
+        comment_ids
+            .into_iter()
+            .filter_map(|comment_id| {
+                self.comment_threads
+                    .get(&comment_id)
+                    .cloned()
+                    .map(|thread| CommentThreadView { comment_id, thread })
+            })
+            .collect()
+    }
+
+    /// Public view method to read a [`Report`] of kudos or commentary message
+    pub fn view_report(
+        &self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: Option<CommentId>,
+    ) -> Option<Report> {
+        self.reports
+            .get(&ReportedContent {
+                receiver_id,
+                kudos_id,
+                comment_id,
+            })
+            .cloned()
+    }
+}
+
+impl Contract {
+    /// Verify that caller is a receiver of kudos associated with [`KudosId`] and update its [`KudosStatus`]
+    /// at a storage backend.
+    fn update_kudos_status(
+        &mut self,
+        kudos_id: KudosId,
+        status: KudosStatus,
+    ) -> Result<Promise, KudosError> {
+        self.assert_contract_running();
+
+        let minimum_gas_requirement = UPDATE_KUDOS_STATUS_RESERVED_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + KUDOS_RECEIVER_VERIFIED_CALLBACK_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + KUDOS_STATUS_UPDATED_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        let attached_deposit = env::attached_deposit();
+        require!(
+            attached_deposit == UPDATE_KUDOS_STATUS_COST,
+            &display_deposit_requirement_in_near(UPDATE_KUDOS_STATUS_COST)
+        );
+
+        let predecessor_account_id = env::predecessor_account_id();
+        let receiver_id = env::signer_account_id();
+        let storage = self.storage_backend()?;
+        let kudos_sender_path =
+            build_kudos_sender_path(&env::current_account_id(), &receiver_id, &kudos_id);
+
+        // Compute minimum required gas and split the remaining gas by two equal parts for
+        // storage backend subsequent calls
+        let get_kudos_sender_gas = (env::prepaid_gas()
+            - (env::used_gas()
+                + UPDATE_KUDOS_STATUS_RESERVED_GAS
+                + KUDOS_RECEIVER_VERIFIED_CALLBACK_GAS
+                + KUDOS_STATUS_UPDATED_CALLBACK_GAS
+                + FAILURE_CALLBACK_GAS))
+            / 2;
+        let get_kudos_sender_callback_gas = get_kudos_sender_gas
+            + KUDOS_RECEIVER_VERIFIED_CALLBACK_GAS
+            + KUDOS_STATUS_UPDATED_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+
+        Ok(storage
+            .read_kudos(
+                &receiver_id,
+                &kudos_id,
+                &[KudosField::SenderId],
+                get_kudos_sender_gas,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(get_kudos_sender_callback_gas)
+                    .on_kudos_receiver_verified(
+                        predecessor_account_id,
+                        attached_deposit.into(),
+                        storage.clone(),
+                        kudos_id,
+                        status,
+                        kudos_sender_path,
+                    ),
+            ))
+    }
+
+    /// Verify that reported content could be reported by caller, acquire it from a storage backend to verify
+    /// its existence and record a report
+    fn report_content(&mut self, content: ReportedContent) -> Result<Promise, KudosError> {
+        self.assert_contract_running();
+
+        let minimum_gas_requirement = REPORT_CONTENT_RESERVED_GAS
+            + VERIFY_HUMANITY_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + REPORT_VERIFIED_CALLBACK_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + REPORTED_CONTENT_HIDDEN_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        let attached_deposit = env::attached_deposit();
+        require!(
+            attached_deposit == REPORT_CONTENT_COST,
+            &display_deposit_requirement_in_near(REPORT_CONTENT_COST)
+        );
+
+        let predecessor_account_id = env::predecessor_account_id();
+        let reporter_id = env::signer_account_id();
+        self.verify_report_allowed(&content, &reporter_id)?;
+
+        let storage = self.storage_backend()?;
+        let content_path = build_reported_content_path(&env::current_account_id(), &content);
+        let content_field = match content.comment_id.as_ref() {
+            Some(comment_id) => KudosField::Comment(comment_id.clone()),
+            None => KudosField::SenderId,
+        };
+
+        // Compute minimum required gas and split the remaining gas by two equal parts for
+        // storage backend subsequent calls
+        let get_content_gas = (env::prepaid_gas()
+            - (env::used_gas()
+                + REPORT_CONTENT_RESERVED_GAS
+                + VERIFY_HUMANITY_GAS
+                + REPORT_VERIFIED_CALLBACK_GAS
+                + REPORTED_CONTENT_HIDDEN_CALLBACK_GAS
+                + FAILURE_CALLBACK_GAS))
+            / 2;
+        let get_content_callback_gas = get_content_gas
+            + REPORT_VERIFIED_CALLBACK_GAS
+            + REPORTED_CONTENT_HIDDEN_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+
+        let get_content = storage.read_kudos(
+            &content.receiver_id,
+            &content.kudos_id,
+            &[content_field],
+            get_content_gas,
+        );
+        let get_content = if self.is_human_cached(
+            &reporter_id,
+            HumanityAction::Report,
+            env::block_timestamp_ms(),
+        ) {
+            get_content
+        } else {
+            get_content.and(self.is_human_with_sbts(reporter_id))
+        };
+
+        Ok(get_content.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(get_content_callback_gas)
+                .on_report_verified(
+                    predecessor_account_id,
+                    attached_deposit.into(),
+                    storage,
+                    content,
+                    content_path,
+                ),
+        ))
+    }
+
+    /// Verify that caller is a moderator and update moderation flag of reported content at a storage backend
+    fn resolve_report(
+        &mut self,
+        content: ReportedContent,
+        status: ReportStatus,
+    ) -> Result<Promise, KudosError> {
+        self.assert_contract_running();
+
+        let predecessor_account_id = env::predecessor_account_id();
+        require!(self.is_moderator(&predecessor_account_id), "Not allowed");
+
+        let minimum_gas_requirement = MODERATE_REPORT_RESERVED_GAS
+            + SOCIAL_DB_REQUEST_MIN_RESERVED_GAS
+            + REPORT_RESOLVED_CALLBACK_GAS
+            + FAILURE_CALLBACK_GAS;
+        require!(
+            env::prepaid_gas() >= minimum_gas_requirement,
+            display_gas_requirement_in_tgas(minimum_gas_requirement)
+        );
+
+        let attached_deposit = env::attached_deposit();
+        require!(
+            attached_deposit == MODERATE_REPORT_COST,
+            &display_deposit_requirement_in_near(MODERATE_REPORT_COST)
+        );
+
+        match self.reports.get(&content) {
+            None => return Err(KudosError::ReportNotFound),
+            Some(report) if report.status.is_resolved() => {
+                return Err(KudosError::ReportAlreadyResolved)
+            }
+            Some(_) => (),
+        }
+
+        let storage = self.storage_backend()?;
+
+        let set_flag_gas = env::prepaid_gas()
+            - (env::used_gas()
+                + MODERATE_REPORT_RESERVED_GAS
+                + REPORT_RESOLVED_CALLBACK_GAS
+                + FAILURE_CALLBACK_GAS);
 
-        Ok(ext_sbtreg::ext(self.iah_registry.clone())
-            .with_static_gas(IS_HUMAN_GAS)
-            .is_human(sender_id)
+        Ok(storage
+            .set_content_hidden(&content, status.is_hidden(), attached_deposit, set_flag_gas)?
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(gas_remaining)
-                    .save_kudos(
+                    .with_static_gas(REPORT_RESOLVED_CALLBACK_GAS + FAILURE_CALLBACK_GAS)
+                    .on_report_resolved(
                         predecessor_account_id,
                         attached_deposit.into(),
-                        external_db_id,
-                        receiver_id,
-                        kind,
-                        message,
-                        icon_cid,
-                        hashtags,
+                        content,
+                        status,
                     ),
             ))
     }
@@ -0,0 +1,95 @@
+use crate::settings::Settings;
+use crate::types::RateLimitedAction;
+use crate::{Contract, KudosError};
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Return number of actions [`RateLimitedAction`] which user is still allowed to perform within
+    /// the last window. Returns [`u32::MAX`] if rate limiting is disabled.
+    pub(crate) fn remaining_quota(
+        &self,
+        settings: &Settings,
+        account_id: &AccountId,
+        action: RateLimitedAction,
+        now: u64,
+    ) -> u32 {
+        let window = settings.rate_limit_window_of(action);
+        if window == 0 {
+            return u32::MAX;
+        }
+
+        let performed = self
+            .rate_limits
+            .get(&(account_id.clone(), action))
+            .map(|counter| counter.count_at(window, now))
+            .unwrap_or_default();
+
+        settings
+            .max_actions_per_window(action)
+            .saturating_sub(performed)
+    }
+
+    /// Verify that user didn't exceed a maximum number of actions [`RateLimitedAction`] allowed within
+    /// the last window
+    pub(crate) fn verify_rate_limit(
+        &self,
+        settings: &Settings,
+        account_id: &AccountId,
+        action: RateLimitedAction,
+        now: u64,
+    ) -> Result<(), KudosError> {
+        if self.remaining_quota(settings, account_id, action, now) == 0 {
+            return Err(KudosError::RateLimitExceeded { action });
+        }
+
+        Ok(())
+    }
+
+    /// Memorise an action [`RateLimitedAction`] performed by user. Called once an action is saved to
+    /// a storage backend, so failed and refunded actions don't use up user's quota.
+    pub(crate) fn record_rate_limited_action(
+        &mut self,
+        settings: &Settings,
+        account_id: &AccountId,
+        action: RateLimitedAction,
+        now: u64,
+    ) {
+        let window = settings.rate_limit_window_of(action);
+        if window == 0 {
+            return;
+        }
+
+        self.rate_limits
+            .entry((account_id.clone(), action))
+            .or_default()
+            .inc(window, now);
+    }
+
+    /// Remove counters of actions [`RateLimitedAction`] performed by user [`AccountId`] which don't
+    /// limit user anymore, because no actions were performed within the last window or rate limiting
+    /// is disabled. Returns a number of removed counters.
+    pub(crate) fn prune_rate_limits_of(
+        &mut self,
+        settings: &Settings,
+        account_id: &AccountId,
+        now: u64,
+    ) -> u32 {
+        let mut removed = 0;
+
+        for action in RateLimitedAction::ALL {
+            let key = (account_id.clone(), action);
+            let window = settings.rate_limit_window_of(action);
+            let is_stale = self
+                .rate_limits
+                .get(&key)
+                .is_some_and(|counter| window == 0 || counter.count_at(window, now) == 0);
+
+            if is_stale {
+                self.rate_limits.remove(&key);
+                removed += 1;
+            }
+        }
+
+        removed
+    }
+}
@@ -6,6 +6,13 @@ pub type TokenId = u64;
 
 pub const IS_HUMAN_GAS: Gas = Gas(12 * Gas::ONE_TERA.0);
 
+pub const SBTS_GAS: Gas = Gas(12 * Gas::ONE_TERA.0);
+
+pub const SBT_TOKENS_BY_OWNER_GAS: Gas = Gas(12 * Gas::ONE_TERA.0);
+
+/// Gas required to verify user's humanity and acquire user's SBTs by a joint call
+pub const VERIFY_HUMANITY_GAS: Gas = Gas(IS_HUMAN_GAS.0 + SBT_TOKENS_BY_OWNER_GAS.0);
+
 /// TokenMetadata defines attributes for each SBT token.
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -24,6 +31,15 @@ pub struct OwnedToken {
     pub metadata: TokenMetadata,
 }
 
+/// Full information about SBT token returned by `sbts` view method of i-am-human-registry
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Token {
+    pub token: TokenId,
+    pub owner: AccountId,
+    pub metadata: TokenMetadata,
+}
+
 #[ext_contract(ext_sbtreg)]
 pub trait ExtSbtRegistry {
     /*
@@ -38,5 +54,16 @@ pub trait ExtSbtRegistry {
 
     fn is_human(&self, account: AccountId) -> Vec<(AccountId, Vec<TokenId>)>;
 
+    fn sbts(&self, issuer: AccountId, tokens: Vec<TokenId>) -> Vec<Option<Token>>;
+
+    fn sbt_tokens_by_owner(
+        &self,
+        account: AccountId,
+        issuer: Option<AccountId>,
+        from_class: Option<u64>,
+        limit: Option<u32>,
+        with_expired: Option<bool>,
+    ) -> Vec<(AccountId, Vec<OwnedToken>)>;
+
     fn sbt_mint(&mut self, token_spec: Vec<(AccountId, Vec<TokenMetadata>)>) -> Promise;
 }
@@ -0,0 +1,166 @@
+use crate::types::{
+    KudosKind, Season, SeasonId, SeasonPosition, SeasonStanding, SeasonTally, StorageKey,
+};
+use crate::{Contract, KudosError};
+use near_sdk::collections::TreeMap;
+use near_sdk::store::UnorderedMap;
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Return [`SeasonId`] of a season active at provided timestamp. Seasons follow each other, so
+    /// the current season is moved forward past seasons which have ended by provided timestamp.
+    pub(crate) fn active_season_id(&mut self, now: u64) -> Option<SeasonId> {
+        while let Some(season) = self.seasons.get(self.current_season_id) {
+            if season.end_at > now {
+                return season.is_active(now).then_some(self.current_season_id);
+            }
+            self.current_season_id += 1;
+        }
+
+        None
+    }
+
+    /// Return [`Season`] by [`SeasonId`]
+    pub(crate) fn season(&self, season_id: SeasonId) -> Result<&Season, KudosError> {
+        self.seasons
+            .get(season_id)
+            .ok_or(KudosError::SeasonNotFound)
+    }
+
+    /// Update [`SeasonTally`] of a receiver [`AccountId`] and receiver's position in a season ranking
+    /// [`SeasonPosition`] within a season active at provided timestamp, nothing is tallied if there is
+    /// no active season.
+    ///
+    /// Storage of season tallies and rankings is paid by this contract.
+    fn tally_season<F>(&mut self, account_id: &AccountId, now: u64, update: F)
+    where
+        F: FnOnce(&mut SeasonTally),
+    {
+        let Some(season_id) = self.active_season_id(now) else {
+            return;
+        };
+
+        let tallies = self
+            .season_tallies
+            .entry(season_id)
+            .or_insert_with(|| UnorderedMap::new(StorageKey::SeasonTalliesOf { season_id }));
+
+        let tally = tallies.entry(account_id.clone()).or_default();
+        let position = SeasonPosition::new(account_id, tally);
+        update(tally);
+        let updated_position = SeasonPosition::new(account_id, tally);
+
+        if position != updated_position {
+            let ranking = self
+                .season_rankings
+                .entry(season_id)
+                .or_insert_with(|| TreeMap::new(StorageKey::SeasonRankingOf { season_id }));
+
+            if let Some(position) = position {
+                ranking.remove(&position);
+            }
+            if let Some(position) = updated_position {
+                ranking.insert(&position, &());
+            }
+        }
+    }
+
+    /// Tally kudos of provided [`KudosKind`] received within an active season, dings are not tallied
+    pub(crate) fn tally_season_kudos(
+        &mut self,
+        receiver_id: &AccountId,
+        kind: &KudosKind,
+        now: u64,
+    ) {
+        if kind != &KudosKind::Ding {
+            self.tally_season(receiver_id, now, |tally| {
+                tally.kudos_received = tally.kudos_received.saturating_add(1);
+            });
+        }
+    }
+
+    /// Tally an upvote of kudos received within an active season
+    pub(crate) fn tally_season_upvote(&mut self, receiver_id: &AccountId, now: u64) {
+        self.tally_season(receiver_id, now, |tally| {
+            tally.upvotes_received = tally.upvotes_received.saturating_add(1);
+        });
+    }
+
+    /// Tally kudos exchanged for ProofOfKudos SBTs within an active season
+    pub(crate) fn tally_season_exchange(
+        &mut self,
+        receiver_id: &AccountId,
+        exchanged: usize,
+        now: u64,
+    ) {
+        self.tally_season(receiver_id, now, |tally| {
+            tally.kudos_exchanged = tally.kudos_exchanged.saturating_add(exchanged as u64);
+        });
+    }
+
+    /// Return receivers [`SeasonStanding`] of a season ordered by a number of received kudos, then by
+    /// a number of received upvotes and exchanged kudos. Receivers without kudos are not ranked.
+    pub(crate) fn season_ranking(&self, season_id: SeasonId, limit: usize) -> Vec<SeasonStanding> {
+        let (Some(ranking), Some(tallies)) = (
+            self.season_rankings.get(&season_id),
+            self.season_tallies.get(&season_id),
+        ) else {
+            return vec![];
+        };
+
+        ranking
+            .iter()
+            .take(limit)
+            .filter_map(|(position, _)| {
+                tallies
+                    .get(&position.account_id)
+                    .map(|tally| SeasonStanding {
+                        account_id: position.account_id,
+                        tally: tally.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Return receivers [`SeasonStanding`] of a season, which are frozen if a season is closed or
+    /// ranked on the fly otherwise
+    pub(crate) fn season_top(
+        &self,
+        season_id: SeasonId,
+        limit: usize,
+    ) -> Result<Vec<SeasonStanding>, KudosError> {
+        if !self.season(season_id)?.closed {
+            return Ok(self.season_ranking(season_id, limit));
+        }
+
+        Ok(self
+            .season_snapshots
+            .get(&season_id)
+            .map(|snapshot| snapshot.iter().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Freeze a ranked snapshot of up to provided number of receivers of a season
+    pub(crate) fn freeze_season(
+        &mut self,
+        season_id: SeasonId,
+        size: usize,
+    ) -> Vec<SeasonStanding> {
+        let snapshot = self.season_ranking(season_id, size);
+
+        if let Some(season) = self.seasons.get_mut(season_id) {
+            season.closed = true;
+        }
+        self.season_snapshots.insert(season_id, snapshot.clone());
+
+        snapshot
+    }
+
+    /// Reopen a closed season and forget its snapshot, e.g. when SBTs of a season failed to mint
+    pub(crate) fn unfreeze_season(&mut self, season_id: SeasonId) {
+        if let Some(season) = self.seasons.get_mut(season_id) {
+            season.closed = false;
+        }
+        self.season_snapshots.remove(&season_id);
+    }
+}
@@ -1,5 +1,10 @@
+use crate::consts::COMMENT_REACTION_MAX_LENGTH;
 use crate::utils::opt_default;
-use crate::Hashtag;
+use crate::{
+    CidCodec, CidMultihash, CommentaryEncoding, Hashtag, HashtagMode, HashtagSeparator,
+    HumanityPolicy, KudosAttachment, KudosError, KudosKind, KudosMessage, RateLimitedAction,
+    SocialDbLayout, WrappedCid,
+};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 
@@ -12,13 +17,51 @@ pub struct Settings {
     pub hashtag_text_max_length: u8,
     pub min_number_of_upvotes_to_exchange_kudos: u8,
     pub pok_sbt_ttl: u64,
+    pub ignore_kudos_sender_upvotes: bool,
+    pub max_reciprocal_upvotes_per_period: u8,
+    pub reciprocal_upvotes_period: u64,
+    pub min_upvoter_iah_sbt_age: u64,
+    pub min_number_of_reports_to_hide_content: u8,
+    pub max_kudos_per_window: u16,
+    pub max_dings_per_window: u16,
+    pub max_upvotes_per_window: u16,
+    pub max_comments_per_window: u16,
+    pub rate_limit_window: u64,
+    pub humanity_cache_ttl: u64,
+    pub humanity_policy: HumanityPolicy,
+    pub social_db_layout: SocialDbLayout,
+    pub max_number_of_mentions_per_kudos: u8,
+    pub hashtag_separator: HashtagSeparator,
+    pub hashtag_mode: HashtagMode,
+    pub min_ding_message_length: u16,
+    pub ding_upvotes_quorum: u8,
+    pub ding_rate_limit_window: u64,
+    pub season_snapshot_size: u16,
+    pub max_comment_thread_depth: u8,
+    pub comment_reactions: Vec<String>,
+    pub commentary_encoding: CommentaryEncoding,
+    pub max_number_of_attachments_per_kudos: u8,
+    pub allowed_cid_codecs: Vec<CidCodec>,
+    pub allowed_cid_multihashes: Vec<CidMultihash>,
+}
+
+/// Settings of the initially deployed version of this contract
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct SettingsV0 {
+    pub commentary_message_max_length: u16,
+    pub max_number_of_hashtags_per_kudos: u8,
+    pub hashtag_text_max_length: u8,
+    pub min_number_of_upvotes_to_exchange_kudos: u8,
+    pub pok_sbt_ttl: u64,
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
 #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[allow(clippy::large_enum_variant)] // old versions are read only once while migrating
 pub enum VSettings {
     // Add old versions here, keep ordering, the oldest on top, most recent at bottom
-    // e.g. V0(SettingsV0),
+    V0(SettingsV0),
     Current(Settings), // most recent version
 }
 
@@ -36,6 +79,58 @@ pub struct SettingsView {
     pub min_number_of_upvotes_to_exchange_kudos: Option<u8>,
     #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
     pub pok_sbt_ttl: Option<u64>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub ignore_kudos_sender_upvotes: Option<bool>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_reciprocal_upvotes_per_period: Option<u8>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub reciprocal_upvotes_period: Option<u64>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub min_upvoter_iah_sbt_age: Option<u64>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub min_number_of_reports_to_hide_content: Option<u8>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_kudos_per_window: Option<u16>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_dings_per_window: Option<u16>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_upvotes_per_window: Option<u16>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_comments_per_window: Option<u16>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub rate_limit_window: Option<u64>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub humanity_cache_ttl: Option<u64>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub humanity_policy: Option<HumanityPolicy>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub social_db_layout: Option<SocialDbLayout>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_number_of_mentions_per_kudos: Option<u8>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub hashtag_separator: Option<HashtagSeparator>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub hashtag_mode: Option<HashtagMode>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub min_ding_message_length: Option<u16>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub ding_upvotes_quorum: Option<u8>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub ding_rate_limit_window: Option<u64>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub season_snapshot_size: Option<u16>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_comment_thread_depth: Option<u8>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub comment_reactions: Option<Vec<String>>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub commentary_encoding: Option<CommentaryEncoding>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub max_number_of_attachments_per_kudos: Option<u8>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub allowed_cid_codecs: Option<Vec<CidCodec>>,
+    #[serde(default = "opt_default", skip_serializing_if = "Option::is_none")]
+    pub allowed_cid_multihashes: Option<Vec<CidMultihash>>,
 }
 
 impl Settings {
@@ -65,31 +160,258 @@ impl Settings {
             self.pok_sbt_ttl = pok_sbt_ttl;
         }
 
+        if let Some(ignore_kudos_sender_upvotes) = settings_json.ignore_kudos_sender_upvotes {
+            self.ignore_kudos_sender_upvotes = ignore_kudos_sender_upvotes;
+        }
+
+        if let Some(max_reciprocal_upvotes_per_period) =
+            settings_json.max_reciprocal_upvotes_per_period
+        {
+            self.max_reciprocal_upvotes_per_period = max_reciprocal_upvotes_per_period;
+        }
+
+        if let Some(reciprocal_upvotes_period) = settings_json.reciprocal_upvotes_period {
+            self.reciprocal_upvotes_period = reciprocal_upvotes_period;
+        }
+
+        if let Some(min_upvoter_iah_sbt_age) = settings_json.min_upvoter_iah_sbt_age {
+            self.min_upvoter_iah_sbt_age = min_upvoter_iah_sbt_age;
+        }
+
+        if let Some(min_number_of_reports_to_hide_content) =
+            settings_json.min_number_of_reports_to_hide_content
+        {
+            self.min_number_of_reports_to_hide_content = min_number_of_reports_to_hide_content;
+        }
+
+        if let Some(max_kudos_per_window) = settings_json.max_kudos_per_window {
+            self.max_kudos_per_window = max_kudos_per_window;
+        }
+
+        if let Some(max_dings_per_window) = settings_json.max_dings_per_window {
+            self.max_dings_per_window = max_dings_per_window;
+        }
+
+        if let Some(max_upvotes_per_window) = settings_json.max_upvotes_per_window {
+            self.max_upvotes_per_window = max_upvotes_per_window;
+        }
+
+        if let Some(max_comments_per_window) = settings_json.max_comments_per_window {
+            self.max_comments_per_window = max_comments_per_window;
+        }
+
+        if let Some(rate_limit_window) = settings_json.rate_limit_window {
+            self.rate_limit_window = rate_limit_window;
+        }
+
+        if let Some(humanity_cache_ttl) = settings_json.humanity_cache_ttl {
+            self.humanity_cache_ttl = humanity_cache_ttl;
+        }
+
+        if let Some(humanity_policy) = settings_json.humanity_policy {
+            self.humanity_policy = humanity_policy;
+        }
+
+        if let Some(social_db_layout) = settings_json.social_db_layout {
+            self.social_db_layout = social_db_layout;
+        }
+
+        if let Some(max_number_of_mentions_per_kudos) =
+            settings_json.max_number_of_mentions_per_kudos
+        {
+            self.max_number_of_mentions_per_kudos = max_number_of_mentions_per_kudos;
+        }
+
+        if let Some(hashtag_separator) = settings_json.hashtag_separator {
+            self.hashtag_separator = hashtag_separator;
+        }
+
+        if let Some(hashtag_mode) = settings_json.hashtag_mode {
+            self.hashtag_mode = hashtag_mode;
+        }
+
+        if let Some(min_ding_message_length) = settings_json.min_ding_message_length {
+            self.min_ding_message_length = min_ding_message_length;
+        }
+
+        if let Some(ding_upvotes_quorum) = settings_json.ding_upvotes_quorum {
+            self.ding_upvotes_quorum = ding_upvotes_quorum;
+        }
+
+        if let Some(ding_rate_limit_window) = settings_json.ding_rate_limit_window {
+            self.ding_rate_limit_window = ding_rate_limit_window;
+        }
+
+        if let Some(season_snapshot_size) = settings_json.season_snapshot_size {
+            self.season_snapshot_size = season_snapshot_size;
+        }
+
+        if let Some(max_comment_thread_depth) = settings_json.max_comment_thread_depth {
+            self.max_comment_thread_depth = max_comment_thread_depth;
+        }
+
+        if let Some(comment_reactions) = settings_json.comment_reactions {
+            self.comment_reactions = comment_reactions;
+        }
+
+        if let Some(commentary_encoding) = settings_json.commentary_encoding {
+            self.commentary_encoding = commentary_encoding;
+        }
+
+        if let Some(max_number_of_attachments_per_kudos) =
+            settings_json.max_number_of_attachments_per_kudos
+        {
+            self.max_number_of_attachments_per_kudos = max_number_of_attachments_per_kudos;
+        }
+
+        if let Some(allowed_cid_codecs) = settings_json.allowed_cid_codecs {
+            self.allowed_cid_codecs = allowed_cid_codecs;
+        }
+
+        if let Some(allowed_cid_multihashes) = settings_json.allowed_cid_multihashes {
+            self.allowed_cid_multihashes = allowed_cid_multihashes;
+        }
+
         self
     }
 
-    /// Validate and convert array slice of [String] to an array of [Hashtag]
-    pub(crate) fn validate_hashtags(
+    /// Validate and convert array slice of [String] to an array of unique canonical [Hashtag].
+    /// Curated hashtags, for which `is_curated` returns `true`, are verified according to [`HashtagMode`].
+    pub(crate) fn validate_hashtags<F>(
         &self,
         hashtags: Option<&[String]>,
-    ) -> Result<Option<Vec<Hashtag>>, &'static str> {
-        let Some(hashtags) = hashtags else { return Ok(None) };
+        is_curated: F,
+    ) -> Result<Option<Vec<Hashtag>>, KudosError>
+    where
+        F: Fn(&Hashtag) -> bool,
+    {
+        let Some(hashtags) = hashtags else {
+            return match self.hashtag_mode {
+                HashtagMode::CuratedRequired => Err(KudosError::CuratedHashtagRequired),
+                HashtagMode::Open | HashtagMode::CuratedOnly => Ok(None),
+            };
+        };
 
         if hashtags.len() > self.max_number_of_hashtags_per_kudos as usize {
-            return Err("Maximum number of hashtags per Kudos exceeded");
+            return Err(KudosError::HashtagsLimitExceeded);
         }
 
-        hashtags
-            .iter()
-            .map(|ht_text| Hashtag::new(ht_text, self.hashtag_text_max_length as usize))
-            .collect::<Result<Vec<_>, _>>()
-            .map(Some)
+        let mut canonical_hashtags = Vec::with_capacity(hashtags.len());
+
+        for ht_text in hashtags {
+            let hashtag = Hashtag::new(ht_text, self.hashtag_text_max_length as usize)?
+                .canonicalize(self.hashtag_separator);
+
+            if self.hashtag_mode == HashtagMode::CuratedOnly && !is_curated(&hashtag) {
+                return Err(KudosError::HashtagNotCurated {
+                    hashtag: hashtag.to_string(),
+                });
+            }
+
+            if !canonical_hashtags.contains(&hashtag) {
+                canonical_hashtags.push(hashtag);
+            }
+        }
+
+        if self.hashtag_mode == HashtagMode::CuratedRequired
+            && !canonical_hashtags.iter().any(&is_curated)
+        {
+            return Err(KudosError::CuratedHashtagRequired);
+        }
+
+        Ok(Some(canonical_hashtags))
+    }
+
+    /// Validate kudos message text and extract mentioned users [`KudosMessage`].
+    /// Dings should be justified by a message of a minimum length.
+    pub(crate) fn validate_message(
+        &self,
+        kind: &KudosKind,
+        message: String,
+    ) -> Result<KudosMessage, KudosError> {
+        if kind == &KudosKind::Ding
+            && message.chars().count() < self.min_ding_message_length as usize
+        {
+            return Err(KudosError::DingJustificationTooShort {
+                min_length: self.min_ding_message_length,
+            });
+        }
+
+        KudosMessage::new(
+            message,
+            self.commentary_message_max_length as usize,
+            self.max_number_of_mentions_per_kudos as usize,
+        )
     }
 
-    pub(crate) fn acquire_pok_sbt_expire_at_ts(&self, issued_at: u64) -> Result<u64, &'static str> {
+    /// Validate a number of kudos attachments [`KudosAttachment`] and every attachment
+    pub(crate) fn validate_attachments(
+        &self,
+        attachments: &[KudosAttachment],
+    ) -> Result<(), KudosError> {
+        if attachments.len() > self.max_number_of_attachments_per_kudos as usize {
+            return Err(KudosError::AttachmentsLimitExceeded);
+        }
+
+        attachments.iter().try_for_each(|attachment| {
+            self.validate_cid(&attachment.cid)?;
+            attachment.validate()
+        })
+    }
+
+    /// Validate that codec and multihash of a CID [`WrappedCid`] are allowed
+    pub(crate) fn validate_cid(&self, cid: &WrappedCid) -> Result<(), KudosError> {
+        if !cid.is_allowed(&self.allowed_cid_codecs, &self.allowed_cid_multihashes) {
+            return Err(KudosError::CidNotAllowed);
+        }
+
+        Ok(())
+    }
+
+    /// Validate that a reaction to a commentary message belongs to a configured set of reactions
+    pub(crate) fn validate_reaction(&self, reaction: &str) -> Result<(), KudosError> {
+        if reaction.len() > COMMENT_REACTION_MAX_LENGTH
+            || !self.comment_reactions.iter().any(|r| r == reaction)
+        {
+            return Err(KudosError::InvalidReaction);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn acquire_pok_sbt_expire_at_ts(&self, issued_at: u64) -> Result<u64, KudosError> {
         issued_at
             .checked_add(self.pok_sbt_ttl)
-            .ok_or("ProofOfKudos SBT expiration date overflow")
+            .ok_or(KudosError::PokSbtExpirationOverflow)
+    }
+
+    /// Return maximum number of actions [`RateLimitedAction`] allowed to be performed by a user within a window
+    pub(crate) fn max_actions_per_window(&self, action: RateLimitedAction) -> u32 {
+        match action {
+            RateLimitedAction::Kudos => self.max_kudos_per_window as u32,
+            RateLimitedAction::Ding => self.max_dings_per_window as u32,
+            RateLimitedAction::Upvote => self.max_upvotes_per_window as u32,
+            RateLimitedAction::Comment => self.max_comments_per_window as u32,
+        }
+    }
+
+    /// Return a window in milliseconds within which actions [`RateLimitedAction`] are limited.
+    /// Dings have their own window, so every sender gets a separate daily budget of dings by default.
+    pub(crate) fn rate_limit_window_of(&self, action: RateLimitedAction) -> u64 {
+        match action {
+            RateLimitedAction::Ding => self.ding_rate_limit_window,
+            RateLimitedAction::Kudos | RateLimitedAction::Upvote | RateLimitedAction::Comment => {
+                self.rate_limit_window
+            }
+        }
+    }
+
+    /// Return start timestamp of a period which includes provided timestamp, used to count reciprocal upvotes
+    pub(crate) fn reciprocal_upvotes_period_start(&self, timestamp: u64) -> u64 {
+        match self.reciprocal_upvotes_period {
+            0 => 0,
+            period => timestamp - timestamp % period,
+        }
     }
 }
 
@@ -120,6 +442,93 @@ fn default_pok_sbt_ttl() -> u64 {
     365 * 86_400_000
 }
 
+fn default_ignore_kudos_sender_upvotes() -> bool {
+    true
+}
+
+fn default_max_reciprocal_upvotes_per_period() -> u8 {
+    3
+}
+
+fn default_reciprocal_upvotes_period() -> u64 {
+    30 * 86_400_000
+}
+
+fn default_min_upvoter_iah_sbt_age() -> u64 {
+    0
+}
+
+fn default_min_number_of_reports_to_hide_content() -> u8 {
+    5
+}
+
+fn default_max_kudos_per_window() -> u16 {
+    20
+}
+
+fn default_max_dings_per_window() -> u16 {
+    5
+}
+
+fn default_max_upvotes_per_window() -> u16 {
+    100
+}
+
+fn default_max_comments_per_window() -> u16 {
+    50
+}
+
+fn default_rate_limit_window() -> u64 {
+    86_400_000
+}
+
+fn default_humanity_cache_ttl() -> u64 {
+    86_400_000
+}
+
+fn default_max_number_of_mentions_per_kudos() -> u8 {
+    5
+}
+
+fn default_min_ding_message_length() -> u16 {
+    20
+}
+
+fn default_ding_upvotes_quorum() -> u8 {
+    3
+}
+
+fn default_ding_rate_limit_window() -> u64 {
+    86_400_000
+}
+
+fn default_season_snapshot_size() -> u16 {
+    100
+}
+
+fn default_max_comment_thread_depth() -> u8 {
+    5
+}
+
+fn default_comment_reactions() -> Vec<String> {
+    ["👍", "❤️", "🎉", "😂", "😮"]
+        .into_iter()
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn default_max_number_of_attachments_per_kudos() -> u8 {
+    5
+}
+
+fn default_allowed_cid_codecs() -> Vec<CidCodec> {
+    vec![CidCodec::Raw, CidCodec::DagPb]
+}
+
+fn default_allowed_cid_multihashes() -> Vec<CidMultihash> {
+    vec![CidMultihash::Sha2_256]
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -129,6 +538,32 @@ impl Default for Settings {
             min_number_of_upvotes_to_exchange_kudos:
                 default_min_number_of_upvotes_to_exchange_kudos(),
             pok_sbt_ttl: default_pok_sbt_ttl(),
+            ignore_kudos_sender_upvotes: default_ignore_kudos_sender_upvotes(),
+            max_reciprocal_upvotes_per_period: default_max_reciprocal_upvotes_per_period(),
+            reciprocal_upvotes_period: default_reciprocal_upvotes_period(),
+            min_upvoter_iah_sbt_age: default_min_upvoter_iah_sbt_age(),
+            min_number_of_reports_to_hide_content: default_min_number_of_reports_to_hide_content(),
+            max_kudos_per_window: default_max_kudos_per_window(),
+            max_dings_per_window: default_max_dings_per_window(),
+            max_upvotes_per_window: default_max_upvotes_per_window(),
+            max_comments_per_window: default_max_comments_per_window(),
+            rate_limit_window: default_rate_limit_window(),
+            humanity_cache_ttl: default_humanity_cache_ttl(),
+            humanity_policy: HumanityPolicy::default(),
+            social_db_layout: SocialDbLayout::default(),
+            max_number_of_mentions_per_kudos: default_max_number_of_mentions_per_kudos(),
+            hashtag_separator: HashtagSeparator::default(),
+            hashtag_mode: HashtagMode::default(),
+            min_ding_message_length: default_min_ding_message_length(),
+            ding_upvotes_quorum: default_ding_upvotes_quorum(),
+            ding_rate_limit_window: default_ding_rate_limit_window(),
+            season_snapshot_size: default_season_snapshot_size(),
+            max_comment_thread_depth: default_max_comment_thread_depth(),
+            comment_reactions: default_comment_reactions(),
+            commentary_encoding: CommentaryEncoding::default(),
+            max_number_of_attachments_per_kudos: default_max_number_of_attachments_per_kudos(),
+            allowed_cid_codecs: default_allowed_cid_codecs(),
+            allowed_cid_multihashes: default_allowed_cid_multihashes(),
         }
     }
 }
@@ -137,14 +572,24 @@ impl From<&VSettings> for Settings {
     fn from(v_settings: &VSettings) -> Self {
         match v_settings {
             VSettings::Current(settings) => settings.clone(),
-            // TODO: add any migration stuff below
-            // e.g. VSettings::V0(settings_v0) => Settings::from(settings_v0),
+            VSettings::V0(settings_v0) => Settings::from(settings_v0),
         }
     }
 }
 
-// TODO: impl From<&OLD_VERSION_STRUCT> for CURRENT_VERSION_STRUCT
-// e.g. impl From<&SettingsV0> for Settings
+impl From<&SettingsV0> for Settings {
+    fn from(settings_v0: &SettingsV0) -> Self {
+        Self {
+            commentary_message_max_length: settings_v0.commentary_message_max_length,
+            max_number_of_hashtags_per_kudos: settings_v0.max_number_of_hashtags_per_kudos,
+            hashtag_text_max_length: settings_v0.hashtag_text_max_length,
+            min_number_of_upvotes_to_exchange_kudos: settings_v0
+                .min_number_of_upvotes_to_exchange_kudos,
+            pok_sbt_ttl: settings_v0.pok_sbt_ttl,
+            ..Settings::default()
+        }
+    }
+}
 
 impl From<Settings> for VSettings {
     fn from(settings: Settings) -> Self {
@@ -162,34 +607,179 @@ impl From<Settings> for SettingsView {
                 settings.min_number_of_upvotes_to_exchange_kudos,
             ),
             pok_sbt_ttl: Some(settings.pok_sbt_ttl),
+            ignore_kudos_sender_upvotes: Some(settings.ignore_kudos_sender_upvotes),
+            max_reciprocal_upvotes_per_period: Some(settings.max_reciprocal_upvotes_per_period),
+            reciprocal_upvotes_period: Some(settings.reciprocal_upvotes_period),
+            min_upvoter_iah_sbt_age: Some(settings.min_upvoter_iah_sbt_age),
+            min_number_of_reports_to_hide_content: Some(
+                settings.min_number_of_reports_to_hide_content,
+            ),
+            max_kudos_per_window: Some(settings.max_kudos_per_window),
+            max_dings_per_window: Some(settings.max_dings_per_window),
+            max_upvotes_per_window: Some(settings.max_upvotes_per_window),
+            max_comments_per_window: Some(settings.max_comments_per_window),
+            rate_limit_window: Some(settings.rate_limit_window),
+            humanity_cache_ttl: Some(settings.humanity_cache_ttl),
+            humanity_policy: Some(settings.humanity_policy),
+            social_db_layout: Some(settings.social_db_layout),
+            max_number_of_mentions_per_kudos: Some(settings.max_number_of_mentions_per_kudos),
+            hashtag_separator: Some(settings.hashtag_separator),
+            hashtag_mode: Some(settings.hashtag_mode),
+            min_ding_message_length: Some(settings.min_ding_message_length),
+            ding_upvotes_quorum: Some(settings.ding_upvotes_quorum),
+            ding_rate_limit_window: Some(settings.ding_rate_limit_window),
+            season_snapshot_size: Some(settings.season_snapshot_size),
+            max_comment_thread_depth: Some(settings.max_comment_thread_depth),
+            comment_reactions: Some(settings.comment_reactions),
+            commentary_encoding: Some(settings.commentary_encoding),
+            max_number_of_attachments_per_kudos: Some(settings.max_number_of_attachments_per_kudos),
+            allowed_cid_codecs: Some(settings.allowed_cid_codecs),
+            allowed_cid_multihashes: Some(settings.allowed_cid_multihashes),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{settings::Settings, Hashtag};
+    use crate::{
+        settings::Settings, CidMultihash, Hashtag, HashtagMode, HashtagSeparator, KudosAttachment,
+        KudosError, WrappedCid,
+    };
     use assert_matches::assert_matches;
 
+    fn hashtags(hashtags: &[&str]) -> Vec<String> {
+        hashtags.iter().map(|ht| ht.to_string()).collect()
+    }
+
     #[test]
     fn test_validate_hashtags() {
         let settings = Settings::default();
-        assert_matches!(settings.validate_hashtags(None), Ok(None));
-        assert_matches!(settings.validate_hashtags(Some(&[])), Ok(_));
+        assert_matches!(settings.validate_hashtags(None, |_| false), Ok(None));
+        assert_matches!(settings.validate_hashtags(Some(&[]), |_| false), Ok(_));
         assert_eq!(
-            settings.validate_hashtags(Some(vec!["abc".to_owned(), "1Def".to_owned()].as_slice())),
+            settings.validate_hashtags(Some(&hashtags(&["abc", "1Def"])), |_| false),
             Ok(Some(vec![
                 Hashtag::new_unchecked("abc"),
-                Hashtag::new_unchecked("1Def")
+                Hashtag::new_unchecked("1def")
             ]))
         );
         assert_matches!(
-            settings.validate_hashtags(Some(vec!["abc".to_owned(), "@ABC".to_owned()].as_slice())),
+            settings.validate_hashtags(Some(&hashtags(&["abc", "@ABC"])), |_| false),
             Err(_)
         );
         assert_matches!(
-            settings.validate_hashtags(Some(vec!["a".repeat(33)].as_slice())),
+            settings.validate_hashtags(Some(vec!["a".repeat(33)].as_slice()), |_| false),
             Err(_)
         );
+
+        // Hashtags are canonicalized and deduplicated
+        let settings = Settings {
+            hashtag_separator: HashtagSeparator::Underscore,
+            ..Settings::default()
+        };
+        assert_eq!(
+            settings.validate_hashtags(Some(&hashtags(&["Love-NDC", "love-ndc", "ndc"])), |_| {
+                false
+            }),
+            Ok(Some(vec![
+                Hashtag::new_unchecked("love_ndc"),
+                Hashtag::new_unchecked("ndc")
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_validate_curated_hashtags() {
+        let is_curated = |hashtag: &Hashtag| hashtag == &Hashtag::new_unchecked("ndc");
+
+        let settings = Settings {
+            hashtag_mode: HashtagMode::CuratedOnly,
+            ..Settings::default()
+        };
+        assert_matches!(settings.validate_hashtags(None, is_curated), Ok(None));
+        assert_matches!(
+            settings.validate_hashtags(Some(&hashtags(&["NDC"])), is_curated),
+            Ok(Some(_))
+        );
+        assert_eq!(
+            settings.validate_hashtags(Some(&hashtags(&["ndc", "other"])), is_curated),
+            Err(KudosError::HashtagNotCurated {
+                hashtag: "other".to_owned()
+            })
+        );
+
+        let settings = Settings {
+            hashtag_mode: HashtagMode::CuratedRequired,
+            ..Settings::default()
+        };
+        assert_matches!(
+            settings.validate_hashtags(Some(&hashtags(&["ndc", "other"])), is_curated),
+            Ok(Some(_))
+        );
+        assert_eq!(
+            settings.validate_hashtags(Some(&hashtags(&["other"])), is_curated),
+            Err(KudosError::CuratedHashtagRequired)
+        );
+        assert_eq!(
+            settings.validate_hashtags(None, is_curated),
+            Err(KudosError::CuratedHashtagRequired)
+        );
+    }
+
+    #[test]
+    fn test_validate_reaction() {
+        let settings = Settings {
+            comment_reactions: vec!["👍".to_owned(), "too long reaction text".to_owned()],
+            ..Settings::default()
+        };
+
+        assert_eq!(settings.validate_reaction("👍"), Ok(()));
+        assert_eq!(
+            settings.validate_reaction("🎉"),
+            Err(KudosError::InvalidReaction)
+        );
+        // Reactions longer than deposit was computed for are not allowed even if configured
+        assert_eq!(
+            settings.validate_reaction("too long reaction text"),
+            Err(KudosError::InvalidReaction)
+        );
+    }
+
+    #[test]
+    fn test_validate_cid() {
+        let attachment = |cid: &str| KudosAttachment {
+            cid: WrappedCid::new(cid).unwrap(),
+            mime_type: "image/png".to_owned(),
+            caption: None,
+        };
+        // CIDv1 with dag-cbor codec and CIDv1 with raw codec and blake3 multihash
+        let dag_cbor_cid = "bafyreiaaaebagbafaydqqcikbmga2dqpcaireeyuculbogazdinryhi6d4";
+        let blake3_cid = "bafkr4iaaaebagbafaydqqcikbmga2dqpcaireeyuculbogazdinryhi6d4";
+
+        let settings = Settings::default();
+        assert_eq!(
+            settings.validate_attachments(&[
+                attachment("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"),
+                attachment("bafkreieq5jui4j25lacwomsqgjeswwl3y5zcdrresptwgmfylxo2depppq"),
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            settings.validate_cid(&WrappedCid::new(dag_cbor_cid).unwrap()),
+            Err(KudosError::CidNotAllowed)
+        );
+        assert_eq!(
+            settings.validate_attachments(&[attachment(blake3_cid)]),
+            Err(KudosError::CidNotAllowed)
+        );
+
+        let settings = Settings {
+            allowed_cid_multihashes: vec![CidMultihash::Blake3],
+            ..Settings::default()
+        };
+        assert_eq!(
+            settings.validate_attachments(&[attachment(blake3_cid)]),
+            Ok(())
+        );
     }
 }
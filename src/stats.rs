@@ -0,0 +1,104 @@
+use crate::types::{AccountStats, AccountStatsView, KudosKind, LeaderboardPosition};
+use crate::Contract;
+use near_sdk::AccountId;
+
+impl Contract {
+    /// Update activity counters [`AccountStats`] of a user [`AccountId`] and user's position in
+    /// a leaderboard [`LeaderboardPosition`].
+    ///
+    /// Storage of activity counters and a leaderboard is paid by this contract.
+    fn update_account_stats<F>(&mut self, account_id: &AccountId, update: F)
+    where
+        F: FnOnce(&mut AccountStats),
+    {
+        let stats = self.account_stats.entry(account_id.clone()).or_default();
+        let position = LeaderboardPosition::new(account_id, stats);
+        update(stats);
+        let updated_position = LeaderboardPosition::new(account_id, stats);
+
+        if position != updated_position {
+            if let Some(position) = position {
+                self.leaderboard.remove(&position);
+            }
+            if let Some(position) = updated_position {
+                self.leaderboard.insert(&position, &());
+            }
+        }
+    }
+
+    /// Count kudos of provided [`KudosKind`] given by a sender to a receiver
+    pub(crate) fn record_kudos_stats(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        kind: &KudosKind,
+    ) {
+        let is_ding = kind == &KudosKind::Ding;
+
+        self.update_account_stats(sender_id, |stats| {
+            let given = if is_ding {
+                &mut stats.dings_given
+            } else {
+                &mut stats.kudos_given
+            };
+            *given = given.saturating_add(1);
+        });
+        self.update_account_stats(receiver_id, |stats| {
+            let received = if is_ding {
+                &mut stats.dings_received
+            } else {
+                &mut stats.kudos_received
+            };
+            *received = received.saturating_add(1);
+        });
+    }
+
+    /// Count an upvote given by an upvoter to kudos of a receiver
+    pub(crate) fn record_upvote_stats(&mut self, upvoter_id: &AccountId, receiver_id: &AccountId) {
+        self.update_account_stats(upvoter_id, |stats| {
+            stats.upvotes_given = stats.upvotes_given.saturating_add(1);
+        });
+        self.update_account_stats(receiver_id, |stats| {
+            stats.upvotes_received = stats.upvotes_received.saturating_add(1);
+        });
+    }
+
+    /// Count a commentary message left by a sender to kudos of a receiver. Commentary messages left by
+    /// a receiver to own kudos are not counted as received.
+    pub(crate) fn record_comment_stats(&mut self, sender_id: &AccountId, receiver_id: &AccountId) {
+        self.update_account_stats(sender_id, |stats| {
+            stats.comments_given = stats.comments_given.saturating_add(1);
+        });
+
+        if sender_id != receiver_id {
+            self.update_account_stats(receiver_id, |stats| {
+                stats.comments_received = stats.comments_received.saturating_add(1);
+            });
+        }
+    }
+
+    /// Count ProofOfKudos SBTs minted for a user
+    pub(crate) fn record_pok_sbts_stats(&mut self, account_id: &AccountId, minted: usize) {
+        self.update_account_stats(account_id, |stats| {
+            stats.pok_sbts = stats.pok_sbts.saturating_add(minted as u64);
+        });
+    }
+
+    /// Return users [`AccountStatsView`] ordered by a number of received kudos, users without received
+    /// kudos are skipped
+    pub(crate) fn top_receivers(&self, from_index: usize, limit: usize) -> Vec<AccountStatsView> {
+        self.leaderboard
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .filter_map(|(position, _)| {
+                self.account_stats
+                    .get(&position.account_id)
+                    .map(|stats| AccountStatsView {
+                        account_id: position.account_id,
+                        stats: stats.clone(),
+                    })
+            })
+            .collect()
+    }
+}
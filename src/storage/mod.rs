@@ -0,0 +1,248 @@
+mod on_chain;
+mod records;
+mod social_db;
+
+pub use on_chain::*;
+pub use records::*;
+pub use social_db::*;
+
+use crate::types::{KudosId, KudosStatus, ReportedContent};
+use crate::{
+    CommentId, EncodedCommentary, Hashtag, KudosAttachment, KudosError, KudosKind, WrappedCid,
+};
+use near_sdk::json_types::U64;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Balance, Gas, Promise};
+
+/// Kudos given by a sender to a receiver, which should be saved to a storage backend
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NewKudos {
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    pub kudos_id: KudosId,
+    /// The timestamp in milliseconds when kudos was given
+    pub created_at: U64,
+    pub kind: KudosKind,
+    pub message: String,
+    pub icon_cid: Option<WrappedCid>,
+    pub attachments: Vec<KudosAttachment>,
+    pub hashtags: Option<Vec<Hashtag>>,
+    /// Users mentioned in the message
+    pub mentions: Vec<AccountId>,
+    /// Initial kudos status, only set for dings which should be accepted by receiver
+    pub status: Option<KudosStatus>,
+}
+
+/// Field of a stored kudos which could be read from a storage backend
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum KudosField {
+    /// All top-level kudos fields, e.g. sender, message, etc.
+    Info,
+    SenderId,
+    Kind,
+    CreatedAt,
+    Upvotes,
+    Comment(CommentId),
+    /// All commentary messages of kudos
+    Comments,
+}
+
+/// Storage backend used to persist kudos, upvotes and commentary messages.
+///
+/// Every operation returns a [`Promise`], so contract callbacks handle results of all backends the same way.
+/// Kudos are read as JSON [`Value`](near_sdk::serde_json::Value) in NEAR social db layout, e.g.
+/// "{root_id}/kudos/{receiver_id}/{kudos_id}/sender_id", regardless of a backend.
+pub trait KudosStorage {
+    /// Save kudos given by a sender to a receiver
+    fn save_kudos(
+        &self,
+        kudos: NewKudos,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError>;
+
+    /// Add upvote of a user to kudos
+    fn add_upvote(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        upvoter_id: &AccountId,
+        status: Option<&KudosStatus>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError>;
+
+    /// Add encoded commentary message to kudos, a sender of a parent commentary message
+    /// is provided to be notified about a reply
+    fn add_comment(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        comment: &EncodedCommentary,
+        parent_comment_sender_id: Option<&AccountId>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError>;
+
+    /// Set a reaction of a user to a commentary message of kudos
+    fn add_comment_reaction(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        sender_id: &AccountId,
+        reaction: &str,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError>;
+
+    /// Update status of kudos set by its receiver
+    fn set_kudos_status(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        status: &KudosStatus,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError>;
+
+    /// Set or remove moderation flag of kudos or commentary message
+    fn set_content_hidden(
+        &self,
+        content: &ReportedContent,
+        hidden: bool,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError>;
+
+    /// Read fields of kudos. Missing kudos or fields are not present in the result.
+    fn read_kudos(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        fields: &[KudosField],
+        gas: Gas,
+    ) -> Promise;
+}
+
+/// Storage backend configured for this contract. Passed to callbacks, so every step of an action uses
+/// the same backend.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum StorageBackend {
+    SocialDb(SocialDbStorage),
+    OnChain(OnChainStorage),
+}
+
+impl StorageBackend {
+    fn inner(&self) -> &dyn KudosStorage {
+        match self {
+            Self::SocialDb(storage) => storage,
+            Self::OnChain(storage) => storage,
+        }
+    }
+}
+
+impl KudosStorage for StorageBackend {
+    fn save_kudos(
+        &self,
+        kudos: NewKudos,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        self.inner().save_kudos(kudos, deposit, gas)
+    }
+
+    fn add_upvote(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        upvoter_id: &AccountId,
+        status: Option<&KudosStatus>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        self.inner()
+            .add_upvote(receiver_id, kudos_id, upvoter_id, status, deposit, gas)
+    }
+
+    fn add_comment(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        comment: &EncodedCommentary,
+        parent_comment_sender_id: Option<&AccountId>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        self.inner().add_comment(
+            receiver_id,
+            kudos_id,
+            comment_id,
+            comment,
+            parent_comment_sender_id,
+            deposit,
+            gas,
+        )
+    }
+
+    fn add_comment_reaction(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        sender_id: &AccountId,
+        reaction: &str,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        self.inner().add_comment_reaction(
+            receiver_id,
+            kudos_id,
+            comment_id,
+            sender_id,
+            reaction,
+            deposit,
+            gas,
+        )
+    }
+
+    fn set_kudos_status(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        status: &KudosStatus,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        self.inner()
+            .set_kudos_status(receiver_id, kudos_id, status, deposit, gas)
+    }
+
+    fn set_content_hidden(
+        &self,
+        content: &ReportedContent,
+        hidden: bool,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        self.inner()
+            .set_content_hidden(content, hidden, deposit, gas)
+    }
+
+    fn read_kudos(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        fields: &[KudosField],
+        gas: Gas,
+    ) -> Promise {
+        self.inner().read_kudos(receiver_id, kudos_id, fields, gas)
+    }
+}
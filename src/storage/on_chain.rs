@@ -0,0 +1,578 @@
+use super::records::{FLAG_SET, SET_MEMBER};
+use super::{
+    AttachmentRecord, KudosField, KudosRecord, KudosStorage, KudosTags, NewKudos, SocialDbWrite,
+};
+use crate::types::{KudosId, KudosStatus, ReportedContent, SocialDbLayout, StorageKey};
+use crate::{CommentId, EncodedCommentary, Hashtag, KudosError, KudosKind};
+use crate::{Contract, ContractExt};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{json, Value};
+use near_sdk::store::{UnorderedMap, UnorderedSet};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Gas, Promise};
+use std::collections::BTreeMap;
+
+/// Storage backend which persists kudos in the state of this contract, so NEAR social db is not required.
+///
+/// Kudos are saved and read by calling this contract, so every operation is still a [`Promise`].
+/// Storage is paid by this contract, deposits attached to actions are kept by this contract.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct OnChainStorage;
+
+/// Kudos stored in the state of this contract by [`OnChainStorage`], versioned to allow layout changes
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub enum VStoredKudos {
+    // Add old versions here, keep ordering, the oldest on top, most recent at bottom
+    Current(StoredKudos), // most recent version
+}
+
+/// Kudos stored in the state of this contract by [`OnChainStorage`].
+///
+/// Upvotes and commentary messages are stored under separate keys, so storing them doesn't rewrite kudos.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct StoredKudos {
+    pub sender_id: AccountId,
+    pub kind: KudosKind,
+    pub message: String,
+    pub icon: Option<String>,
+    /// The timestamp in milliseconds when kudos was given
+    pub created_at: u64,
+    pub status: Option<KudosStatus>,
+    pub attachments: Vec<StoredAttachment>,
+    pub tags: Vec<Hashtag>,
+    pub hidden: bool,
+    pub upvotes: UnorderedSet<AccountId>,
+    pub comments: UnorderedMap<CommentId, VStoredComment>,
+}
+
+/// Attachment of kudos stored in the state of this contract by [`OnChainStorage`]
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct StoredAttachment {
+    pub cid: String,
+    pub mime_type: String,
+    pub caption: Option<String>,
+}
+
+/// Commentary message stored in the state of this contract by [`OnChainStorage`], versioned to allow
+/// layout changes
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub enum VStoredComment {
+    // Add old versions here, keep ordering, the oldest on top, most recent at bottom
+    Current(StoredComment), // most recent version
+}
+
+/// Commentary message stored in the state of this contract by [`OnChainStorage`]
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct StoredComment {
+    pub comment: EncodedCommentary,
+    pub hidden: bool,
+    /// Reactions of users to a commentary message, a single reaction per user
+    pub reactions: UnorderedMap<AccountId, String>,
+}
+
+impl VStoredKudos {
+    /// Return kudos of the current version
+    pub fn as_current(&self) -> &StoredKudos {
+        match self {
+            Self::Current(kudos) => kudos,
+        }
+    }
+
+    /// Return mutable kudos of the current version
+    pub fn as_current_mut(&mut self) -> &mut StoredKudos {
+        match self {
+            Self::Current(kudos) => kudos,
+        }
+    }
+}
+
+impl From<StoredKudos> for VStoredKudos {
+    fn from(kudos: StoredKudos) -> Self {
+        Self::Current(kudos)
+    }
+}
+
+impl VStoredComment {
+    /// Return commentary message of the current version
+    pub fn as_current(&self) -> &StoredComment {
+        match self {
+            Self::Current(comment) => comment,
+        }
+    }
+
+    /// Return mutable commentary message of the current version
+    pub fn as_current_mut(&mut self) -> &mut StoredComment {
+        match self {
+            Self::Current(comment) => comment,
+        }
+    }
+}
+
+impl From<StoredComment> for VStoredComment {
+    fn from(comment: StoredComment) -> Self {
+        Self::Current(comment)
+    }
+}
+
+impl StoredKudos {
+    /// Return kudos as [`KudosRecord`] in NEAR social db layout, the same as it's written by
+    /// [`build_give_kudos_request`](crate::utils::build_give_kudos_request) and subsequent updates
+    pub fn to_record(&self) -> KudosRecord<'_> {
+        let comments = self
+            .comments
+            .iter()
+            .map(|(comment_id, comment)| (comment_id, comment.as_current()))
+            .collect::<BTreeMap<_, _>>();
+        let hidden_comments = comments
+            .iter()
+            .filter(|(_, c)| c.hidden)
+            .map(|(&comment_id, _)| (comment_id, Some(FLAG_SET)))
+            .collect::<BTreeMap<_, _>>();
+        let comments_reactions = comments
+            .iter()
+            .filter(|(_, c)| !c.reactions.is_empty())
+            .map(|(&comment_id, c)| {
+                let reactions = c
+                    .reactions
+                    .iter()
+                    .map(|(sender_id, reaction)| (sender_id, reaction.as_str()))
+                    .collect();
+
+                (comment_id, reactions)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        KudosRecord {
+            created_at: Some(self.created_at.into()),
+            sender_id: Some(&self.sender_id),
+            kind: Some(&self.kind),
+            message: Some(&self.message),
+            icon: Some(self.icon.clone().unwrap_or_default()),
+            status: self.status.as_ref(),
+            attachments: (!self.attachments.is_empty()).then(|| {
+                self.attachments
+                    .iter()
+                    .map(|attachment| AttachmentRecord {
+                        cid: attachment.cid.clone(),
+                        mime_type: &attachment.mime_type,
+                        caption: attachment.caption.as_deref(),
+                    })
+                    .enumerate()
+                    .collect()
+            }),
+            upvotes: (!self.upvotes.is_empty()).then(|| {
+                self.upvotes
+                    .iter()
+                    .map(|upvoter_id| (upvoter_id, SET_MEMBER))
+                    .collect()
+            }),
+            comments: (!comments.is_empty()).then(|| {
+                comments
+                    .iter()
+                    .map(|(&comment_id, c)| (comment_id, &c.comment))
+                    .collect()
+            }),
+            tags: Some(KudosTags {
+                hashtags: &self.tags,
+                layout: SocialDbLayout::Compat,
+            }),
+            hidden: self.hidden.then_some(Some(FLAG_SET)),
+            hidden_comments: (!hidden_comments.is_empty()).then_some(hidden_comments),
+            comments_reactions: (!comments_reactions.is_empty()).then_some(comments_reactions),
+        }
+    }
+
+    /// Return only requested fields [`KudosField`] of kudos as [`KudosRecord`] in NEAR social db layout, the
+    /// same as they are read by [`SocialDbStorage`](super::SocialDbStorage), so upvotes and commentary messages
+    /// are not loaded unless requested
+    pub fn to_fields_record<'a>(&'a self, fields: &'a [KudosField]) -> KudosRecord<'a> {
+        let mut record = KudosRecord::default();
+
+        for field in fields {
+            match field {
+                KudosField::Info => {
+                    record.created_at = Some(self.created_at.into());
+                    record.sender_id = Some(&self.sender_id);
+                    record.kind = Some(&self.kind);
+                    record.message = Some(&self.message);
+                    record.icon = Some(self.icon.clone().unwrap_or_default());
+                    record.status = self.status.as_ref();
+                    record.tags = Some(KudosTags {
+                        hashtags: &self.tags,
+                        layout: SocialDbLayout::Compat,
+                    });
+                    record.hidden = self.hidden.then_some(Some(FLAG_SET));
+                }
+                KudosField::SenderId => record.sender_id = Some(&self.sender_id),
+                KudosField::Kind => record.kind = Some(&self.kind),
+                KudosField::CreatedAt => record.created_at = Some(self.created_at.into()),
+                KudosField::Upvotes => {
+                    record.upvotes = (!self.upvotes.is_empty()).then(|| {
+                        self.upvotes
+                            .iter()
+                            .map(|upvoter_id| (upvoter_id, SET_MEMBER))
+                            .collect()
+                    })
+                }
+                KudosField::Comment(comment_id) => {
+                    if let Some(comment) = self.comments.get(comment_id) {
+                        record
+                            .comments
+                            .get_or_insert_with(BTreeMap::new)
+                            .insert(comment_id, &comment.as_current().comment);
+                    }
+                }
+                KudosField::Comments => {
+                    if !self.comments.is_empty() {
+                        record.comments.get_or_insert_with(BTreeMap::new).extend(
+                            self.comments
+                                .iter()
+                                .map(|(comment_id, c)| (comment_id, &c.as_current().comment)),
+                        );
+                    }
+                }
+            }
+        }
+
+        record
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn store_kudos(&mut self, kudos: NewKudos) {
+        let NewKudos {
+            sender_id,
+            receiver_id,
+            kudos_id,
+            created_at,
+            kind,
+            message,
+            icon_cid,
+            attachments,
+            hashtags,
+            mentions: _,
+            status,
+        } = kudos;
+
+        let stored_kudos = StoredKudos {
+            sender_id,
+            kind,
+            message,
+            icon: icon_cid.map(|cid| cid.to_string()),
+            created_at: created_at.0,
+            status,
+            attachments: attachments
+                .into_iter()
+                .map(|attachment| StoredAttachment {
+                    cid: attachment.cid.to_string(),
+                    mime_type: attachment.mime_type,
+                    caption: attachment.caption,
+                })
+                .collect(),
+            tags: hashtags.unwrap_or_default(),
+            hidden: false,
+            upvotes: UnorderedSet::new(StorageKey::StoredUpvotesOf {
+                kudos_id: kudos_id.clone(),
+            }),
+            comments: UnorderedMap::new(StorageKey::StoredCommentsOf {
+                kudos_id: kudos_id.clone(),
+            }),
+        };
+
+        self.stored_kudos
+            .insert((receiver_id, kudos_id), stored_kudos.into());
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn store_upvote(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        upvoter_id: AccountId,
+        status: Option<KudosStatus>,
+    ) -> Result<(), KudosError> {
+        let kudos = self.stored_kudos_mut(receiver_id, kudos_id)?;
+
+        kudos.upvotes.insert(upvoter_id);
+        if status.is_some() {
+            kudos.status = status;
+        }
+
+        Ok(())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn store_comment(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+        comment: EncodedCommentary,
+    ) -> Result<(), KudosError> {
+        let reactions = UnorderedMap::new(StorageKey::StoredReactionsOf {
+            kudos_id: kudos_id.clone(),
+            comment_id: comment_id.clone(),
+        });
+
+        self.stored_kudos_mut(receiver_id, kudos_id)?
+            .comments
+            .insert(
+                comment_id,
+                StoredComment {
+                    comment,
+                    hidden: false,
+                    reactions,
+                }
+                .into(),
+            );
+
+        Ok(())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn store_comment_reaction(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        comment_id: CommentId,
+        sender_id: AccountId,
+        reaction: String,
+    ) -> Result<(), KudosError> {
+        self.stored_kudos_mut(receiver_id, kudos_id)?
+            .comments
+            .get_mut(&comment_id)
+            .ok_or(KudosError::CommentNotFound)?
+            .as_current_mut()
+            .reactions
+            .insert(sender_id, reaction);
+
+        Ok(())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn store_kudos_status(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        status: KudosStatus,
+    ) -> Result<(), KudosError> {
+        self.stored_kudos_mut(receiver_id, kudos_id)?.status = Some(status);
+
+        Ok(())
+    }
+
+    #[private]
+    #[handle_result]
+    pub fn store_content_hidden(
+        &mut self,
+        content: ReportedContent,
+        hidden: bool,
+    ) -> Result<(), KudosError> {
+        let ReportedContent {
+            receiver_id,
+            kudos_id,
+            comment_id,
+        } = content;
+        let kudos = self.stored_kudos_mut(receiver_id, kudos_id)?;
+
+        match comment_id {
+            Some(comment_id) => {
+                kudos
+                    .comments
+                    .get_mut(&comment_id)
+                    .ok_or(KudosError::ReportedContentNotFound)?
+                    .as_current_mut()
+                    .hidden = hidden
+            }
+            None => kudos.hidden = hidden,
+        }
+
+        Ok(())
+    }
+
+    /// Public view method to read kudos stored on-chain as JSON [`Value`] in NEAR social db layout,
+    /// e.g. "{root_id}/kudos/{receiver_id}/{kudos_id}/sender_id". Returns an empty object if kudos not found.
+    #[handle_result]
+    pub fn view_kudos(
+        &self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+    ) -> Result<Value, KudosError> {
+        let Some(kudos) = self
+            .stored_kudos
+            .get(&(receiver_id.clone(), kudos_id.clone()))
+        else {
+            return Ok(json!({}));
+        };
+
+        SocialDbWrite::kudos(
+            &env::current_account_id(),
+            &receiver_id,
+            &kudos_id,
+            kudos.as_current().to_record(),
+        )
+        .into_json()
+    }
+
+    /// Public view method to read only requested fields [`KudosField`] of kudos stored on-chain as JSON
+    /// [`Value`] in NEAR social db layout. Returns an empty object if kudos not found.
+    #[handle_result]
+    pub fn view_kudos_fields(
+        &self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+        fields: Vec<KudosField>,
+    ) -> Result<Value, KudosError> {
+        let Some(kudos) = self
+            .stored_kudos
+            .get(&(receiver_id.clone(), kudos_id.clone()))
+        else {
+            return Ok(json!({}));
+        };
+
+        SocialDbWrite::kudos(
+            &env::current_account_id(),
+            &receiver_id,
+            &kudos_id,
+            kudos.as_current().to_fields_record(&fields),
+        )
+        .into_json()
+    }
+}
+
+impl Contract {
+    fn stored_kudos_mut(
+        &mut self,
+        receiver_id: AccountId,
+        kudos_id: KudosId,
+    ) -> Result<&mut StoredKudos, KudosError> {
+        self.stored_kudos
+            .get_mut(&(receiver_id, kudos_id))
+            .map(VStoredKudos::as_current_mut)
+            .ok_or(KudosError::KudosNotFound)
+    }
+}
+
+impl KudosStorage for OnChainStorage {
+    fn save_kudos(
+        &self,
+        kudos: NewKudos,
+        _deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        Ok(Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .store_kudos(kudos))
+    }
+
+    fn add_upvote(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        upvoter_id: &AccountId,
+        status: Option<&KudosStatus>,
+        _deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        Ok(Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .store_upvote(
+                receiver_id.clone(),
+                kudos_id.clone(),
+                upvoter_id.clone(),
+                status.cloned(),
+            ))
+    }
+
+    fn add_comment(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        comment: &EncodedCommentary,
+        _parent_comment_sender_id: Option<&AccountId>,
+        _deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        Ok(Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .store_comment(
+                receiver_id.clone(),
+                kudos_id.clone(),
+                comment_id.clone(),
+                comment.clone(),
+            ))
+    }
+
+    fn add_comment_reaction(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        sender_id: &AccountId,
+        reaction: &str,
+        _deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        Ok(Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .store_comment_reaction(
+                receiver_id.clone(),
+                kudos_id.clone(),
+                comment_id.clone(),
+                sender_id.clone(),
+                reaction.to_owned(),
+            ))
+    }
+
+    fn set_kudos_status(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        status: &KudosStatus,
+        _deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        Ok(Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .store_kudos_status(receiver_id.clone(), kudos_id.clone(), status.clone()))
+    }
+
+    fn set_content_hidden(
+        &self,
+        content: &ReportedContent,
+        hidden: bool,
+        _deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        Ok(Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .store_content_hidden(content.clone(), hidden))
+    }
+
+    /// Kudos is read entirely, regardless of requested fields
+    fn read_kudos(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        fields: &[KudosField],
+        gas: Gas,
+    ) -> Promise {
+        Contract::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .view_kudos_fields(receiver_id.clone(), kudos_id.clone(), fields.to_vec())
+    }
+}
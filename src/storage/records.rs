@@ -0,0 +1,394 @@
+use super::NewKudos;
+use crate::types::{KudosId, KudosStatus, SocialDbLayout};
+use crate::{CommentId, EncodedCommentary, Hashtag, KudosError, KudosKind};
+use near_sdk::json_types::U64;
+use near_sdk::serde::{ser, Serialize, Serializer};
+use near_sdk::serde_json::{self, Value};
+use near_sdk::AccountId;
+use std::collections::BTreeMap;
+
+/// Value stored in NEAR social db for members of a set, e.g. upvoters of kudos
+pub(super) const SET_MEMBER: &str = "";
+/// Value stored in NEAR social db for a set flag, e.g. hidden kudos
+pub(super) const FLAG_SET: &str = "true";
+
+/// Kudos data written to NEAR social db under "{root_id}/kudos/{receiver_id}/{kudos_id}".
+///
+/// Only provided fields are written, so the same record is used to save kudos and to update it later,
+/// e.g. to add an upvote or a commentary message.
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct KudosRecord<'a> {
+    /// The timestamp in milliseconds when kudos was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_id: Option<&'a AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<&'a KudosKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<&'a KudosStatus>,
+    /// Attachments of kudos by their index, written only if kudos has attachments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<BTreeMap<usize, AttachmentRecord<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upvotes: Option<BTreeMap<&'a AccountId, &'static str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<BTreeMap<&'a CommentId, &'a EncodedCommentary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<KudosTags<'a>>,
+    /// Moderation flag of kudos, `Some(None)` removes a stored flag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<Option<&'static str>>,
+    /// Moderation flags of commentary messages, `None` removes a stored flag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden_comments: Option<BTreeMap<&'a CommentId, Option<&'static str>>>,
+    /// Reactions to commentary messages by users, e.g. "comments_reactions/{comment_id}/{sender_id}"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments_reactions: Option<BTreeMap<&'a CommentId, BTreeMap<&'a AccountId, &'a str>>>,
+}
+
+impl<'a> KudosRecord<'a> {
+    /// Return a record of given kudos [`NewKudos`] with empty upvotes and commentary messages
+    pub fn new(kudos: &'a NewKudos, layout: SocialDbLayout) -> Self {
+        let icon = kudos.icon_cid.as_ref().map(|cid| cid.to_string());
+
+        Self {
+            created_at: Some(kudos.created_at),
+            sender_id: Some(&kudos.sender_id),
+            kind: Some(&kudos.kind),
+            message: Some(&kudos.message),
+            icon: match layout {
+                SocialDbLayout::Compat => Some(icon.unwrap_or_default()),
+                SocialDbLayout::V2 => icon,
+            },
+            status: kudos.status.as_ref(),
+            attachments: (!kudos.attachments.is_empty()).then(|| {
+                kudos
+                    .attachments
+                    .iter()
+                    .map(|attachment| AttachmentRecord {
+                        cid: attachment.cid.to_string(),
+                        mime_type: &attachment.mime_type,
+                        caption: attachment.caption.as_deref(),
+                    })
+                    .enumerate()
+                    .collect()
+            }),
+            upvotes: Some(BTreeMap::new()),
+            comments: Some(BTreeMap::new()),
+            tags: Some(KudosTags {
+                hashtags: kudos.hashtags.as_deref().unwrap_or_default(),
+                layout,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Return a record which adds an upvote of a user [`AccountId`] and optionally updates kudos status
+    /// [`KudosStatus`], e.g. when an upvote reaches a quorum of a proposed ding
+    pub fn upvote(upvoter_id: &'a AccountId, status: Option<&'a KudosStatus>) -> Self {
+        Self {
+            upvotes: Some(BTreeMap::from([(upvoter_id, SET_MEMBER)])),
+            status,
+            ..Default::default()
+        }
+    }
+
+    /// Return a record which adds encoded commentary message [`EncodedCommentary`]
+    pub fn comment(comment_id: &'a CommentId, comment: &'a EncodedCommentary) -> Self {
+        Self {
+            comments: Some(BTreeMap::from([(comment_id, comment)])),
+            ..Default::default()
+        }
+    }
+
+    /// Return a record which sets a reaction of a user [`AccountId`] to a commentary message, a previous
+    /// reaction of the same user is replaced
+    pub fn reaction(
+        comment_id: &'a CommentId,
+        sender_id: &'a AccountId,
+        reaction: &'a str,
+    ) -> Self {
+        Self {
+            comments_reactions: Some(BTreeMap::from([(
+                comment_id,
+                BTreeMap::from([(sender_id, reaction)]),
+            )])),
+            ..Default::default()
+        }
+    }
+
+    /// Return a record which updates kudos status [`KudosStatus`]
+    pub fn status(status: &'a KudosStatus) -> Self {
+        Self {
+            status: Some(status),
+            ..Default::default()
+        }
+    }
+
+    /// Return a record which sets or removes moderation flag of kudos or its commentary message
+    pub fn hidden(comment_id: Option<&'a CommentId>, hidden: bool) -> Self {
+        let flag = hidden.then_some(FLAG_SET);
+
+        match comment_id {
+            Some(comment_id) => Self {
+                hidden_comments: Some(BTreeMap::from([(comment_id, flag)])),
+                ..Default::default()
+            },
+            None => Self {
+                hidden: Some(flag),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Attachment of kudos written to NEAR social db under "attachments/{index}"
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttachmentRecord<'a> {
+    pub cid: String,
+    pub mime_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<&'a str>,
+}
+
+/// Hashtags of kudos serialized according to [`SocialDbLayout`]
+pub struct KudosTags<'a> {
+    pub hashtags: &'a [Hashtag],
+    pub layout: SocialDbLayout,
+}
+
+impl Serialize for KudosTags<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.layout {
+            SocialDbLayout::Compat => serializer
+                .serialize_str(&serde_json::to_string(self.hashtags).map_err(ser::Error::custom)?),
+            SocialDbLayout::V2 => {
+                serializer.collect_map(self.hashtags.iter().map(|hashtag| (hashtag, SET_MEMBER)))
+            }
+        }
+    }
+}
+
+/// Data written to NEAR social db by this contract.
+///
+/// Example of JSON output:
+/// ```json
+/// {
+///   "kudos.near": {
+///     "kudos": {
+///       "some_user.near": {
+///         "1": { ... }
+///       }
+///     },
+///     "hashtags": {
+///       "firstkudos": {
+///         "1": "some_user.near"
+///       }
+///     },
+///     "mentions": {
+///       "alex.near": {
+///         "1": "some_user.near"
+///       }
+///     },
+///     "index": {
+///       "notify": "[{\"key\":\"some_user.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/some_user.near/1\"}}}]"
+///     }
+///   }
+/// }
+/// ```
+pub struct SocialDbWrite<'a> {
+    root_id: &'a AccountId,
+    receiver_id: &'a AccountId,
+    kudos_id: &'a KudosId,
+    root: SocialDbRoot<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct SocialDbRoot<'a> {
+    kudos: BTreeMap<&'a AccountId, BTreeMap<&'a KudosId, KudosRecord<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hashtags: Option<BTreeMap<&'a Hashtag, BTreeMap<&'a KudosId, &'a AccountId>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mentions: Option<BTreeMap<&'a AccountId, BTreeMap<&'a KudosId, &'a AccountId>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<SocialDbIndex<'a>>,
+}
+
+/// Standard near.social index, which values are stored as JSON serialized strings
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+struct SocialDbIndex<'a> {
+    #[serde(serialize_with = "serialize_to_string")]
+    notify: Vec<Notification<'a>>,
+}
+
+/// Type of a notification supported by near.social
+#[derive(Serialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum NotificationKind {
+    Mention,
+    Like,
+    Comment,
+}
+
+/// Notification of a user about kudos, e.g. `{ "key": "alex.near", "value": { "type": "like", ... } }`
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct Notification<'a> {
+    key: &'a AccountId,
+    value: NotificationValue<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct NotificationValue<'a> {
+    #[serde(rename = "type")]
+    kind: NotificationKind,
+    item: NotificationItem<'a>,
+}
+
+/// Item of a notification which refers to kudos path, e.g. "kudos.near/kudos/some_user.near/1".
+/// A receiver of kudos is notified with a kind of received kudos, so clients could tell kudos from dings.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+struct NotificationItem<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kudos_kind: Option<&'a KudosKind>,
+}
+
+fn serialize_to_string<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    serializer.serialize_str(&serde_json::to_string(value).map_err(ser::Error::custom)?)
+}
+
+impl<'a> SocialDbWrite<'a> {
+    /// Return data which writes kudos record [`KudosRecord`] under "{root_id}/kudos/{receiver_id}/{kudos_id}"
+    pub fn kudos(
+        root_id: &'a AccountId,
+        receiver_id: &'a AccountId,
+        kudos_id: &'a KudosId,
+        record: KudosRecord<'a>,
+    ) -> Self {
+        Self {
+            root_id,
+            receiver_id,
+            kudos_id,
+            root: SocialDbRoot {
+                kudos: BTreeMap::from([(receiver_id, BTreeMap::from([(kudos_id, record)]))]),
+                hashtags: None,
+                mentions: None,
+                index: None,
+            },
+        }
+    }
+
+    /// Additionally write relationship of hashtags to kudos and its receiver under
+    /// "{root_id}/hashtags/{hashtag}/{kudos_id}"
+    pub fn with_hashtags(
+        mut self,
+        receiver_id: &'a AccountId,
+        kudos_id: &'a KudosId,
+        hashtags: &'a [Hashtag],
+    ) -> Self {
+        self.root.hashtags = Some(
+            hashtags
+                .iter()
+                .map(|hashtag| (hashtag, BTreeMap::from([(kudos_id, receiver_id)])))
+                .collect(),
+        );
+
+        self
+    }
+
+    /// Additionally write relationship of mentioned users to kudos and its receiver under
+    /// "{root_id}/mentions/{account_id}/{kudos_id}", nothing is written if no users mentioned
+    pub fn with_mentions(
+        mut self,
+        receiver_id: &'a AccountId,
+        kudos_id: &'a KudosId,
+        mentions: &'a [AccountId],
+    ) -> Self {
+        if !mentions.is_empty() {
+            self.root.mentions = Some(
+                mentions
+                    .iter()
+                    .map(|account_id| (account_id, BTreeMap::from([(kudos_id, receiver_id)])))
+                    .collect(),
+            );
+        }
+
+        self
+    }
+
+    /// Additionally notify a user about kudos under near.social index "{root_id}/index/notify".
+    /// A user is notified only once per write.
+    pub fn notify(self, account_id: &'a AccountId, kind: NotificationKind) -> Self {
+        self.push_notification(account_id, kind, None)
+    }
+
+    /// Additionally notify a receiver about received kudos of a given kind [`KudosKind`] by a standard
+    /// `mention` notification under near.social index "{root_id}/index/notify"
+    pub fn notify_receiver(self, receiver_id: &'a AccountId, kudos_kind: &'a KudosKind) -> Self {
+        self.push_notification(receiver_id, NotificationKind::Mention, Some(kudos_kind))
+    }
+
+    fn push_notification(
+        mut self,
+        account_id: &'a AccountId,
+        kind: NotificationKind,
+        kudos_kind: Option<&'a KudosKind>,
+    ) -> Self {
+        let path = format!(
+            "{}/kudos/{}/{}",
+            self.root_id, self.receiver_id, self.kudos_id
+        );
+        let index = self.root.index.get_or_insert_with(Default::default);
+
+        if index.notify.iter().all(|n| n.key != account_id) {
+            index.notify.push(Notification {
+                key: account_id,
+                value: NotificationValue {
+                    kind,
+                    item: NotificationItem {
+                        kind: "social",
+                        path,
+                        kudos_kind,
+                    },
+                },
+            });
+        }
+
+        self
+    }
+
+    /// Return JSON [`Value`] which should be passed to `set` method of NEAR social db
+    pub fn into_json(self) -> Result<Value, KudosError> {
+        serde_json::to_value(self).map_err(|_| KudosError::SerializationFailure)
+    }
+}
+
+impl Serialize for SocialDbWrite<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map([(self.root_id, &self.root)])
+    }
+}
@@ -0,0 +1,181 @@
+use super::{KudosField, KudosStorage, NewKudos};
+use crate::external_db::ext_db;
+use crate::types::{KudosId, KudosStatus, ReportedContent, SocialDbLayout};
+use crate::utils::*;
+use crate::{CommentId, EncodedCommentary, KudosError};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::Value;
+use near_sdk::{env, AccountId, Balance, Gas, Promise};
+
+/// Storage backend which persists kudos in NEAR social db smart contract
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct SocialDbStorage {
+    /// A valid [`AccountId`] of NEAR social db smart contract
+    pub db_id: AccountId,
+    /// Layout [`SocialDbLayout`] of written kudos
+    #[serde(default)]
+    pub layout: SocialDbLayout,
+}
+
+impl SocialDbStorage {
+    pub fn new(db_id: AccountId, layout: SocialDbLayout) -> Self {
+        Self { db_id, layout }
+    }
+
+    fn set(&self, data: Value, deposit: Balance, gas: Gas) -> Promise {
+        ext_db::ext(self.db_id.clone())
+            .with_attached_deposit(deposit)
+            .with_static_gas(gas)
+            .set(data)
+    }
+}
+
+impl KudosStorage for SocialDbStorage {
+    fn save_kudos(
+        &self,
+        kudos: NewKudos,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        let kudos_json = build_give_kudos_request(&env::current_account_id(), &kudos, self.layout)?;
+
+        Ok(self.set(kudos_json, deposit, gas))
+    }
+
+    fn add_upvote(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        upvoter_id: &AccountId,
+        status: Option<&KudosStatus>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        let upvote_kudos_req = build_upvote_kudos_request(
+            &env::current_account_id(),
+            upvoter_id,
+            receiver_id,
+            kudos_id,
+            status,
+        )?;
+
+        Ok(self.set(upvote_kudos_req, deposit, gas))
+    }
+
+    fn add_comment(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        comment: &EncodedCommentary,
+        parent_comment_sender_id: Option<&AccountId>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        let leave_comment_req = build_leave_comment_request(
+            &env::current_account_id(),
+            &env::signer_account_id(),
+            receiver_id,
+            kudos_id,
+            comment_id,
+            comment,
+            parent_comment_sender_id,
+        )?;
+
+        Ok(self.set(leave_comment_req, deposit, gas))
+    }
+
+    fn add_comment_reaction(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        comment_id: &CommentId,
+        sender_id: &AccountId,
+        reaction: &str,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        let react_to_comment_req = build_react_to_comment_request(
+            &env::current_account_id(),
+            receiver_id,
+            kudos_id,
+            comment_id,
+            sender_id,
+            reaction,
+        )?;
+
+        Ok(self.set(react_to_comment_req, deposit, gas))
+    }
+
+    fn set_kudos_status(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        status: &KudosStatus,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        let update_kudos_status_req = build_update_kudos_status_request(
+            &env::current_account_id(),
+            receiver_id,
+            kudos_id,
+            status,
+        )?;
+
+        Ok(self.set(update_kudos_status_req, deposit, gas))
+    }
+
+    fn set_content_hidden(
+        &self,
+        content: &ReportedContent,
+        hidden: bool,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Promise, KudosError> {
+        let hide_content_req =
+            build_hide_content_request(&env::current_account_id(), content, hidden)?;
+
+        Ok(self.set(hide_content_req, deposit, gas))
+    }
+
+    fn read_kudos(
+        &self,
+        receiver_id: &AccountId,
+        kudos_id: &KudosId,
+        fields: &[KudosField],
+        gas: Gas,
+    ) -> Promise {
+        let root_id = env::current_account_id();
+        let keys = fields
+            .iter()
+            .map(|field| match field {
+                KudosField::Info => build_get_kudos_by_id_request(&root_id, receiver_id, kudos_id),
+                KudosField::SenderId => build_kudos_sender_path(&root_id, receiver_id, kudos_id),
+                KudosField::Kind => build_kudos_kind_path(&root_id, receiver_id, kudos_id),
+                KudosField::CreatedAt => {
+                    build_kudos_created_at_path(&root_id, receiver_id, kudos_id)
+                }
+                KudosField::Upvotes => [
+                    build_kudos_upvotes_path(&root_id, receiver_id, kudos_id),
+                    "/*".to_owned(),
+                ]
+                .concat(),
+                KudosField::Comment(comment_id) => build_get_kudos_comment_by_id_request(
+                    &root_id,
+                    receiver_id,
+                    kudos_id,
+                    comment_id,
+                ),
+                KudosField::Comments => {
+                    build_get_kudos_comments_request(&root_id, receiver_id, kudos_id)
+                }
+            })
+            .collect();
+
+        ext_db::ext(self.db_id.clone())
+            .with_static_gas(gas)
+            .get(keys, None)
+    }
+}
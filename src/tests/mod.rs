@@ -1,2 +1,16 @@
+mod tests_account_stats;
+mod tests_comment_reactions;
+mod tests_comment_threads;
+mod tests_dings;
 mod tests_exchange_kudos_for_sbt;
+mod tests_external_db_migration;
+mod tests_hashtags;
+mod tests_humanity_cache;
+mod tests_kudos_kinds;
+mod tests_kudos_status;
+mod tests_migrate;
+mod tests_moderation;
+mod tests_on_chain_storage;
+mod tests_rate_limit;
+mod tests_seasons;
 mod utils;
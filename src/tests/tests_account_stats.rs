@@ -0,0 +1,274 @@
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    AccountStats, AccountStatsView, CommentId, Contract, IncrementalUniqueId, KudosError, KudosId,
+    KudosKind, EXCHANGE_KUDOS_COST, GIVE_KUDOS_COST, LEAVE_COMMENT_COST, UPVOTE_KUDOS_COST,
+};
+use near_sdk::test_utils::accounts;
+use near_sdk::{testing_env, AccountId, PromiseOrValue};
+
+/// Save kudos as if it was given by a sender and saved at a storage backend
+fn save_kudos(
+    kudos_contract: &mut Contract,
+    sender_id: AccountId,
+    receiver_id: AccountId,
+    kind: KudosKind,
+) {
+    testing_env!(build_default_context(sender_id.clone(), None, Some(MAX_GAS)).build());
+
+    assert!(matches!(
+        kudos_contract.on_kudos_saved(
+            sender_id,
+            GIVE_KUDOS_COST.into(),
+            receiver_id,
+            KudosId::from(IncrementalUniqueId::default().next()),
+            kind,
+            None,
+            None,
+            vec![],
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+}
+
+#[test]
+fn test_account_stats() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    assert_eq!(
+        kudos_contract.account_stats(accounts(1)),
+        AccountStats::default()
+    );
+
+    save_kudos(
+        &mut kudos_contract,
+        accounts(1),
+        accounts(2),
+        KudosKind::Kudos,
+    );
+    save_kudos(
+        &mut kudos_contract,
+        accounts(1),
+        accounts(2),
+        KudosKind::Custom(b'x'),
+    );
+    save_kudos(
+        &mut kudos_contract,
+        accounts(2),
+        accounts(1),
+        KudosKind::Ding,
+    );
+
+    testing_env!(build_default_context(accounts(3), None, Some(MAX_GAS)).build());
+    assert!(matches!(
+        kudos_contract.on_kudos_upvote_saved(
+            accounts(3),
+            UPVOTE_KUDOS_COST.into(),
+            accounts(2),
+            kudos_id.clone(),
+            None,
+            None,
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+    assert!(matches!(
+        kudos_contract.on_commentary_saved(
+            accounts(3),
+            LEAVE_COMMENT_COST.into(),
+            accounts(2),
+            kudos_id.clone(),
+            CommentId::new_unchecked(2),
+            None,
+            0,
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+
+    // Commentary message left to own kudos is not counted as received
+    testing_env!(build_default_context(accounts(2), None, Some(MAX_GAS)).build());
+    assert!(matches!(
+        kudos_contract.on_commentary_saved(
+            accounts(2),
+            LEAVE_COMMENT_COST.into(),
+            accounts(2),
+            kudos_id.clone(),
+            CommentId::new_unchecked(3),
+            None,
+            0,
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+    assert!(matches!(
+        kudos_contract.on_pok_sbt_mint(
+            accounts(2),
+            EXCHANGE_KUDOS_COST.into(),
+            kudos_id.clone(),
+            Ok(vec![1]),
+        ),
+        Ok(PromiseOrValue::Value(_))
+    ));
+
+    // Nothing is counted if a storage backend write fails
+    assert!(matches!(
+        kudos_contract.on_kudos_upvote_saved(
+            accounts(2),
+            UPVOTE_KUDOS_COST.into(),
+            accounts(1),
+            kudos_id,
+            None,
+            None,
+            Err(near_sdk::PromiseError::Failed),
+        ),
+        PromiseOrValue::Promise(_)
+    ));
+
+    assert_eq!(
+        kudos_contract.account_stats(accounts(1)),
+        AccountStats {
+            kudos_given: 2,
+            dings_received: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        kudos_contract.account_stats(accounts(2)),
+        AccountStats {
+            kudos_received: 2,
+            dings_given: 1,
+            upvotes_received: 1,
+            comments_given: 1,
+            comments_received: 1,
+            pok_sbts: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        kudos_contract.account_stats(accounts(3)),
+        AccountStats {
+            upvotes_given: 1,
+            comments_given: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_repeated_upvote() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    testing_env!(build_default_context(accounts(3), None, Some(MAX_GAS)).build());
+    for _ in 0..2 {
+        assert!(matches!(
+            kudos_contract.on_kudos_upvote_saved(
+                accounts(3),
+                UPVOTE_KUDOS_COST.into(),
+                accounts(2),
+                kudos_id.clone(),
+                None,
+                None,
+                Ok(()),
+            ),
+            PromiseOrValue::Value(_)
+        ));
+    }
+
+    let stats = kudos_contract.account_stats(accounts(2));
+    assert_eq!(stats.upvotes_received, 1);
+
+    // Upvoter is not allowed to upvote the same kudos again
+    testing_env!(
+        build_default_context(accounts(3), Some(UPVOTE_KUDOS_COST), Some(MAX_GAS)).build()
+    );
+    assert_eq!(
+        kudos_contract
+            .upvote_kudos(accounts(2), kudos_id)
+            .map(|_| ()),
+        Err(KudosError::KudosAlreadyUpvoted)
+    );
+    assert_eq!(kudos_contract.account_stats(accounts(2)), stats);
+    assert_eq!(
+        kudos_contract.account_stats(accounts(3)),
+        AccountStats {
+            upvotes_given: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_leaderboard() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+
+    save_kudos(
+        &mut kudos_contract,
+        accounts(0),
+        accounts(3),
+        KudosKind::Kudos,
+    );
+    save_kudos(
+        &mut kudos_contract,
+        accounts(0),
+        accounts(2),
+        KudosKind::Kudos,
+    );
+    save_kudos(
+        &mut kudos_contract,
+        accounts(0),
+        accounts(1),
+        KudosKind::Kudos,
+    );
+    save_kudos(
+        &mut kudos_contract,
+        accounts(0),
+        accounts(1),
+        KudosKind::Kudos,
+    );
+    // Dings are not counted by a leaderboard
+    save_kudos(
+        &mut kudos_contract,
+        accounts(1),
+        accounts(4),
+        KudosKind::Ding,
+    );
+
+    let leaderboard = kudos_contract.view_leaderboard(None, None);
+    assert_eq!(
+        leaderboard
+            .iter()
+            .map(|view| (view.account_id.clone(), view.stats.kudos_received))
+            .collect::<Vec<_>>(),
+        vec![(accounts(1), 2), (accounts(2), 1), (accounts(3), 1)]
+    );
+
+    assert_eq!(
+        kudos_contract.view_leaderboard(Some(1), Some(1)),
+        vec![AccountStatsView {
+            account_id: accounts(2),
+            stats: AccountStats {
+                kudos_received: 1,
+                ..Default::default()
+            },
+        }]
+    );
+    assert!(kudos_contract.view_leaderboard(Some(3), None).is_empty());
+
+    // Leaderboard position is updated once received upvotes change
+    kudos_contract.record_upvote_stats(&accounts(0), &accounts(3));
+    assert_eq!(
+        kudos_contract
+            .view_leaderboard(None, None)
+            .into_iter()
+            .map(|view| view.account_id)
+            .collect::<Vec<_>>(),
+        vec![accounts(1), accounts(3), accounts(2)]
+    );
+    assert_eq!(kudos_contract.leaderboard.len(), 3);
+}
@@ -0,0 +1,207 @@
+use crate::settings::SettingsView;
+use crate::storage::NewKudos;
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    CommentId, Contract, EncodedCommentary, IncrementalUniqueId, KudosError, KudosId, KudosKind,
+    KudosStatus, RateLimitedAction, REACT_TO_COMMENT_COST,
+};
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::accounts;
+use near_sdk::{env, testing_env, PromiseOrValue, RuntimeFeesConfig, VMConfig};
+
+fn store_comment(kudos_contract: &mut Contract, kudos_id: &KudosId, comment_id: &CommentId) {
+    kudos_contract.store_kudos(NewKudos {
+        sender_id: accounts(1),
+        receiver_id: accounts(2),
+        kudos_id: kudos_id.clone(),
+        created_at: 1689976833613.into(),
+        kind: KudosKind::Kudos,
+        message: "test message".to_owned(),
+        icon_cid: None,
+        attachments: vec![],
+        hashtags: None,
+        mentions: vec![],
+        status: None,
+    });
+    kudos_contract
+        .store_comment(
+            accounts(2),
+            kudos_id.clone(),
+            comment_id.clone(),
+            EncodedCommentary::new_unchecked("eyJtIjoiaGVsbG8ifQ==".to_owned()),
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_react_to_comment() {
+    testing_env!(
+        build_default_context(accounts(0), Some(REACT_TO_COMMENT_COST), Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let mut kudos_contract = init_contract(TestStorage::OnChain);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let comment_id = CommentId::new_unchecked(2);
+
+    assert_eq!(
+        kudos_contract
+            .react_to_comment(
+                accounts(2),
+                kudos_id.clone(),
+                comment_id.clone(),
+                "🦀".to_owned(),
+            )
+            .err(),
+        Some(KudosError::InvalidReaction)
+    );
+    assert!(kudos_contract
+        .react_to_comment(accounts(2), kudos_id, comment_id, "👍".to_owned())
+        .is_ok());
+
+    assert!(matches!(
+        kudos_contract.on_comment_reaction_saved(
+            accounts(0),
+            REACT_TO_COMMENT_COST.into(),
+            "👍".to_owned(),
+            Ok(()),
+        ),
+        PromiseOrValue::Value(reaction) if reaction == "👍"
+    ));
+}
+
+#[test]
+fn test_react_to_comment_restricted() {
+    testing_env!(
+        build_default_context(accounts(0), Some(REACT_TO_COMMENT_COST), Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let mut kudos_contract = init_contract(TestStorage::OnChain);
+    kudos_contract.update_settings(SettingsView {
+        max_comments_per_window: Some(1),
+        ..Default::default()
+    });
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let comment_id = CommentId::new_unchecked(2);
+
+    // Commentary messages of hidden kudos can't be reacted to
+    kudos_contract
+        .kudos_status
+        .insert(kudos_id.clone(), KudosStatus::Hidden);
+    assert_eq!(
+        kudos_contract
+            .react_to_comment(
+                accounts(2),
+                kudos_id.clone(),
+                comment_id.clone(),
+                "👍".to_owned(),
+            )
+            .err(),
+        Some(KudosError::KudosNotPublic)
+    );
+
+    // Reactions are rate limited together with commentary messages
+    kudos_contract.kudos_status.remove(&kudos_id);
+    assert!(kudos_contract
+        .react_to_comment(
+            accounts(2),
+            kudos_id.clone(),
+            comment_id.clone(),
+            "👍".to_owned(),
+        )
+        .is_ok());
+    assert!(matches!(
+        kudos_contract.on_comment_reaction_saved(
+            accounts(0),
+            REACT_TO_COMMENT_COST.into(),
+            "👍".to_owned(),
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+    assert_eq!(
+        kudos_contract
+            .react_to_comment(accounts(2), kudos_id, comment_id, "🎉".to_owned())
+            .err(),
+        Some(KudosError::RateLimitExceeded {
+            action: RateLimitedAction::Comment
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Requires exact amount of attached deposit")]
+fn test_react_to_comment_insufficient_deposit() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::OnChain);
+    let _ = kudos_contract.react_to_comment(
+        accounts(2),
+        KudosId::from(IncrementalUniqueId::default().next()),
+        CommentId::new_unchecked(2),
+        "👍".to_owned(),
+    );
+}
+
+#[test]
+fn test_store_comment_reaction() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::OnChain);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let comment_id = CommentId::new_unchecked(2);
+
+    assert_eq!(
+        kudos_contract.store_comment_reaction(
+            accounts(2),
+            kudos_id.clone(),
+            comment_id.clone(),
+            accounts(3),
+            "👍".to_owned(),
+        ),
+        Err(KudosError::KudosNotFound)
+    );
+
+    store_comment(&mut kudos_contract, &kudos_id, &comment_id);
+    assert_eq!(
+        kudos_contract.store_comment_reaction(
+            accounts(2),
+            kudos_id.clone(),
+            CommentId::new_unchecked(3),
+            accounts(3),
+            "👍".to_owned(),
+        ),
+        Err(KudosError::CommentNotFound)
+    );
+
+    // A previous reaction of the same user is replaced
+    for (sender_id, reaction) in [
+        (accounts(3), "👍"),
+        (accounts(4), "🎉"),
+        (accounts(3), "❤️"),
+    ] {
+        kudos_contract
+            .store_comment_reaction(
+                accounts(2),
+                kudos_id.clone(),
+                comment_id.clone(),
+                sender_id,
+                reaction.to_owned(),
+            )
+            .unwrap();
+    }
+
+    assert_eq!(
+        kudos_contract.view_kudos(accounts(2), kudos_id).unwrap()
+            [env::current_account_id().as_str()]["kudos"]["charlie"]["1"]["comments_reactions"],
+        json!({
+            "2": {
+                "danny": "❤️",
+                "eugene": "🎉"
+            }
+        })
+    );
+}
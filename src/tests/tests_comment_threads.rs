@@ -0,0 +1,137 @@
+use crate::storage::{OnChainStorage, StorageBackend};
+use crate::tests::utils::{build_default_context, MAX_GAS};
+use crate::{
+    CommentId, CommentThread, CommentThreadView, Contract, EncodedCommentary, IncrementalUniqueId,
+    KudosError, KudosId, LEAVE_COMMENT_COST,
+};
+use near_sdk::test_utils::accounts;
+use near_sdk::{testing_env, AccountId, RuntimeFeesConfig, VMConfig};
+
+/// Save commentary message as if it was saved at a storage backend
+fn save_comment(
+    kudos_contract: &mut Contract,
+    kudos_id: &KudosId,
+    comment_id: u64,
+    parent_comment_id: Option<u64>,
+    depth: u8,
+    saved: bool,
+) {
+    kudos_contract.on_commentary_saved(
+        accounts(1),
+        LEAVE_COMMENT_COST.into(),
+        accounts(2),
+        kudos_id.clone(),
+        CommentId::new_unchecked(comment_id),
+        parent_comment_id.map(CommentId::new_unchecked),
+        depth,
+        if saved {
+            Ok(())
+        } else {
+            Err(near_sdk::PromiseError::Failed)
+        },
+    );
+}
+
+#[test]
+fn test_comment_threads() {
+    testing_env!(build_default_context(accounts(1), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    save_comment(&mut kudos_contract, &kudos_id, 2, None, 0, true);
+    save_comment(&mut kudos_contract, &kudos_id, 3, Some(2), 1, true);
+    // Commentary message which failed to save is not indexed
+    save_comment(&mut kudos_contract, &kudos_id, 4, Some(3), 2, false);
+
+    assert_eq!(
+        kudos_contract.view_comment_threads(vec![
+            CommentId::new_unchecked(3),
+            CommentId::new_unchecked(4),
+            CommentId::new_unchecked(2),
+        ]),
+        vec![
+            CommentThreadView {
+                comment_id: CommentId::new_unchecked(3),
+                thread: CommentThread {
+                    kudos_id: kudos_id.clone(),
+                    parent_comment_id: Some(CommentId::new_unchecked(2)),
+                    depth: 1,
+                },
+            },
+            CommentThreadView {
+                comment_id: CommentId::new_unchecked(2),
+                thread: CommentThread {
+                    kudos_id,
+                    parent_comment_id: None,
+                    depth: 0,
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_reply_depth_from_parent_thread() {
+    testing_env!(
+        build_default_context(accounts(1), None, Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let other_kudos_id = KudosId::from(IncrementalUniqueId::default().next().next());
+
+    // Thread 2 <- 3 <- 4 <- 5 <- 6 <- 7 of the maximum depth
+    save_comment(&mut kudos_contract, &kudos_id, 2, None, 0, true);
+    for comment_id in 3..=7 {
+        save_comment(
+            &mut kudos_contract,
+            &kudos_id,
+            comment_id,
+            Some(comment_id - 1),
+            (comment_id - 2) as u8,
+            true,
+        );
+    }
+
+    let mut reply = |kudos_id: &KudosId, parent_comment_id: u64| {
+        // Every reply is a separate transaction with its own prepaid gas
+        testing_env!(
+            build_default_context(accounts(1), None, Some(MAX_GAS)).build(),
+            VMConfig::free(),
+            RuntimeFeesConfig::free()
+        );
+
+        kudos_contract
+            .acquire_kudos_info_verified(
+                accounts(1),
+                LEAVE_COMMENT_COST,
+                StorageBackend::OnChain(OnChainStorage),
+                accounts(2),
+                kudos_id.clone(),
+                Some(CommentId::new_unchecked(parent_comment_id)),
+                EncodedCommentary::new_unchecked("reply".to_owned()),
+            )
+            .err()
+    };
+
+    assert_eq!(reply(&kudos_id, 6), None);
+    assert_eq!(
+        reply(&kudos_id, 7),
+        Some(KudosError::CommentThreadTooDeep { max_depth: 5 })
+    );
+    assert_eq!(
+        reply(&other_kudos_id, 2),
+        Some(KudosError::ParentCommentNotFound)
+    );
+    // Parent without a thread is verified by walking commentary messages of kudos
+    assert_eq!(reply(&kudos_id, 100), None);
+}
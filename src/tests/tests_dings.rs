@@ -0,0 +1,288 @@
+use crate::settings::{Settings, SettingsView};
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    Contract, DingQuorum, IncrementalUniqueId, KudosError, KudosId, KudosKind, KudosStatus,
+    RateLimitedAction, GIVE_KUDOS_COST, UPDATE_KUDOS_STATUS_COST, UPVOTE_KUDOS_COST,
+};
+use near_sdk::test_utils::{accounts, get_created_receipts};
+use near_sdk::{env, testing_env, AccountId, PromiseOrValue, RuntimeFeesConfig, VMConfig};
+
+fn propose_ding(kudos_contract: &mut Contract, kudos_id: &KudosId) {
+    assert!(matches!(
+        kudos_contract.on_kudos_saved(
+            accounts(1),
+            GIVE_KUDOS_COST.into(),
+            accounts(2),
+            kudos_id.clone(),
+            KudosKind::Ding,
+            Some(KudosStatus::Proposed),
+            None,
+            vec![],
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+}
+
+/// Upvote a proposed ding as if an upvote was saved at a storage backend
+fn upvote_ding(
+    kudos_contract: &mut Contract,
+    kudos_id: &KudosId,
+    upvoter_id: AccountId,
+) -> Option<KudosStatus> {
+    let status = kudos_contract.ding_status_on_upvote(
+        &Settings::default(),
+        &accounts(2),
+        kudos_id,
+        &upvoter_id,
+    );
+    kudos_contract.record_ding_upvote(&Settings::default(), kudos_id, &upvoter_id, status.clone());
+
+    status
+}
+
+#[test]
+fn test_ding_justification_and_rate_limit_window() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    kudos_contract.update_settings(SettingsView {
+        ding_rate_limit_window: Some(0),
+        ..Default::default()
+    });
+
+    testing_env!(
+        build_default_context(accounts(1), Some(GIVE_KUDOS_COST), Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+    assert_eq!(
+        kudos_contract
+            .give_kudos(
+                accounts(2),
+                Some(KudosKind::Ding),
+                "bad".to_owned(),
+                None,
+                None,
+                None,
+            )
+            .err(),
+        Some(KudosError::DingJustificationTooShort { min_length: 20 })
+    );
+    assert!(kudos_contract
+        .give_kudos(accounts(2), None, "good".to_owned(), None, None, None)
+        .is_ok());
+    assert!(matches!(
+        kudos_contract.on_kudos_saved(
+            accounts(1),
+            GIVE_KUDOS_COST.into(),
+            accounts(2),
+            KudosId::from(IncrementalUniqueId::default().next()),
+            KudosKind::Kudos,
+            None,
+            None,
+            vec![],
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+
+    // Dings are limited within their own window
+    assert_eq!(
+        kudos_contract.view_remaining_quota(accounts(1), RateLimitedAction::Ding),
+        u32::MAX
+    );
+    assert_eq!(
+        kudos_contract.view_remaining_quota(accounts(1), RateLimitedAction::Kudos),
+        19
+    );
+}
+
+#[test]
+fn test_ding_quorum() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    let mut unique_id = IncrementalUniqueId::default();
+    let kudos_id = KudosId::from(unique_id.inc());
+
+    propose_ding(&mut kudos_contract, &kudos_id);
+    assert_eq!(
+        kudos_contract.kudos_status.get(&kudos_id),
+        Some(&KudosStatus::Proposed)
+    );
+    // Proposed ding is not counted until it's published
+    assert_eq!(kudos_contract.account_stats(accounts(2)).dings_received, 0);
+    assert_eq!(kudos_contract.account_stats(accounts(0)).dings_given, 0);
+
+    // Repeated upvotes of the same user are not counted
+    assert_eq!(
+        upvote_ding(&mut kudos_contract, &kudos_id, accounts(3)),
+        None
+    );
+    assert_eq!(
+        upvote_ding(&mut kudos_contract, &kudos_id, accounts(3)),
+        None
+    );
+    assert_eq!(
+        upvote_ding(&mut kudos_contract, &kudos_id, accounts(4)),
+        None
+    );
+    assert_eq!(
+        kudos_contract.ding_quorums.get(&kudos_id),
+        Some(&DingQuorum {
+            sender_id: accounts(0),
+            receiver_id: accounts(2),
+            upvoters: vec![accounts(3), accounts(4)],
+        })
+    );
+
+    assert_eq!(
+        upvote_ding(&mut kudos_contract, &kudos_id, accounts(5)),
+        Some(KudosStatus::Accepted)
+    );
+    assert_eq!(kudos_contract.kudos_status.get(&kudos_id), None);
+    assert!(!kudos_contract.ding_quorums.contains_key(&kudos_id));
+    assert_eq!(kudos_contract.account_stats(accounts(2)).dings_received, 1);
+    assert_eq!(kudos_contract.account_stats(accounts(0)).dings_given, 1);
+
+    // Published ding stays pending if receiver requires dings to be accepted
+    let kudos_id = KudosId::from(unique_id.inc());
+    kudos_contract.dings_acceptance_required.insert(accounts(2));
+    propose_ding(&mut kudos_contract, &kudos_id);
+    upvote_ding(&mut kudos_contract, &kudos_id, accounts(3));
+    upvote_ding(&mut kudos_contract, &kudos_id, accounts(4));
+    assert_eq!(
+        upvote_ding(&mut kudos_contract, &kudos_id, accounts(5)),
+        Some(KudosStatus::Pending)
+    );
+    assert_eq!(
+        kudos_contract.kudos_status.get(&kudos_id),
+        Some(&KudosStatus::Pending)
+    );
+}
+
+#[test]
+fn test_ding_quorum_reached_by_concurrent_upvotes() {
+    testing_env!(
+        build_default_context(accounts(0), None, Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    propose_ding(&mut kudos_contract, &kudos_id);
+    upvote_ding(&mut kudos_contract, &kudos_id, accounts(3));
+
+    // Both upvotes are acquired before any of them is saved, so none expects to reach a quorum
+    let settings = Settings::default();
+    let first_status =
+        kudos_contract.ding_status_on_upvote(&settings, &accounts(2), &kudos_id, &accounts(4));
+    let second_status =
+        kudos_contract.ding_status_on_upvote(&settings, &accounts(2), &kudos_id, &accounts(5));
+    assert_eq!(first_status, None);
+    assert_eq!(second_status, None);
+
+    testing_env!(
+        build_default_context(accounts(4), None, Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+    assert!(matches!(
+        kudos_contract.on_kudos_upvote_saved(
+            accounts(4),
+            UPVOTE_KUDOS_COST.into(),
+            accounts(2),
+            kudos_id.clone(),
+            None,
+            first_status,
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+    assert!(get_created_receipts().is_empty());
+
+    // The last saved upvote schedules a status write of a ding, which reached a quorum
+    testing_env!(
+        build_default_context(accounts(5), None, Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+    assert!(matches!(
+        kudos_contract.on_kudos_upvote_saved(
+            accounts(5),
+            UPVOTE_KUDOS_COST.into(),
+            accounts(2),
+            kudos_id.clone(),
+            None,
+            second_status,
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+    assert_eq!(
+        get_created_receipts()
+            .into_iter()
+            .map(|receipt| receipt.receiver_id)
+            .collect::<Vec<_>>(),
+        vec![
+            AccountId::new_unchecked("social.near".to_owned()),
+            env::current_account_id()
+        ]
+    );
+    assert_eq!(
+        kudos_contract.kudos_status.get(&kudos_id),
+        Some(&KudosStatus::Proposed)
+    );
+
+    // Ding is published once a status is saved
+    kudos_contract.on_ding_published(kudos_id.clone(), KudosStatus::Accepted, Ok(()));
+    assert_eq!(kudos_contract.kudos_status.get(&kudos_id), None);
+    assert!(!kudos_contract.ding_quorums.contains_key(&kudos_id));
+    assert_eq!(kudos_contract.account_stats(accounts(2)).dings_received, 1);
+}
+
+#[test]
+fn test_approve_ding() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    kudos_contract.add_moderator(accounts(5));
+
+    testing_env!(
+        build_default_context(accounts(5), Some(UPDATE_KUDOS_STATUS_COST), Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+    assert_eq!(
+        kudos_contract.approve_ding(kudos_id.clone()).err(),
+        Some(KudosError::KudosNotProposed)
+    );
+
+    propose_ding(&mut kudos_contract, &kudos_id);
+    assert!(kudos_contract.approve_ding(kudos_id.clone()).is_ok());
+
+    let _ = kudos_contract.on_kudos_status_updated(
+        accounts(5),
+        UPDATE_KUDOS_STATUS_COST.into(),
+        kudos_id.clone(),
+        KudosStatus::Accepted,
+        Ok(()),
+    );
+    assert_eq!(kudos_contract.kudos_status.get(&kudos_id), None);
+    assert!(!kudos_contract.ding_quorums.contains_key(&kudos_id));
+    assert_eq!(kudos_contract.account_stats(accounts(2)).dings_received, 1);
+}
+
+#[test]
+#[should_panic(expected = "Not allowed")]
+fn test_approve_ding_not_moderator() {
+    testing_env!(
+        build_default_context(accounts(1), Some(UPDATE_KUDOS_STATUS_COST), Some(MAX_GAS)).build()
+    );
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    let _ = kudos_contract.approve_ding(KudosId::from(IncrementalUniqueId::default().next()));
+}
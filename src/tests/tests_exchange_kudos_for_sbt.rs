@@ -1,7 +1,11 @@
-use crate::consts::{EXCHANGE_KUDOS_COST, EXCHANGE_KUDOS_STORAGE};
+use crate::consts::{EXCHANGE_KUDOS_COST, EXCHANGE_KUDOS_STORAGE, UPVOTE_KUDOS_STORAGE};
+use crate::settings::Settings;
 use crate::tests::utils::{build_default_context, promise_or_value_result_into_result, MAX_GAS};
-use crate::utils::{build_kudos_kind_path, build_kudos_upvotes_path};
-use crate::{Contract, IncrementalUniqueId, KudosId, PROOF_OF_KUDOS_SBT_MINT_COST};
+use crate::utils::{
+    build_kudos_created_at_path, build_kudos_kind_path, build_kudos_sender_path,
+    build_kudos_upvotes_path,
+};
+use crate::{Contract, IncrementalUniqueId, KudosError, KudosId, PROOF_OF_KUDOS_SBT_MINT_COST};
 use near_sdk::borsh::BorshSerialize;
 use near_sdk::serde_json::json;
 use near_sdk::test_utils::accounts;
@@ -54,18 +58,24 @@ fn test_required_deposit_to_exchange_kudos() -> anyhow::Result<()> {
     let sender_id = accounts(1);
     let kudos_upvotes_path = build_kudos_upvotes_path(&contract_id, &receiver_id, &kudos_id);
     let kudos_kind_path = build_kudos_kind_path(&contract_id, &receiver_id, &kudos_id);
+    let kudos_sender_path = build_kudos_sender_path(&contract_id, &receiver_id, &kudos_id);
+    let kudos_created_at_path = build_kudos_created_at_path(&contract_id, &receiver_id, &kudos_id);
     kudos_contract.on_kudos_upvotes_acquired(
         sender_id.clone(),
         EXCHANGE_KUDOS_COST.into(),
         kudos_id.clone(),
         kudos_upvotes_path.clone(),
         kudos_kind_path.clone(),
+        kudos_sender_path.clone(),
+        kudos_created_at_path.clone(),
         Ok(json!({
             "kudos.near": {
               "kudos": {
                 "alice": {
                   "1": {
                     "kind": "k",
+                    "sender_id": "bob",
+                    "created_at": "1234567890",
                     "upvotes": {
                       "charlie": "",
                       "danny": "",
@@ -91,6 +101,8 @@ fn test_required_deposit_to_exchange_kudos() -> anyhow::Result<()> {
         kudos_id.clone(),
         kudos_upvotes_path.clone(),
         kudos_kind_path.clone(),
+        kudos_sender_path.clone(),
+        kudos_created_at_path.clone(),
         Ok(json!({
             "kudos.near": {
               "kudos": {
@@ -114,6 +126,8 @@ fn test_required_deposit_to_exchange_kudos() -> anyhow::Result<()> {
         kudos_id,
         kudos_upvotes_path,
         kudos_kind_path,
+        kudos_sender_path,
+        kudos_created_at_path,
         Ok(json!({
             "kudos.near": {
               "kudos": {
@@ -156,7 +170,7 @@ fn test_on_pok_sbt_mint() {
     struct TestCase<'a> {
         name: &'a str,
         input: Result<Vec<u64>, PromiseError>,
-        output: Result<String, String>,
+        output: Result<String, KudosError>,
     }
 
     let test_cases = [
@@ -168,7 +182,7 @@ fn test_on_pok_sbt_mint() {
         TestCase {
             name: "SBT mint failure",
             input: Ok(vec![]),
-            output: Err("IAHRegistry::sbt_mint() responses with an empty tokens array".to_owned()),
+            output: Err(KudosError::EmptyMintResponse),
         },
         TestCase {
             name: "Promise error",
@@ -193,3 +207,264 @@ fn test_on_pok_sbt_mint() {
         );
     }
 }
+
+#[test]
+fn test_required_storage_to_upvote_kudos() {
+    testing_env!(build_default_context(accounts(0), None, Some(Gas::ONE_TERA)).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    let upvoter_id = AccountId::new_unchecked("u".repeat(64));
+    let receiver_id = AccountId::new_unchecked("r".repeat(64));
+    let initial_storage = env::storage_usage();
+    kudos_contract.record_upvote(
+        &Settings::default(),
+        &upvoter_id,
+        &receiver_id,
+        &IncrementalUniqueId::default().next().into(),
+        Some(1_000),
+        2_000,
+    );
+    kudos_contract.upvotes_by_pair.flush();
+    kudos_contract.upvote_periods.flush();
+    kudos_contract.iah_sbt_issued_at.flush();
+    assert_eq!(env::storage_usage() - initial_storage, UPVOTE_KUDOS_STORAGE);
+}
+
+#[test]
+fn test_ignore_kudos_sender_upvotes() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    let receiver_id = accounts(0);
+    let kudos_sender_id = accounts(1);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let mut settings = Settings::default();
+
+    assert!(!kudos_contract.is_upvote_counted(
+        &settings,
+        &kudos_sender_id,
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        0
+    ));
+    assert!(kudos_contract.is_upvote_counted(
+        &settings,
+        &accounts(2),
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        0
+    ));
+
+    settings.ignore_kudos_sender_upvotes = false;
+    assert!(kudos_contract.is_upvote_counted(
+        &settings,
+        &kudos_sender_id,
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        0
+    ));
+}
+
+#[test]
+fn test_reciprocal_upvotes_cap() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    let receiver_id = accounts(0);
+    let upvoter_id = accounts(1);
+    let kudos_sender_id = accounts(2);
+    let settings = Settings {
+        max_reciprocal_upvotes_per_period: 2,
+        reciprocal_upvotes_period: 1_000,
+        ..Settings::default()
+    };
+    let now = 10_500;
+    let mut id = IncrementalUniqueId::default();
+    let kudos_ids = (0..5).map(|_| KudosId::from(id.inc())).collect::<Vec<_>>();
+    let is_counted = |kudos_contract: &Contract, upvoter_id, receiver_id, kudos_id| {
+        kudos_contract.is_upvote_counted(
+            &settings,
+            upvoter_id,
+            receiver_id,
+            kudos_id,
+            &kudos_sender_id,
+            0,
+        )
+    };
+
+    // Upvotes given by upvoter to receiver's kudos are not reciprocal
+    for kudos_id in &kudos_ids[..3] {
+        kudos_contract.record_upvote(&settings, &upvoter_id, &receiver_id, kudos_id, None, now);
+    }
+    assert!(is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[2]
+    ));
+
+    // Receiver upvotes upvoter's kudos within the same period, only upvotes given after
+    // the allowed maximum are not counted
+    kudos_contract.record_upvote(
+        &settings,
+        &receiver_id,
+        &upvoter_id,
+        &kudos_ids[3],
+        None,
+        now,
+    );
+    assert!(is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[0]
+    ));
+    assert!(is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[1]
+    ));
+    assert!(!is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[2]
+    ));
+    // Repeated upvote doesn't change a memorised position of an upvote
+    assert!(!kudos_contract.record_upvote(
+        &settings,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[0],
+        None,
+        now
+    ));
+    assert_eq!(
+        kudos_contract
+            .upvotes_by_pair
+            .get(&(upvoter_id.clone(), receiver_id.clone(), 10_000)),
+        Some(&3)
+    );
+    assert!(is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[0]
+    ));
+    // Receiver's upvotes for upvoter are still counted, maximum not exceeded
+    assert!(is_counted(
+        &kudos_contract,
+        &receiver_id,
+        &upvoter_id,
+        &kudos_ids[3]
+    ));
+
+    // Upvotes are counted again within the next period, regardless of when kudos are exchanged
+    kudos_contract.record_upvote(
+        &settings,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[4],
+        None,
+        now + 1_000,
+    );
+    assert!(is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[4]
+    ));
+    assert!(!is_counted(
+        &kudos_contract,
+        &upvoter_id,
+        &receiver_id,
+        &kudos_ids[2]
+    ));
+}
+
+#[test]
+fn test_min_upvoter_iah_sbt_age() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    let receiver_id = accounts(0);
+    let kudos_sender_id = accounts(1);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let settings = Settings {
+        min_upvoter_iah_sbt_age: 1_000,
+        ..Settings::default()
+    };
+    let kudos_created_at = 5_000;
+
+    for (upvoter_id, iah_sbt_issued_at) in [
+        (accounts(2), Some(4_000)),
+        (accounts(3), Some(4_001)),
+        (accounts(4), None),
+    ] {
+        kudos_contract.record_upvote(
+            &settings,
+            &upvoter_id,
+            &receiver_id,
+            &kudos_id,
+            iah_sbt_issued_at,
+            6_000,
+        );
+    }
+
+    // Upvoter's SBT issued long enough before kudos was created
+    assert!(kudos_contract.is_upvote_counted(
+        &settings,
+        &accounts(2),
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        kudos_created_at
+    ));
+    // Upvoter's SBT is too young
+    assert!(!kudos_contract.is_upvote_counted(
+        &settings,
+        &accounts(3),
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        kudos_created_at
+    ));
+    // Unknown SBT issue date
+    assert!(!kudos_contract.is_upvote_counted(
+        &settings,
+        &accounts(4),
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        kudos_created_at
+    ));
+    // Rule disabled
+    assert!(kudos_contract.is_upvote_counted(
+        &Settings::default(),
+        &accounts(4),
+        &receiver_id,
+        &kudos_id,
+        &kudos_sender_id,
+        kudos_created_at
+    ));
+}
@@ -0,0 +1,262 @@
+use crate::tests::utils::{build_default_context, MAX_GAS};
+use crate::{
+    Contract, ExternalDbMigration, IncrementalUniqueId, KudosError, KudosId, MigrationSection,
+};
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::accounts;
+use near_sdk::{testing_env, AccountId, PromiseOrValue, RuntimeFeesConfig, VMConfig};
+
+fn setup_contract() -> Contract {
+    testing_env!(
+        build_default_context(accounts(0), None, Some(MAX_GAS))
+            .current_account_id(AccountId::new_unchecked("kudos.near".to_owned()))
+            .build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+    kudos_contract
+        .on_ext_db_write_permission_granted(
+            AccountId::new_unchecked("social.near".to_owned()),
+            AccountId::new_unchecked("iah_registry.near".to_owned()),
+            Ok(()),
+        )
+        .unwrap();
+
+    kudos_contract
+}
+
+fn new_db_id() -> AccountId {
+    AccountId::new_unchecked("v2.social.near".to_owned())
+}
+
+#[test]
+fn test_migration_blocks_storage_backend() {
+    let mut kudos_contract = setup_contract();
+    assert!(kudos_contract.storage_backend().is_ok());
+
+    let migration = kudos_contract
+        .on_migration_db_init(new_db_id(), Ok(()))
+        .unwrap();
+    assert_eq!(migration, ExternalDbMigration::new(new_db_id()));
+    assert_eq!(
+        kudos_contract.storage_backend(),
+        Err(KudosError::ExternalDbMigrationInProgress)
+    );
+    assert_eq!(
+        kudos_contract
+            .start_external_db_migration(new_db_id())
+            .err(),
+        Some(KudosError::ExternalDbMigrationInProgress)
+    );
+
+    kudos_contract
+        .on_external_db_migrated(new_db_id(), Ok(()))
+        .unwrap();
+    assert_eq!(kudos_contract.view_external_db_migration(), None);
+    assert!(kudos_contract.storage_backend().is_ok());
+}
+
+#[test]
+fn test_migration_cursor() {
+    let mut kudos_contract = setup_contract();
+    kudos_contract
+        .on_migration_db_init(new_db_id(), Ok(()))
+        .unwrap();
+
+    let keys = json!({
+        "kudos.near": {
+            "kudos": {
+                "bob.near": true,
+                "alice.near": true
+            }
+        }
+    });
+    assert!(matches!(
+        kudos_contract.on_migration_keys_acquired(
+            AccountId::new_unchecked("social.near".to_owned()),
+            MigrationSection::Kudos,
+            1,
+            Ok(keys.clone()),
+        ),
+        Ok(PromiseOrValue::Promise(_))
+    ));
+
+    // Cursor is moved only after a batch is saved to a new external db
+    assert_eq!(
+        kudos_contract.on_migration_batch_saved(
+            MigrationSection::Kudos,
+            "alice.near".to_owned(),
+            1,
+            Err(near_sdk::PromiseError::Failed),
+        ),
+        Err(KudosError::external_call_failure(
+            "SocialDB::set()",
+            near_sdk::PromiseError::Failed
+        ))
+    );
+    assert_eq!(
+        kudos_contract.view_external_db_migration().unwrap().cursor,
+        None
+    );
+
+    let migration = kudos_contract
+        .on_migration_batch_saved(MigrationSection::Kudos, "alice.near".to_owned(), 1, Ok(()))
+        .unwrap();
+    assert_eq!(migration.cursor.as_deref(), Some("alice.near"));
+    assert_eq!(migration.migrated_keys, 1);
+
+    // Batch without stored data is skipped
+    assert!(matches!(
+        kudos_contract.on_migration_batch_acquired(
+            MigrationSection::Kudos,
+            "bob.near".to_owned(),
+            1,
+            Ok(json!({})),
+        ),
+        Ok(PromiseOrValue::Value(ExternalDbMigration {
+            migrated_keys: 2,
+            ..
+        }))
+    ));
+
+    // Section is completed when no keys left after cursor
+    assert!(matches!(
+        kudos_contract.on_migration_keys_acquired(
+            AccountId::new_unchecked("social.near".to_owned()),
+            MigrationSection::Kudos,
+            1,
+            Ok(keys),
+        ),
+        Ok(PromiseOrValue::Value(ExternalDbMigration {
+            section: Some(MigrationSection::Hashtags),
+            cursor: None,
+            ..
+        }))
+    ));
+
+    // Stale steps of a completed section are rejected
+    assert_eq!(
+        kudos_contract
+            .on_migration_batch_saved(MigrationSection::Kudos, "bob.near".to_owned(), 1, Ok(()))
+            .err(),
+        Some(KudosError::ExternalDbMigrationNotStarted)
+    );
+
+    assert!(matches!(
+        kudos_contract.on_migration_keys_acquired(
+            AccountId::new_unchecked("social.near".to_owned()),
+            MigrationSection::Hashtags,
+            1,
+            Ok(json!({})),
+        ),
+        Ok(PromiseOrValue::Value(ExternalDbMigration {
+            section: Some(MigrationSection::Mentions),
+            ..
+        }))
+    ));
+
+    assert!(matches!(
+        kudos_contract.on_migration_keys_acquired(
+            AccountId::new_unchecked("social.near".to_owned()),
+            MigrationSection::Mentions,
+            1,
+            Ok(json!({})),
+        ),
+        Ok(PromiseOrValue::Value(ExternalDbMigration {
+            section: None,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn test_migration_entries_cursor() {
+    let mut kudos_contract = setup_contract();
+    kudos_contract
+        .on_migration_db_init(new_db_id(), Ok(()))
+        .unwrap();
+
+    // Kudos of a key are copied by batches limited to a number of entries
+    let entries = json!({
+        "kudos.near": {
+            "kudos": {
+                "alice.near": { "1": true, "2": true }
+            }
+        }
+    });
+    assert!(matches!(
+        kudos_contract.on_migration_entries_acquired(
+            AccountId::new_unchecked("social.near".to_owned()),
+            MigrationSection::Kudos,
+            "alice.near".to_owned(),
+            1,
+            Ok(entries),
+        ),
+        Ok(PromiseOrValue::Promise(_))
+    ));
+    let migration = kudos_contract
+        .on_migration_batch_saved(
+            MigrationSection::Kudos,
+            "alice.near/1".to_owned(),
+            1,
+            Ok(()),
+        )
+        .unwrap();
+    assert_eq!(migration.cursor.as_deref(), Some("alice.near/1"));
+    assert_eq!(migration.migrated_keys, 1);
+
+    // Key without entries left after the cursor is completed without copying
+    assert!(matches!(
+        kudos_contract.on_migration_entries_acquired(
+            AccountId::new_unchecked("social.near".to_owned()),
+            MigrationSection::Kudos,
+            "alice.near".to_owned(),
+            1,
+            Ok(json!({})),
+        ),
+        Ok(PromiseOrValue::Value(ExternalDbMigration {
+            migrated_keys: 1,
+            ..
+        }))
+    ));
+    assert_eq!(
+        kudos_contract
+            .view_external_db_migration()
+            .unwrap()
+            .cursor
+            .as_deref(),
+        Some("alice.near")
+    );
+}
+
+#[test]
+fn test_skip_and_abort_migration() {
+    let mut kudos_contract = setup_contract();
+    kudos_contract
+        .on_migration_db_init(new_db_id(), Ok(()))
+        .unwrap();
+
+    let migration = kudos_contract
+        .skip_external_db_migration_entry(
+            "bob.near".to_owned(),
+            Some(KudosId::from(IncrementalUniqueId::default().next())),
+        )
+        .unwrap();
+    assert_eq!(migration.cursor.as_deref(), Some("bob.near/1"));
+    let migration = kudos_contract
+        .skip_external_db_migration_entry("bob.near".to_owned(), None)
+        .unwrap();
+    assert_eq!(migration.cursor.as_deref(), Some("bob.near"));
+
+    kudos_contract.abort_external_db_migration().unwrap();
+    assert_eq!(kudos_contract.view_external_db_migration(), None);
+    assert_eq!(
+        kudos_contract.abort_external_db_migration(),
+        Err(KudosError::ExternalDbMigrationNotStarted)
+    );
+}
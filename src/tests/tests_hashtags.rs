@@ -0,0 +1,205 @@
+use crate::settings::SettingsView;
+use crate::tests::utils::{build_default_context, MAX_GAS};
+use crate::types::HashtagMode;
+use crate::utils::give_kudos_cost;
+use crate::{
+    Contract, CuratedHashtag, CuratedHashtagView, Hashtag, HashtagUsage, IncrementalUniqueId,
+    KudosError, KudosId, KudosKind, GIVE_KUDOS_COST, HASHTAGS_MAX_TRACKED, HASHTAG_USAGE_PERIOD,
+};
+use near_sdk::test_utils::accounts;
+use near_sdk::{testing_env, AccountId, PromiseOrValue, RuntimeFeesConfig, VMConfig};
+
+fn save_kudos_with_hashtags(kudos_contract: &mut Contract, now: u64, hashtags: &[&str]) {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS))
+        .block_timestamp(now * 1_000_000)
+        .build());
+
+    assert!(matches!(
+        kudos_contract.on_kudos_saved(
+            accounts(1),
+            GIVE_KUDOS_COST.into(),
+            accounts(2),
+            KudosId::from(IncrementalUniqueId::default().next()),
+            KudosKind::Kudos,
+            None,
+            Some(
+                hashtags
+                    .iter()
+                    .map(|ht| Hashtag::new_unchecked(ht))
+                    .collect()
+            ),
+            vec![],
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+}
+
+fn usage(hashtag: &str, count: u64) -> HashtagUsage {
+    HashtagUsage {
+        hashtag: Hashtag::new_unchecked(hashtag),
+        count,
+    }
+}
+
+#[test]
+fn test_top_and_trending_hashtags() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    let day = HASHTAG_USAGE_PERIOD;
+    save_kudos_with_hashtags(&mut kudos_contract, 10 * day, &["ndc", "lovendc"]);
+    save_kudos_with_hashtags(&mut kudos_contract, 10 * day, &["ndc"]);
+    save_kudos_with_hashtags(&mut kudos_contract, 20 * day, &["lovendc", "nearweek"]);
+    save_kudos_with_hashtags(&mut kudos_contract, 20 * day, &["nearweek"]);
+    save_kudos_with_hashtags(&mut kudos_contract, 20 * day, &["nearweek"]);
+
+    assert_eq!(
+        kudos_contract.view_top_hashtags(None),
+        vec![usage("nearweek", 3), usage("lovendc", 2), usage("ndc", 2)]
+    );
+    assert_eq!(
+        kudos_contract.view_top_hashtags(Some(1)),
+        vec![usage("nearweek", 3)]
+    );
+    assert_eq!(
+        kudos_contract.view_trending_hashtags(day, None),
+        vec![usage("nearweek", 3), usage("lovendc", 1)]
+    );
+    assert_eq!(
+        kudos_contract.view_trending_hashtags(10 * day, None),
+        vec![usage("nearweek", 3), usage("lovendc", 2), usage("ndc", 2)]
+    );
+}
+
+#[test]
+fn test_hashtags_max_tracked() {
+    testing_env!(
+        build_default_context(accounts(0), None, Some(MAX_GAS)).build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    let day = HASHTAG_USAGE_PERIOD;
+    let hashtags = (0..HASHTAGS_MAX_TRACKED)
+        .map(|n| Hashtag::new_unchecked(&format!("h{n:04}")))
+        .collect::<Vec<_>>();
+    kudos_contract.record_hashtags_usage(&hashtags, 0);
+    kudos_contract.record_hashtags_usage(&[Hashtag::new_unchecked("h0000")], 0);
+
+    // The least used hashtag which wasn't used recently is forgotten to count a new one
+    kudos_contract.record_hashtags_usage(&[Hashtag::new_unchecked("ndc")], 40 * day);
+    assert_eq!(kudos_contract.hashtags.len(), HASHTAGS_MAX_TRACKED);
+    assert!(!kudos_contract
+        .hashtags
+        .contains_key(&Hashtag::new_unchecked("h0999")));
+
+    // The least used hashtag "ndc" was used recently, so a new one is not counted
+    kudos_contract.record_hashtags_usage(&[Hashtag::new_unchecked("abc")], 40 * day);
+    assert!(!kudos_contract
+        .hashtags
+        .contains_key(&Hashtag::new_unchecked("abc")));
+    assert_eq!(
+        kudos_contract.view_top_hashtags(Some(2)),
+        vec![usage("h0000", 2), usage("h0001", 1)]
+    );
+
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS))
+        .block_timestamp(40 * day * 1_000_000)
+        .build());
+    assert_eq!(
+        kudos_contract.view_trending_hashtags(day, None),
+        vec![usage("ndc", 1)]
+    );
+}
+
+#[test]
+fn test_curated_hashtags() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+    kudos_contract
+        .add_curated_hashtag("NDC".to_owned(), "Official NDC hashtag".to_owned(), None)
+        .unwrap();
+    kudos_contract
+        .add_curated_hashtag("event".to_owned(), "Expired event".to_owned(), Some(1))
+        .unwrap();
+    assert_eq!(
+        kudos_contract.add_curated_hashtag("ndc".to_owned(), "a".repeat(257), None),
+        Err(KudosError::DescriptionMaxLengthExceeded)
+    );
+    assert_eq!(
+        kudos_contract.view_curated_hashtags(None, Some(1)),
+        vec![CuratedHashtagView {
+            hashtag: Hashtag::new_unchecked("ndc"),
+            info: CuratedHashtag {
+                description: "Official NDC hashtag".to_owned(),
+                expires_at: None,
+            },
+        }]
+    );
+    assert!(kudos_contract.is_hashtag_curated(&Hashtag::new_unchecked("ndc"), 10));
+    assert!(!kudos_contract.is_hashtag_curated(&Hashtag::new_unchecked("event"), 10));
+
+    kudos_contract.update_settings(SettingsView {
+        hashtag_mode: Some(HashtagMode::CuratedOnly),
+        ..Default::default()
+    });
+
+    // Curated hashtag "event" is already expired
+    testing_env!(
+        build_default_context(accounts(1), Some(give_kudos_cost(0, 2)), Some(MAX_GAS))
+            .block_timestamp(10_000_000)
+            .build()
+    );
+    assert_eq!(
+        kudos_contract
+            .give_kudos(
+                accounts(2),
+                None,
+                "test message".to_owned(),
+                None,
+                Some(vec!["ndc".to_owned(), "event".to_owned()]),
+                None,
+            )
+            .err(),
+        Some(KudosError::HashtagNotCurated {
+            hashtag: "event".to_owned()
+        })
+    );
+
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+    kudos_contract
+        .remove_curated_hashtag("event".to_owned())
+        .unwrap();
+    assert_eq!(
+        kudos_contract.remove_curated_hashtag("event".to_owned()),
+        Err(KudosError::CuratedHashtagNotFound)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not allowed")]
+fn test_add_curated_hashtag_not_allowed() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    testing_env!(build_default_context(accounts(1), None, Some(MAX_GAS)).build());
+    let _ = kudos_contract.add_curated_hashtag("ndc".to_owned(), "NDC".to_owned(), None);
+}
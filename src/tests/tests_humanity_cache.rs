@@ -0,0 +1,241 @@
+use crate::registry::{OwnedToken, TokenId, TokenMetadata};
+use crate::settings::{Settings, SettingsView};
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    HumanityAction, HumanityPolicy, HumanityRequirement, KudosError, KudosKind, GIVE_KUDOS_COST,
+};
+use near_sdk::test_utils::{accounts, get_created_receipts};
+use near_sdk::{testing_env, AccountId, RuntimeFeesConfig, VMConfig};
+
+const HOUR_MS: u64 = 3_600_000;
+
+fn issuer() -> AccountId {
+    AccountId::new_unchecked("fractal.near".to_owned())
+}
+
+#[test]
+fn test_cache_humanity() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    kudos_contract.update_settings(SettingsView {
+        humanity_cache_ttl: Some(2 * HOUR_MS),
+        ..Default::default()
+    });
+    let settings = Settings::from(&kudos_contract.settings);
+
+    let now = 10 * HOUR_MS;
+    assert!(!kudos_contract.is_human_cached(&accounts(1), HumanityAction::Kudos, now));
+
+    kudos_contract.cache_humanity(&settings, &accounts(1), now, &[(issuer(), vec![1])], None);
+    assert!(kudos_contract.is_human_cached(&accounts(1), HumanityAction::Kudos, now + HOUR_MS));
+    assert!(!kudos_contract.is_human_cached(
+        &accounts(1),
+        HumanityAction::Kudos,
+        now + 2 * HOUR_MS
+    ));
+
+    // Cached verification result does not outlive i-am-human SBT
+    kudos_contract.cache_humanity(
+        &settings,
+        &accounts(2),
+        now,
+        &[(issuer(), vec![1])],
+        Some(now + HOUR_MS),
+    );
+    assert!(kudos_contract.is_human_cached(&accounts(2), HumanityAction::Kudos, now));
+    assert!(!kudos_contract.is_human_cached(&accounts(2), HumanityAction::Kudos, now + HOUR_MS));
+
+    kudos_contract.invalidate_humanity_cache(Some(accounts(1)));
+    assert!(!kudos_contract.is_human_cached(&accounts(1), HumanityAction::Kudos, now));
+    assert!(kudos_contract.is_human_cached(&accounts(2), HumanityAction::Kudos, now));
+
+    kudos_contract.invalidate_humanity_cache(None);
+    assert!(!kudos_contract.is_human_cached(&accounts(2), HumanityAction::Kudos, now));
+
+    // Verification results are not cached if disabled
+    kudos_contract.update_settings(SettingsView {
+        humanity_cache_ttl: Some(0),
+        ..Default::default()
+    });
+    let settings = Settings::from(&kudos_contract.settings);
+    kudos_contract.cache_humanity(&settings, &accounts(3), now, &[(issuer(), vec![1])], None);
+    assert!(!kudos_contract.is_human_cached(&accounts(3), HumanityAction::Kudos, now));
+}
+
+#[test]
+fn test_give_kudos_skips_cached_humanity_verification() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    let settings = Settings::from(&kudos_contract.settings);
+    let now = 10 * HOUR_MS;
+
+    for (cached, expected_receiver_id) in [(false, "iah_registry.near"), (true, "social.near")] {
+        if cached {
+            kudos_contract.cache_humanity(
+                &settings,
+                &accounts(1),
+                now,
+                &[(issuer(), vec![1])],
+                None,
+            );
+        }
+
+        testing_env!(
+            build_default_context(accounts(1), Some(GIVE_KUDOS_COST), Some(MAX_GAS))
+                .block_timestamp(now * 1_000_000)
+                .build(),
+            VMConfig::free(),
+            RuntimeFeesConfig::free()
+        );
+        assert!(kudos_contract
+            .give_kudos(
+                accounts(2),
+                Some(KudosKind::Kudos),
+                "test".to_owned(),
+                None,
+                None,
+                None
+            )
+            .is_ok());
+        assert_eq!(
+            get_created_receipts()[0].receiver_id.as_str(),
+            expected_receiver_id
+        );
+    }
+}
+
+#[test]
+fn test_humanity_policy() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    kudos_contract.update_settings(SettingsView {
+        humanity_policy: Some(HumanityPolicy {
+            ding: HumanityRequirement {
+                issuers: vec![issuer()],
+                min_tokens: 2,
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let other_issuer = AccountId::new_unchecked("other.near".to_owned());
+    assert_eq!(
+        kudos_contract.verify_humanity(&accounts(1), HumanityAction::Kudos, Ok(vec![]), None),
+        Err(KudosError::NotHuman)
+    );
+    assert_eq!(
+        kudos_contract.verify_humanity(
+            &accounts(1),
+            HumanityAction::Comment,
+            Ok(vec![(other_issuer.clone(), vec![1])]),
+            None
+        ),
+        Ok(vec![(other_issuer.clone(), vec![1])])
+    );
+    assert_eq!(
+        kudos_contract.verify_humanity(
+            &accounts(1),
+            HumanityAction::Ding,
+            Ok(vec![
+                (other_issuer.clone(), vec![1, 2]),
+                (issuer(), vec![3])
+            ]),
+            None
+        ),
+        Err(KudosError::HumanityPolicyNotSatisfied {
+            action: HumanityAction::Ding
+        })
+    );
+    assert_eq!(
+        kudos_contract.verify_humanity(
+            &accounts(1),
+            HumanityAction::Ding,
+            Ok(vec![(other_issuer, vec![1]), (issuer(), vec![2, 3])]),
+            None
+        ),
+        Ok(vec![(issuer(), vec![2, 3])])
+    );
+
+    // Cached verification result is verified against humanity policy of every action
+    kudos_contract.cache_humanity(
+        &Settings::from(&kudos_contract.settings),
+        &accounts(2),
+        0,
+        &[(issuer(), vec![1])],
+        None,
+    );
+    assert!(kudos_contract.is_human_cached(&accounts(2), HumanityAction::Kudos, 0));
+    assert!(!kudos_contract.is_human_cached(&accounts(2), HumanityAction::Ding, 0));
+}
+
+#[test]
+fn test_verify_humanity_caches_until_sbt_expires() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::SocialDb);
+    let now = 10 * HOUR_MS;
+    let owned_token = |token: TokenId, expires_at: Option<u64>| OwnedToken {
+        token,
+        metadata: TokenMetadata {
+            class: 1,
+            issued_at: Some(0),
+            expires_at,
+            reference: None,
+            reference_hash: None,
+        },
+    };
+
+    testing_env!(build_default_context(accounts(0), None, None)
+        .block_timestamp(now * 1_000_000)
+        .build());
+    // Result is not cached if SBTs were not acquired
+    assert!(kudos_contract
+        .verify_humanity(
+            &accounts(1),
+            HumanityAction::Kudos,
+            Ok(vec![(issuer(), vec![1])]),
+            None
+        )
+        .is_ok());
+    assert!(!kudos_contract.is_human_cached(&accounts(1), HumanityAction::Kudos, now));
+
+    // Expiration time of SBTs which were not used to verify user is ignored
+    assert!(kudos_contract
+        .verify_humanity(
+            &accounts(1),
+            HumanityAction::Kudos,
+            Ok(vec![(issuer(), vec![1])]),
+            Some(vec![(
+                issuer(),
+                vec![
+                    owned_token(1, Some(now + HOUR_MS)),
+                    owned_token(2, Some(now + 1))
+                ]
+            )])
+        )
+        .is_ok());
+    assert!(kudos_contract.is_human_cached(&accounts(1), HumanityAction::Kudos, now + 1));
+    assert!(!kudos_contract.is_human_cached(&accounts(1), HumanityAction::Kudos, now + HOUR_MS));
+
+    // Expired result is pruned, valid one is kept
+    kudos_contract.cache_humanity(
+        &Settings::from(&kudos_contract.settings),
+        &accounts(2),
+        now,
+        &[(issuer(), vec![1])],
+        None,
+    );
+    testing_env!(build_default_context(accounts(3), None, None)
+        .block_timestamp((now + HOUR_MS) * 1_000_000)
+        .build());
+    assert_eq!(
+        kudos_contract.prune_humanity_cache(vec![accounts(1), accounts(2), accounts(3)]),
+        1
+    );
+    assert!(!kudos_contract.humanity_cache.contains_key(&accounts(1)));
+    assert!(kudos_contract.humanity_cache.contains_key(&accounts(2)));
+}
@@ -0,0 +1,107 @@
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{KudosError, KudosKind, KudosKindInfo, KudosKindView, GIVE_KUDOS_COST};
+use near_sdk::test_utils::accounts;
+use near_sdk::testing_env;
+
+fn mentorship() -> KudosKindInfo {
+    KudosKindInfo {
+        name: "Mentorship".to_owned(),
+        icon_cid: Some("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_owned()),
+        exchangeable: true,
+        allowed_in_batch: false,
+        needs_upvotes: false,
+    }
+}
+
+#[test]
+fn test_kudos_kinds_registry() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let builtin_kinds = kudos_contract.view_kudos_kinds();
+    assert_eq!(
+        builtin_kinds
+            .iter()
+            .map(|view| &view.kind)
+            .collect::<Vec<_>>(),
+        vec![&KudosKind::Kudos, &KudosKind::Ding]
+    );
+
+    kudos_contract
+        .set_kudos_kind(KudosKind::Custom(b'm'), mentorship())
+        .unwrap();
+    assert_eq!(
+        kudos_contract.set_kudos_kind(
+            KudosKind::Custom(b'r'),
+            KudosKindInfo {
+                icon_cid: Some("invalid".to_owned()),
+                ..mentorship()
+            }
+        ),
+        Err(KudosError::InvalidCid)
+    );
+    assert_eq!(
+        kudos_contract.view_kudos_kinds()[2],
+        KudosKindView {
+            kind: KudosKind::Custom(b'm'),
+            info: mentorship(),
+        }
+    );
+
+    // Built-in kind could be overridden and restored
+    kudos_contract
+        .set_kudos_kind(
+            KudosKind::Ding,
+            KudosKindInfo {
+                exchangeable: true,
+                ..KudosKind::Ding.builtin_info().unwrap()
+            },
+        )
+        .unwrap();
+    assert!(kudos_contract.view_kudos_kinds()[1].info.exchangeable);
+    kudos_contract.remove_kudos_kind(KudosKind::Ding).unwrap();
+    assert_eq!(kudos_contract.view_kudos_kinds().len(), 3);
+    assert!(!kudos_contract.view_kudos_kinds()[1].info.exchangeable);
+
+    kudos_contract
+        .remove_kudos_kind(KudosKind::Custom(b'm'))
+        .unwrap();
+    assert_eq!(kudos_contract.view_kudos_kinds(), builtin_kinds);
+    assert_eq!(
+        kudos_contract.remove_kudos_kind(KudosKind::Custom(b'm')),
+        Err(KudosError::KudosKindNotFound)
+    );
+}
+
+#[test]
+fn test_give_kudos_of_unregistered_kind() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+
+    testing_env!(build_default_context(accounts(1), Some(GIVE_KUDOS_COST), Some(MAX_GAS)).build());
+    assert_eq!(
+        kudos_contract
+            .give_kudos(
+                accounts(2),
+                Some(KudosKind::Custom(b'm')),
+                "test message".to_owned(),
+                None,
+                None,
+                None,
+            )
+            .err(),
+        Some(KudosError::KudosKindNotFound)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not allowed")]
+fn test_set_kudos_kind_not_owner() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+
+    testing_env!(build_default_context(accounts(1), None, Some(MAX_GAS)).build());
+    let _ = kudos_contract.set_kudos_kind(KudosKind::Custom(b'm'), mentorship());
+}
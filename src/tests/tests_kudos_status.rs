@@ -0,0 +1,180 @@
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    IncrementalUniqueId, KudosError, KudosId, KudosStatus, BLOCK_SENDER_COST,
+    UPDATE_KUDOS_STATUS_COST, UPVOTE_KUDOS_COST,
+};
+use near_sdk::env::STORAGE_PRICE_PER_BYTE;
+use near_sdk::test_utils::accounts;
+use near_sdk::{env, testing_env, AccountId, Balance};
+
+#[test]
+fn test_on_kudos_status_updated() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    let _ = kudos_contract.on_kudos_status_updated(
+        accounts(1),
+        UPDATE_KUDOS_STATUS_COST.into(),
+        kudos_id.clone(),
+        KudosStatus::Hidden,
+        Ok(()),
+    );
+    assert_eq!(
+        kudos_contract.kudos_status.get(&kudos_id),
+        Some(&KudosStatus::Hidden)
+    );
+
+    let _ = kudos_contract.on_kudos_status_updated(
+        accounts(1),
+        UPDATE_KUDOS_STATUS_COST.into(),
+        kudos_id.clone(),
+        KudosStatus::Accepted,
+        Ok(()),
+    );
+    assert_eq!(kudos_contract.kudos_status.get(&kudos_id), None);
+
+    // Status remains unchanged if NEAR social db write failed
+    let _ = kudos_contract.on_kudos_status_updated(
+        accounts(1),
+        UPDATE_KUDOS_STATUS_COST.into(),
+        kudos_id.clone(),
+        KudosStatus::Declined,
+        Err(near_sdk::PromiseError::Failed),
+    );
+    assert_eq!(kudos_contract.kudos_status.get(&kudos_id), None);
+}
+
+#[test]
+fn test_accept_or_decline_not_pending_kudos() {
+    testing_env!(build_default_context(accounts(1), Some(UPDATE_KUDOS_STATUS_COST), None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    assert_eq!(
+        kudos_contract.accept_kudos(kudos_id.clone()).err(),
+        Some(KudosError::KudosNotPending)
+    );
+    assert_eq!(
+        kudos_contract.decline_kudos(kudos_id.clone()).err(),
+        Some(KudosError::KudosNotPending)
+    );
+
+    kudos_contract
+        .kudos_status
+        .insert(kudos_id.clone(), KudosStatus::Hidden);
+    assert_eq!(
+        kudos_contract.hide_kudos(kudos_id).err(),
+        Some(KudosError::KudosAlreadyHidden)
+    );
+}
+
+#[test]
+fn test_upvote_kudos_rejected_by_receiver() {
+    testing_env!(build_default_context(accounts(2), Some(UPVOTE_KUDOS_COST), None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let receiver_id = accounts(1);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    kudos_contract
+        .kudos_status
+        .insert(kudos_id.clone(), KudosStatus::Pending);
+    assert_eq!(
+        kudos_contract
+            .upvote_kudos(receiver_id.clone(), kudos_id.clone())
+            .err(),
+        Some(KudosError::KudosNotPublic)
+    );
+
+    testing_env!(build_default_context(receiver_id.clone(), Some(BLOCK_SENDER_COST), None).build());
+    kudos_contract.block_sender(accounts(2)).unwrap();
+
+    testing_env!(build_default_context(accounts(2), Some(UPVOTE_KUDOS_COST), None).build());
+    assert_eq!(
+        kudos_contract.upvote_kudos(receiver_id, kudos_id).err(),
+        Some(KudosError::SenderBlocked)
+    );
+}
+
+#[test]
+fn test_require_dings_acceptance_and_block_sender() {
+    testing_env!(build_default_context(accounts(1), None, None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+
+    kudos_contract.require_dings_acceptance(true);
+    assert!(kudos_contract
+        .dings_acceptance_required
+        .contains(&accounts(1)));
+    kudos_contract.require_dings_acceptance(false);
+    assert!(!kudos_contract
+        .dings_acceptance_required
+        .contains(&accounts(1)));
+
+    testing_env!(build_default_context(accounts(1), Some(BLOCK_SENDER_COST), None).build());
+    kudos_contract.block_sender(accounts(2)).unwrap();
+    assert!(kudos_contract.is_sender_blocked(&accounts(1), &accounts(2)));
+    assert!(!kudos_contract.is_sender_blocked(&accounts(2), &accounts(1)));
+}
+
+#[test]
+fn test_required_storage_to_block_sender() {
+    let receiver_id = AccountId::new_unchecked("r".repeat(64));
+    testing_env!(build_default_context(receiver_id, Some(BLOCK_SENDER_COST), None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+
+    let initial_storage = env::storage_usage();
+    kudos_contract
+        .block_sender(AccountId::new_unchecked("s".repeat(64)))
+        .unwrap();
+    // Blocklist is written to storage when collections are flushed
+    drop(kudos_contract);
+    let consumed = (env::storage_usage() - initial_storage) as Balance * STORAGE_PRICE_PER_BYTE;
+    assert!(
+        consumed <= BLOCK_SENDER_COST,
+        "`block_sender` call should cost at least {consumed} yoctoⓃ"
+    );
+}
+
+#[test]
+fn test_block_and_unblock_senders() {
+    testing_env!(build_default_context(accounts(1), Some(BLOCK_SENDER_COST), None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+
+    kudos_contract.block_sender(accounts(2)).unwrap();
+    kudos_contract.block_sender(accounts(3)).unwrap();
+    kudos_contract.block_sender(accounts(4)).unwrap();
+    assert_eq!(
+        kudos_contract.block_sender(accounts(3)),
+        Err(KudosError::SenderAlreadyBlocked)
+    );
+
+    assert_eq!(
+        kudos_contract.view_blocked_senders(accounts(1), None, None),
+        vec![accounts(2), accounts(3), accounts(4)]
+    );
+    assert_eq!(
+        kudos_contract.view_blocked_senders(accounts(1), Some(1), Some(1)),
+        vec![accounts(3)]
+    );
+    assert!(kudos_contract
+        .view_blocked_senders(accounts(2), None, None)
+        .is_empty());
+
+    testing_env!(build_default_context(accounts(1), None, None).build());
+    assert!(kudos_contract.unblock_sender(accounts(3)).is_ok());
+    assert_eq!(
+        kudos_contract.unblock_sender(accounts(3)).err(),
+        Some(KudosError::SenderNotBlocked)
+    );
+    assert!(!kudos_contract.is_sender_blocked(&accounts(1), &accounts(3)));
+    assert_eq!(
+        kudos_contract.view_blocked_senders(accounts(1), None, None),
+        vec![accounts(2), accounts(4)]
+    );
+}
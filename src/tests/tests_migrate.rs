@@ -0,0 +1,60 @@
+use crate::migrate::ContractV0;
+use crate::misc::RunningState;
+use crate::settings::{Settings, SettingsV0, VSettings};
+use crate::tests::utils::{build_default_context, MAX_GAS};
+use crate::types::StorageKey;
+use crate::{Contract, IncrementalUniqueId, KudosId};
+use near_sdk::store::LookupSet;
+use near_sdk::test_utils::accounts;
+use near_sdk::{env, testing_env, AccountId};
+
+#[test]
+fn test_migrate() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut last_incremental_id = IncrementalUniqueId::default();
+    last_incremental_id.inc();
+    let exchanged_kudos_id = KudosId::from(last_incremental_id.next());
+    last_incremental_id.inc();
+
+    let mut exchanged_kudos = LookupSet::new(StorageKey::Kudos);
+    exchanged_kudos.insert(exchanged_kudos_id.clone());
+
+    env::state_write(&ContractV0 {
+        owner_id: accounts(0),
+        running_state: RunningState::Running,
+        last_incremental_id,
+        external_db_id: Some(AccountId::new_unchecked("social.near".to_owned())),
+        iah_registry: AccountId::new_unchecked("iah_registry.near".to_owned()),
+        settings: VSettings::V0(SettingsV0 {
+            commentary_message_max_length: 500,
+            max_number_of_hashtags_per_kudos: 3,
+            hashtag_text_max_length: 16,
+            min_number_of_upvotes_to_exchange_kudos: 5,
+            pok_sbt_ttl: 1_000,
+        }),
+        exchanged_kudos,
+    });
+
+    let kudos_contract = Contract::migrate();
+
+    assert_eq!(kudos_contract.last_incremental_id.as_u64(), 2);
+    assert_eq!(
+        kudos_contract.external_db_id,
+        Some(AccountId::new_unchecked("social.near".to_owned()))
+    );
+    assert!(kudos_contract.exchanged_kudos.contains(&exchanged_kudos_id));
+
+    let settings = Settings::from(&kudos_contract.settings);
+    assert_eq!(settings.commentary_message_max_length, 500);
+    assert_eq!(settings.max_number_of_hashtags_per_kudos, 3);
+    assert_eq!(settings.hashtag_text_max_length, 16);
+    assert_eq!(settings.min_number_of_upvotes_to_exchange_kudos, 5);
+    assert_eq!(settings.pok_sbt_ttl, 1_000);
+    // New settings use default values
+    assert_eq!(
+        settings.max_number_of_mentions_per_kudos,
+        Settings::default().max_number_of_mentions_per_kudos
+    );
+    assert!(matches!(kudos_contract.settings, VSettings::Current(_)));
+}
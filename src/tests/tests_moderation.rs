@@ -0,0 +1,243 @@
+use crate::settings::SettingsView;
+use crate::storage::{SocialDbStorage, StorageBackend};
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::utils::build_reported_content_path;
+use crate::{
+    CommentId, Contract, IncrementalUniqueId, KudosError, KudosId, KudosStatus, Report,
+    ReportStatus, ReportedContent, SocialDbLayout, EXCHANGE_KUDOS_COST, MODERATE_REPORT_COST,
+    REPORT_CONTENT_COST,
+};
+use near_sdk::mock::VmAction;
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::{accounts, get_created_receipts};
+use near_sdk::{testing_env, AccountId, PromiseOrValue, RuntimeFeesConfig, VMConfig};
+
+fn report_verified(
+    kudos_contract: &mut Contract,
+    reporter_id: AccountId,
+    content: &ReportedContent,
+) -> Option<Report> {
+    let contract_id = AccountId::new_unchecked("kudos.near".to_owned());
+    testing_env!(
+        build_default_context(reporter_id.clone(), None, Some(MAX_GAS))
+            .current_account_id(contract_id.clone())
+            .build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    let content_path = build_reported_content_path(&contract_id, content);
+    let content_json = match content.comment_id.as_ref() {
+        Some(comment_id) => json!({
+            "kudos.near": {
+              "kudos": {
+                (content.receiver_id.as_str()): {
+                  (content.kudos_id.to_string()): {
+                    "comments": {
+                      (comment_id.to_string()): "eyJtIjoiaGVsbG8ifQ=="
+                    }
+                  }
+                }
+              }
+            }
+        }),
+        None => json!({
+            "kudos.near": {
+              "kudos": {
+                (content.receiver_id.as_str()): {
+                  (content.kudos_id.to_string()): {
+                    "sender_id": "bob.near"
+                  }
+                }
+              }
+            }
+        }),
+    };
+
+    match kudos_contract.on_report_verified(
+        reporter_id.clone(),
+        REPORT_CONTENT_COST.into(),
+        StorageBackend::SocialDb(SocialDbStorage::new(
+            AccountId::new_unchecked("social.near".to_owned()),
+            SocialDbLayout::Compat,
+        )),
+        content.clone(),
+        content_path,
+        Ok(content_json),
+    ) {
+        PromiseOrValue::Value(report) => Some(report),
+        PromiseOrValue::Promise(_) => None,
+    }
+}
+
+#[test]
+fn test_report_content_until_hidden() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    kudos_contract.update_settings(SettingsView {
+        min_number_of_reports_to_hide_content: Some(2),
+        ..Default::default()
+    });
+
+    let content = ReportedContent {
+        receiver_id: accounts(1),
+        kudos_id: KudosId::from(IncrementalUniqueId::default().next()),
+        comment_id: Some(CommentId::new_unchecked(2)),
+    };
+
+    assert_eq!(
+        report_verified(&mut kudos_contract, accounts(2), &content),
+        Some(Report {
+            count: 1,
+            status: ReportStatus::Open
+        })
+    );
+    // Report deposit is returned back if content is not hidden
+    let receipts = get_created_receipts();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].receiver_id, accounts(2));
+    assert!(matches!(
+        receipts[0].actions[..],
+        [VmAction::Transfer { deposit }] if deposit == REPORT_CONTENT_COST
+    ));
+    // Reported content is refunded if already reported by the same user
+    assert_eq!(
+        report_verified(&mut kudos_contract, accounts(2), &content),
+        None
+    );
+    assert_eq!(kudos_contract.reports.get(&content).unwrap().count, 1);
+
+    // The last required report hides content at NEAR social db first
+    assert_eq!(
+        report_verified(&mut kudos_contract, accounts(3), &content),
+        None
+    );
+    let _ = kudos_contract.on_reported_content_hidden(
+        accounts(3),
+        REPORT_CONTENT_COST.into(),
+        content.clone(),
+        Ok(()),
+    );
+    assert_eq!(
+        kudos_contract.view_report(
+            content.receiver_id.clone(),
+            content.kudos_id.clone(),
+            content.comment_id.clone()
+        ),
+        Some(Report {
+            count: 2,
+            status: ReportStatus::Hidden
+        })
+    );
+
+    // Further reports are counted without hiding content again
+    assert_eq!(
+        report_verified(&mut kudos_contract, accounts(4), &content),
+        Some(Report {
+            count: 3,
+            status: ReportStatus::Hidden
+        })
+    );
+}
+
+#[test]
+fn test_resolve_report() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    kudos_contract.add_moderator(accounts(1));
+
+    let content = ReportedContent {
+        receiver_id: accounts(2),
+        kudos_id: KudosId::from(IncrementalUniqueId::default().next()),
+        comment_id: None,
+    };
+
+    testing_env!(build_default_context(accounts(1), Some(MODERATE_REPORT_COST), None).build());
+    assert_eq!(
+        kudos_contract
+            .dismiss_report(content.receiver_id.clone(), content.kudos_id.clone(), None)
+            .err(),
+        Some(KudosError::ReportNotFound)
+    );
+
+    kudos_contract.record_report(content.clone(), accounts(3), None);
+    let _ = kudos_contract.on_report_resolved(
+        accounts(1),
+        MODERATE_REPORT_COST.into(),
+        content.clone(),
+        ReportStatus::Dismissed,
+        Ok(()),
+    );
+    assert_eq!(
+        kudos_contract.reports.get(&content).unwrap().status,
+        ReportStatus::Dismissed
+    );
+    assert_eq!(
+        kudos_contract
+            .uphold_report(content.receiver_id.clone(), content.kudos_id.clone(), None)
+            .err(),
+        Some(KudosError::ReportAlreadyResolved)
+    );
+    assert_eq!(
+        kudos_contract.verify_report_allowed(&content, &accounts(4)),
+        Err(KudosError::ContentAlreadyModerated)
+    );
+}
+
+#[test]
+fn test_exchange_hidden_kudos() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    kudos_contract.add_moderator(accounts(1));
+
+    let content = ReportedContent {
+        receiver_id: accounts(2),
+        kudos_id: KudosId::from(IncrementalUniqueId::default().next()),
+        comment_id: None,
+    };
+
+    testing_env!(build_default_context(accounts(1), Some(MODERATE_REPORT_COST), None).build());
+    kudos_contract.record_report(content.clone(), accounts(3), None);
+    let _ = kudos_contract.on_report_resolved(
+        accounts(1),
+        MODERATE_REPORT_COST.into(),
+        content.clone(),
+        ReportStatus::Upheld,
+        Ok(()),
+    );
+
+    // Receiver can't exchange kudos hidden by moderator
+    testing_env!(build_default_context(accounts(2), Some(EXCHANGE_KUDOS_COST), None).build());
+    assert_eq!(
+        kudos_contract
+            .exchange_kudos_for_sbt(content.kudos_id.clone())
+            .err(),
+        Some(KudosError::KudosNotPublic)
+    );
+
+    // Kudos declined by receiver can't be exchanged either
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().inc().next());
+    kudos_contract
+        .kudos_status
+        .insert(kudos_id.clone(), KudosStatus::Declined);
+    assert_eq!(
+        kudos_contract.exchange_kudos_for_sbt(kudos_id).err(),
+        Some(KudosError::KudosNotPublic)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not allowed")]
+fn test_resolve_report_by_not_moderator() {
+    testing_env!(build_default_context(accounts(1), Some(MODERATE_REPORT_COST), None).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let _ = kudos_contract.uphold_report(
+        accounts(2),
+        KudosId::from(IncrementalUniqueId::default().next()),
+        None,
+    );
+}
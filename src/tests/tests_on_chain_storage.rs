@@ -0,0 +1,331 @@
+use crate::storage::{KudosField, NewKudos, OnChainStorage, StorageBackend};
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    CommentId, Contract, EncodedCommentary, Hashtag, IncrementalUniqueId, KudosError, KudosId,
+    KudosKind, KudosStatus, ReportedContent,
+};
+use near_sdk::serde_json::json;
+use near_sdk::test_utils::accounts;
+use near_sdk::{env, testing_env, AccountId};
+
+fn store_kudos(kudos_contract: &mut Contract, receiver_id: AccountId, kudos_id: KudosId) {
+    kudos_contract.store_kudos(NewKudos {
+        sender_id: accounts(1),
+        receiver_id,
+        kudos_id,
+        created_at: 1689976833613.into(),
+        kind: KudosKind::Kudos,
+        message: "test message".to_owned(),
+        icon_cid: None,
+        attachments: vec![],
+        hashtags: Some(vec![Hashtag::new_unchecked("abc")]),
+        mentions: vec![accounts(3)],
+        status: None,
+    });
+}
+
+#[test]
+fn test_storage_backend() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    assert_eq!(
+        kudos_contract.storage_backend().unwrap_err(),
+        KudosError::ExternalDbNotSet
+    );
+
+    kudos_contract.use_on_chain_storage();
+    assert_eq!(
+        kudos_contract.storage_backend().unwrap(),
+        StorageBackend::OnChain(OnChainStorage)
+    );
+}
+
+#[test]
+#[should_panic(expected = "On-chain storage already used")]
+fn test_set_external_db_after_on_chain_storage() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    kudos_contract.use_on_chain_storage();
+    let _ = kudos_contract.set_external_db(AccountId::new_unchecked("social.near".to_owned()));
+}
+
+#[test]
+fn test_view_kudos() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let receiver_id = accounts(2);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    assert_eq!(
+        kudos_contract
+            .view_kudos(receiver_id.clone(), kudos_id.clone())
+            .unwrap(),
+        json!({})
+    );
+
+    store_kudos(&mut kudos_contract, receiver_id.clone(), kudos_id.clone());
+    assert_eq!(
+        kudos_contract
+            .view_kudos(receiver_id.clone(), kudos_id.clone())
+            .unwrap(),
+        json!({
+            (env::current_account_id().as_str()): {
+                "kudos": {
+                    "charlie": {
+                        "1": {
+                            "created_at": "1689976833613",
+                            "sender_id": "bob",
+                            "kind": "k",
+                            "message": "test message",
+                            "icon": "",
+                            "tags": "[\"abc\"]"
+                        }
+                    }
+                }
+            }
+        })
+    );
+
+    let comment_id = CommentId::new_unchecked(2);
+    kudos_contract
+        .store_upvote(receiver_id.clone(), kudos_id.clone(), accounts(3), None)
+        .unwrap();
+    kudos_contract
+        .store_comment(
+            receiver_id.clone(),
+            kudos_id.clone(),
+            comment_id.clone(),
+            EncodedCommentary::new_unchecked("eyJtIjoiaGVsbG8ifQ==".to_owned()),
+        )
+        .unwrap();
+    kudos_contract
+        .store_kudos_status(receiver_id.clone(), kudos_id.clone(), KudosStatus::Accepted)
+        .unwrap();
+    kudos_contract
+        .store_content_hidden(
+            ReportedContent {
+                receiver_id: receiver_id.clone(),
+                kudos_id: kudos_id.clone(),
+                comment_id: Some(comment_id),
+            },
+            true,
+        )
+        .unwrap();
+    kudos_contract
+        .store_content_hidden(
+            ReportedContent {
+                receiver_id: receiver_id.clone(),
+                kudos_id: kudos_id.clone(),
+                comment_id: None,
+            },
+            true,
+        )
+        .unwrap();
+
+    assert_eq!(
+        kudos_contract.view_kudos(receiver_id, kudos_id).unwrap(),
+        json!({
+            (env::current_account_id().as_str()): {
+                "kudos": {
+                    "charlie": {
+                        "1": {
+                            "created_at": "1689976833613",
+                            "sender_id": "bob",
+                            "kind": "k",
+                            "message": "test message",
+                            "icon": "",
+                            "tags": "[\"abc\"]",
+                            "status": "accepted",
+                            "hidden": "true",
+                            "upvotes": {
+                                "danny": ""
+                            },
+                            "comments": {
+                                "2": "eyJtIjoiaGVsbG8ifQ=="
+                            },
+                            "hidden_comments": {
+                                "2": "true"
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn test_view_kudos_fields() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let receiver_id = accounts(2);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    store_kudos(&mut kudos_contract, receiver_id.clone(), kudos_id.clone());
+    kudos_contract
+        .store_upvote(receiver_id.clone(), kudos_id.clone(), accounts(3), None)
+        .unwrap();
+    for id in 2..50 {
+        kudos_contract
+            .store_comment(
+                receiver_id.clone(),
+                kudos_id.clone(),
+                CommentId::new_unchecked(id),
+                EncodedCommentary::new_unchecked(format!("comment{id}")),
+            )
+            .unwrap();
+    }
+
+    // Only a requested commentary message is read
+    assert_eq!(
+        kudos_contract
+            .view_kudos_fields(
+                receiver_id.clone(),
+                kudos_id.clone(),
+                vec![KudosField::Comment(CommentId::new_unchecked(7))]
+            )
+            .unwrap(),
+        json!({
+            (env::current_account_id().as_str()): {
+                "kudos": {
+                    "charlie": {
+                        "1": {
+                            "comments": {
+                                "7": "comment7"
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    );
+
+    assert_eq!(
+        kudos_contract
+            .view_kudos_fields(
+                receiver_id,
+                kudos_id,
+                vec![
+                    KudosField::SenderId,
+                    KudosField::Upvotes,
+                    KudosField::Comment(CommentId::new_unchecked(50)),
+                ]
+            )
+            .unwrap(),
+        json!({
+            (env::current_account_id().as_str()): {
+                "kudos": {
+                    "charlie": {
+                        "1": {
+                            "sender_id": "bob",
+                            "upvotes": {
+                                "danny": ""
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn test_store_missing_kudos() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let receiver_id = accounts(2);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+
+    assert_eq!(
+        kudos_contract.store_upvote(receiver_id.clone(), kudos_id.clone(), accounts(3), None),
+        Err(KudosError::KudosNotFound)
+    );
+
+    store_kudos(&mut kudos_contract, receiver_id.clone(), kudos_id.clone());
+    assert_eq!(
+        kudos_contract.store_content_hidden(
+            ReportedContent {
+                receiver_id,
+                kudos_id,
+                comment_id: Some(CommentId::new_unchecked(2)),
+            },
+            true,
+        ),
+        Err(KudosError::ReportedContentNotFound)
+    );
+}
+
+#[test]
+fn test_stored_kudos_collections() {
+    testing_env!(build_default_context(accounts(0), None, Some(MAX_GAS)).build());
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    let receiver_id = accounts(2);
+    let kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+    let comment_id = CommentId::new_unchecked(2);
+
+    store_kudos(&mut kudos_contract, receiver_id.clone(), kudos_id.clone());
+    kudos_contract
+        .store_comment(
+            receiver_id.clone(),
+            kudos_id.clone(),
+            comment_id.clone(),
+            EncodedCommentary::new_unchecked("eyJtIjoiaGVsbG8ifQ==".to_owned()),
+        )
+        .unwrap();
+    env::state_write(&kudos_contract);
+    drop(kudos_contract);
+
+    // Upvotes and reactions are written under their own keys and read back together with kudos
+    let mut kudos_contract: Contract = env::state_read().unwrap();
+    let initial_storage = env::storage_usage();
+    for upvoter_id in [accounts(3), accounts(4), accounts(3)] {
+        kudos_contract
+            .store_upvote(receiver_id.clone(), kudos_id.clone(), upvoter_id, None)
+            .unwrap();
+    }
+    kudos_contract
+        .store_comment_reaction(
+            receiver_id.clone(),
+            kudos_id.clone(),
+            comment_id.clone(),
+            accounts(3),
+            "👍".to_owned(),
+        )
+        .unwrap();
+    env::state_write(&kudos_contract);
+    drop(kudos_contract);
+    assert!(env::storage_usage() > initial_storage);
+
+    let kudos_contract: Contract = env::state_read().unwrap();
+    let kudos = kudos_contract
+        .stored_kudos
+        .get(&(receiver_id.clone(), kudos_id.clone()))
+        .unwrap()
+        .as_current();
+    assert_eq!(kudos.upvotes.len(), 2);
+    assert_eq!(
+        kudos
+            .comments
+            .get(&comment_id)
+            .unwrap()
+            .as_current()
+            .reactions
+            .get(&accounts(3))
+            .map(String::as_str),
+        Some("👍")
+    );
+    assert_eq!(
+        kudos_contract.view_kudos(receiver_id, kudos_id).unwrap()
+            [env::current_account_id().as_str()]["kudos"]["charlie"]["1"]["upvotes"],
+        json!({
+            "danny": "",
+            "eugene": ""
+        })
+    );
+}
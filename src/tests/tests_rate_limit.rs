@@ -0,0 +1,166 @@
+use crate::settings::SettingsView;
+use crate::tests::utils::{build_default_context, MAX_GAS};
+use crate::{
+    Contract, IncrementalUniqueId, KudosError, KudosId, KudosKind, RateLimitedAction,
+    GIVE_KUDOS_COST,
+};
+use near_sdk::test_utils::accounts;
+use near_sdk::{testing_env, AccountId, PromiseError, RuntimeFeesConfig, VMConfig};
+
+const DAY_MS: u64 = 86_400_000;
+
+fn give_kudos(
+    kudos_contract: &mut Contract,
+    kind: KudosKind,
+    timestamp_ms: u64,
+) -> Result<(), KudosError> {
+    testing_env!(
+        build_default_context(accounts(1), Some(GIVE_KUDOS_COST), Some(MAX_GAS))
+            .block_timestamp(timestamp_ms * 1_000_000)
+            .build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    kudos_contract.give_kudos(
+        accounts(2),
+        Some(kind.clone()),
+        "test message with justification".to_owned(),
+        None,
+        None,
+        None,
+    )?;
+
+    // Action is counted once kudos is saved to a storage backend
+    save_kudos(kudos_contract, kind, Ok(()));
+
+    Ok(())
+}
+
+fn save_kudos(kudos_contract: &mut Contract, kind: KudosKind, result: Result<(), PromiseError>) {
+    let _ = kudos_contract.on_kudos_saved(
+        accounts(1),
+        GIVE_KUDOS_COST.into(),
+        accounts(2),
+        KudosId::from(IncrementalUniqueId::default().next()),
+        kind,
+        None,
+        None,
+        vec![],
+        result,
+    );
+}
+
+#[test]
+fn test_give_kudos_rate_limit() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+    kudos_contract.external_db_id = Some(AccountId::new_unchecked("social.near".to_owned()));
+    kudos_contract.update_settings(SettingsView {
+        max_dings_per_window: Some(2),
+        ..Default::default()
+    });
+
+    let now = 10 * DAY_MS;
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Ding, now),
+        Ok(())
+    );
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Ding, now),
+        Ok(())
+    );
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Ding, now),
+        Err(KudosError::RateLimitExceeded {
+            action: RateLimitedAction::Ding
+        })
+    );
+    // Other actions are limited separately
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Kudos, now),
+        Ok(())
+    );
+    assert_eq!(
+        kudos_contract.view_remaining_quota(accounts(1), RateLimitedAction::Ding),
+        0
+    );
+    assert_eq!(
+        kudos_contract.view_remaining_quota(accounts(1), RateLimitedAction::Kudos),
+        19
+    );
+
+    // Kudos which failed to be saved don't use up a quota
+    save_kudos(
+        &mut kudos_contract,
+        KudosKind::Kudos,
+        Err(PromiseError::Failed),
+    );
+    assert_eq!(
+        kudos_contract.view_remaining_quota(accounts(1), RateLimitedAction::Kudos),
+        19
+    );
+
+    // Dings given within the previous day are weighted by a remaining part of the sliding window
+    let now = 11 * DAY_MS + DAY_MS / 2;
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Ding, now),
+        Ok(())
+    );
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Ding, now),
+        Err(KudosError::RateLimitExceeded {
+            action: RateLimitedAction::Ding
+        })
+    );
+}
+
+#[test]
+fn test_prune_rate_limits() {
+    testing_env!(build_default_context(accounts(0), None, None).build());
+
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+    kudos_contract.external_db_id = Some(AccountId::new_unchecked("social.near".to_owned()));
+
+    let now = 10 * DAY_MS;
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Ding, now),
+        Ok(())
+    );
+    assert_eq!(
+        give_kudos(&mut kudos_contract, KudosKind::Kudos, now + DAY_MS),
+        Ok(())
+    );
+
+    // Counters still limit user within the last window
+    testing_env!(build_default_context(accounts(3), None, None)
+        .block_timestamp((now + DAY_MS) * 1_000_000)
+        .build());
+    assert_eq!(kudos_contract.prune_rate_limits(vec![accounts(1)]), 0);
+
+    // Only a counter of actions performed before the last window is removed
+    testing_env!(build_default_context(accounts(3), None, None)
+        .block_timestamp((now + 2 * DAY_MS) * 1_000_000)
+        .build());
+    assert_eq!(
+        kudos_contract.prune_rate_limits(vec![accounts(1), accounts(2)]),
+        1
+    );
+    assert!(!kudos_contract
+        .rate_limits
+        .contains_key(&(accounts(1), RateLimitedAction::Ding)));
+    assert!(kudos_contract
+        .rate_limits
+        .contains_key(&(accounts(1), RateLimitedAction::Kudos)));
+    assert_eq!(
+        kudos_contract.view_remaining_quota(accounts(1), RateLimitedAction::Ding),
+        kudos_contract.view_remaining_quota(accounts(2), RateLimitedAction::Ding)
+    );
+}
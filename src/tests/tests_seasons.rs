@@ -0,0 +1,285 @@
+use crate::tests::utils::{build_default_context, init_contract, TestStorage, MAX_GAS};
+use crate::{
+    Contract, IncrementalUniqueId, KudosError, KudosId, KudosKind, Season, SeasonSbt,
+    SeasonStanding, SeasonTally, SeasonView, GIVE_KUDOS_COST, PROOF_OF_KUDOS_SBT_MINT_COST,
+    UPVOTE_KUDOS_COST,
+};
+use near_sdk::test_utils::accounts;
+use near_sdk::{testing_env, AccountId, PromiseOrValue, RuntimeFeesConfig, VMConfig};
+
+fn set_context(now: u64, deposit: Option<u128>) {
+    testing_env!(
+        build_default_context(accounts(0), deposit, Some(MAX_GAS))
+            .block_timestamp(now * 1_000_000)
+            .build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+}
+
+/// Initialize a contract with a season which starts at 1_000 and ends at 2_000
+fn init_contract_with_season() -> Contract {
+    set_context(0, None);
+
+    let mut kudos_contract = init_contract(TestStorage::Unset);
+    assert_eq!(kudos_contract.add_season(1_000, 2_000), Ok(0));
+
+    kudos_contract
+}
+
+/// Save kudos as if it was given and saved at a storage backend at provided timestamp
+fn save_kudos(kudos_contract: &mut Contract, receiver_id: AccountId, kind: KudosKind, now: u64) {
+    set_context(now, None);
+
+    assert!(matches!(
+        kudos_contract.on_kudos_saved(
+            accounts(0),
+            GIVE_KUDOS_COST.into(),
+            receiver_id,
+            KudosId::from(IncrementalUniqueId::default().next()),
+            kind,
+            None,
+            None,
+            vec![],
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+}
+
+/// Save an upvote given by upvoter as if it was saved at a storage backend at provided timestamp
+fn save_upvote(
+    kudos_contract: &mut Contract,
+    upvoter_id: AccountId,
+    receiver_id: AccountId,
+    now: u64,
+) {
+    testing_env!(
+        build_default_context(upvoter_id.clone(), None, Some(MAX_GAS))
+            .block_timestamp(now * 1_000_000)
+            .build(),
+        VMConfig::free(),
+        RuntimeFeesConfig::free()
+    );
+
+    assert!(matches!(
+        kudos_contract.on_kudos_upvote_saved(
+            upvoter_id,
+            UPVOTE_KUDOS_COST.into(),
+            receiver_id,
+            KudosId::from(IncrementalUniqueId::default().next()),
+            None,
+            None,
+            Ok(()),
+        ),
+        PromiseOrValue::Value(_)
+    ));
+}
+
+fn standing(account_id: AccountId, kudos_received: u64, upvotes_received: u64) -> SeasonStanding {
+    SeasonStanding {
+        account_id,
+        tally: SeasonTally {
+            kudos_received,
+            upvotes_received,
+            kudos_exchanged: 0,
+        },
+    }
+}
+
+#[test]
+fn test_add_season() {
+    let mut kudos_contract = init_contract_with_season();
+
+    assert_eq!(
+        kudos_contract.add_season(3_000, 3_000),
+        Err(KudosError::InvalidSeasonPeriod)
+    );
+    assert_eq!(
+        kudos_contract.add_season(1_500, 2_500),
+        Err(KudosError::InvalidSeasonPeriod)
+    );
+    assert_eq!(kudos_contract.add_season(2_000, 3_000), Ok(1));
+
+    assert_eq!(
+        kudos_contract.view_seasons(Some(1), None),
+        vec![SeasonView {
+            season_id: 1,
+            season: Season {
+                start_at: 2_000,
+                end_at: 3_000,
+                closed: false,
+            },
+        }]
+    );
+    assert_eq!(
+        kudos_contract.view_season_top(2, None),
+        Err(KudosError::SeasonNotFound)
+    );
+}
+
+#[test]
+fn test_tally_current_season() {
+    let mut kudos_contract = init_contract_with_season();
+
+    assert_eq!(kudos_contract.add_season(2_000, 3_000), Ok(1));
+    assert_eq!(kudos_contract.add_season(4_000, 5_000), Ok(2));
+    // Seasons should follow each other in order of creation
+    assert_eq!(
+        kudos_contract.add_season(3_000, 4_000),
+        Err(KudosError::InvalidSeasonPeriod)
+    );
+
+    // Nothing is tallied between seasons, ended seasons are skipped
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 3_500);
+    assert_eq!(kudos_contract.current_season_id, 2);
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 4_000);
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Kudos, 4_500);
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Kudos, 4_500);
+    save_upvote(&mut kudos_contract, accounts(3), accounts(1), 4_500);
+    // Repeated upvote is not tallied
+    save_upvote(&mut kudos_contract, accounts(3), accounts(1), 4_500);
+
+    assert_eq!(kudos_contract.view_season_top(1, None), Ok(vec![]));
+    assert_eq!(
+        kudos_contract.view_season_top(2, None),
+        Ok(vec![
+            standing(accounts(2), 2, 0),
+            standing(accounts(1), 1, 1),
+        ])
+    );
+
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 5_000);
+    assert_eq!(kudos_contract.current_season_id, 3);
+}
+
+#[test]
+#[should_panic(expected = "Not allowed")]
+fn test_add_season_not_allowed() {
+    let mut kudos_contract = init_contract_with_season();
+
+    testing_env!(build_default_context(accounts(1), None, Some(MAX_GAS)).build());
+    let _ = kudos_contract.add_season(2_000, 3_000);
+}
+
+#[test]
+fn test_close_season() {
+    let mut kudos_contract = init_contract_with_season();
+
+    // Only kudos and upvotes saved within a season are tallied, dings are not tallied
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 999);
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 1_000);
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Kudos, 1_500);
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Ding, 1_500);
+    save_kudos(&mut kudos_contract, accounts(3), KudosKind::Kudos, 1_999);
+    save_upvote(&mut kudos_contract, accounts(1), accounts(3), 1_999);
+    save_upvote(&mut kudos_contract, accounts(2), accounts(4), 1_999);
+
+    assert_eq!(
+        kudos_contract.view_season_top(0, None),
+        Ok(vec![
+            standing(accounts(3), 1, 1),
+            standing(accounts(1), 1, 0),
+            standing(accounts(2), 1, 0),
+        ])
+    );
+
+    set_context(1_999, None);
+    assert_eq!(
+        kudos_contract.close_season(0, None).err(),
+        Some(KudosError::SeasonNotEnded)
+    );
+
+    set_context(2_000, None);
+    assert!(matches!(
+        kudos_contract.close_season(0, None),
+        Ok(PromiseOrValue::Value(snapshot)) if snapshot.len() == 3
+    ));
+    assert_eq!(
+        kudos_contract.close_season(0, None).err(),
+        Some(KudosError::SeasonAlreadyClosed)
+    );
+
+    // Snapshot of a closed season is frozen
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Kudos, 2_000);
+    assert_eq!(
+        kudos_contract.view_season_top(0, Some(1)),
+        Ok(vec![standing(accounts(3), 1, 1)])
+    );
+    assert!(kudos_contract.view_seasons(None, None)[0].season.closed);
+}
+
+#[test]
+fn test_close_season_with_sbt() {
+    let mut kudos_contract = init_contract_with_season();
+
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 1_000);
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Kudos, 1_000);
+    save_kudos(&mut kudos_contract, accounts(2), KudosKind::Kudos, 1_000);
+
+    set_context(2_000, Some(2 * PROOF_OF_KUDOS_SBT_MINT_COST));
+    assert!(matches!(
+        kudos_contract.close_season(
+            0,
+            Some(SeasonSbt {
+                class: 2,
+                recipients: 5,
+            }),
+        ),
+        Ok(PromiseOrValue::Promise(_))
+    ));
+
+    // Season is reopened if SBTs failed to mint
+    assert!(matches!(
+        kudos_contract.on_season_sbts_minted(
+            accounts(0),
+            (2 * PROOF_OF_KUDOS_SBT_MINT_COST).into(),
+            0,
+            Err(near_sdk::PromiseError::Failed),
+        ),
+        PromiseOrValue::Promise(_)
+    ));
+    assert!(!kudos_contract.view_seasons(None, None)[0].season.closed);
+
+    set_context(2_000, Some(PROOF_OF_KUDOS_SBT_MINT_COST));
+    assert!(matches!(
+        kudos_contract.close_season(
+            0,
+            Some(SeasonSbt {
+                class: 2,
+                recipients: 1,
+            }),
+        ),
+        Ok(PromiseOrValue::Promise(_))
+    ));
+    assert!(matches!(
+        kudos_contract.on_season_sbts_minted(
+            accounts(0),
+            PROOF_OF_KUDOS_SBT_MINT_COST.into(),
+            0,
+            Ok(vec![1]),
+        ),
+        PromiseOrValue::Value(snapshot) if snapshot == vec![
+            standing(accounts(2), 2, 0),
+            standing(accounts(1), 1, 0),
+        ]
+    ));
+    assert!(kudos_contract.view_seasons(None, None)[0].season.closed);
+}
+
+#[test]
+#[should_panic(expected = "Requires exact amount of attached deposit")]
+fn test_close_season_with_sbt_insufficient_deposit() {
+    let mut kudos_contract = init_contract_with_season();
+
+    save_kudos(&mut kudos_contract, accounts(1), KudosKind::Kudos, 1_000);
+
+    set_context(2_000, None);
+    let _ = kudos_contract.close_season(
+        0,
+        Some(SeasonSbt {
+            class: 2,
+            recipients: 1,
+        }),
+    );
+}
@@ -1,5 +1,7 @@
+use crate::{Contract, KudosError};
+use near_sdk::test_utils::{accounts, VMContextBuilder};
 use near_sdk::PromiseOrValue;
-use near_sdk::{test_utils::VMContextBuilder, AccountId, Balance, Gas};
+use near_sdk::{AccountId, Balance, Gas};
 
 pub const MAX_GAS: Gas = Gas(300_000_000_000_000);
 
@@ -17,12 +19,41 @@ pub fn build_default_context(
     builder
 }
 
+/// Storage backend of a contract initialized by [`init_contract`]
+pub enum TestStorage {
+    /// Storage backend is not configured
+    Unset,
+    /// NEAR social db deployed at "social.near"
+    SocialDb,
+    /// Kudos are stored in the state of the contract, requires a contract owner to be a predecessor
+    OnChain,
+}
+
+/// Initialize a contract owned by `accounts(0)` with a given storage backend. Testing context should be
+/// set before, so tests could call the contract within the same context.
+pub fn init_contract(storage: TestStorage) -> Contract {
+    let mut kudos_contract = Contract::init(
+        Some(accounts(0)),
+        AccountId::new_unchecked("iah_registry.near".to_owned()),
+    );
+
+    match storage {
+        TestStorage::Unset => (),
+        TestStorage::SocialDb => {
+            kudos_contract.external_db_id = Some(AccountId::new_unchecked("social.near".to_owned()))
+        }
+        TestStorage::OnChain => kudos_contract.use_on_chain_storage(),
+    }
+
+    kudos_contract
+}
+
 pub fn promise_or_value_result_into_result<T: std::fmt::Debug>(
-    value: Result<PromiseOrValue<T>, &'static str>,
-) -> Result<String, String> {
+    value: Result<PromiseOrValue<T>, KudosError>,
+) -> Result<String, KudosError> {
     match value {
         Ok(PromiseOrValue::Promise(_)) => Ok("Promise".to_owned()),
         Ok(PromiseOrValue::Value(res)) => Ok(format!("{res:?}")),
-        Err(e) => Err(e.to_owned()),
+        Err(e) => Err(e),
     }
 }
@@ -1,9 +1,14 @@
+use crate::{
+    KudosError, CID_MAX_LENGTH, HASHTAG_TRENDING_MAX_WINDOW, HASHTAG_USAGE_PERIOD,
+    KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH, KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH, LEAVE_COMMENT_COST,
+    LEAVE_COMMENT_V2_COST,
+};
 use cid::Cid;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::U64;
 use near_sdk::serde::{self, de, Deserialize, Deserializer, Serialize, Serializer};
 use near_sdk::serde_json::Value;
-use near_sdk::{serde_json, AccountId, BorshStorageKey};
+use near_sdk::{serde_json, AccountId, Balance, BorshStorageKey, CryptoHash};
 use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
@@ -37,7 +42,9 @@ impl Default for IncrementalUniqueId {
 }
 
 /// This type represents a unique identifier of the kudos.
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
 #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
 #[serde(crate = "near_sdk::serde")]
 pub struct KudosId(U64);
@@ -61,8 +68,10 @@ impl Display for KudosId {
 }
 
 /// This type represents a unique identifier of the commentary message.
-#[derive(Serialize, Deserialize, Clone)]
-#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, Eq, PartialEq))]
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
 #[serde(crate = "near_sdk::serde")]
 pub struct CommentId(U64);
 
@@ -102,6 +111,277 @@ impl Display for CommentId {
 #[derive(BorshStorageKey, BorshSerialize)]
 pub(crate) enum StorageKey {
     Kudos,
+    UpvotesByPair,
+    IahSbtIssuedAt,
+    DingsAcceptanceRequired,
+    BlockedSenders,
+    KudosStatus,
+    BlockedSendersOf {
+        receiver_id_hash: CryptoHash,
+    },
+    Reports,
+    Reporters,
+    Moderators,
+    RateLimits,
+    HumanityCache,
+    StoredKudos,
+    Hashtags,
+    CuratedHashtags,
+    KudosKinds,
+    DingQuorums,
+    AccountStats,
+    Seasons,
+    SeasonTallies,
+    SeasonTalliesOf {
+        season_id: SeasonId,
+    },
+    SeasonSnapshots,
+    CommentThreads,
+    UpvotePeriods,
+    StoredUpvotesOf {
+        kudos_id: KudosId,
+    },
+    StoredCommentsOf {
+        kudos_id: KudosId,
+    },
+    StoredReactionsOf {
+        kudos_id: KudosId,
+        comment_id: CommentId,
+    },
+    Leaderboard,
+    HashtagRanking,
+    SeasonRankings,
+    SeasonRankingOf {
+        season_id: SeasonId,
+    },
+}
+
+/// Position of an upvote among upvotes given by one user to kudos of another user within a period
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+pub struct PeriodUpvote {
+    /// The timestamp in milliseconds when the period started
+    pub period_start: u64,
+    /// Number of upvotes given within the period including this upvote
+    pub position: u32,
+}
+
+/// Content which could be reported by users, represented by kudos or a commentary message left to kudos
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReportedContent {
+    /// A valid [`AccountId`] of kudos receiver
+    pub receiver_id: AccountId,
+    /// Unique identifier of reported kudos or kudos where reported commentary message was left
+    pub kudos_id: KudosId,
+    /// Unique identifier of reported commentary message, if reported content is a commentary message
+    pub comment_id: Option<CommentId>,
+}
+
+/// Moderation status of reported content
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Default,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum ReportStatus {
+    /// Content is reported, but number of reports has not reached a threshold yet
+    #[default]
+    Open,
+    /// Content is automatically hidden, because number of reports reached a threshold
+    Hidden,
+    /// Report is upheld by moderator, content is hidden
+    Upheld,
+    /// Report is dismissed by moderator, content is visible
+    Dismissed,
+}
+
+impl ReportStatus {
+    /// Return [`bool`] which represents if report was resolved by moderator
+    pub fn is_resolved(&self) -> bool {
+        matches!(self, Self::Upheld | Self::Dismissed)
+    }
+
+    /// Return [`bool`] which represents if reported content should be hidden
+    pub fn is_hidden(&self) -> bool {
+        matches!(self, Self::Hidden | Self::Upheld)
+    }
+}
+
+/// Number of reports and moderation status of reported content
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct Report {
+    /// Number of unique users who reported content
+    pub count: u32,
+    /// Moderation status of reported content
+    pub status: ReportStatus,
+}
+
+/// Section of NEAR social db data copied by an external db migration, e.g. "{root_id}/kudos"
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum MigrationSection {
+    Kudos,
+    Hashtags,
+    Mentions,
+}
+
+impl MigrationSection {
+    /// Return the next [`MigrationSection`] to be copied, `None` if this is the last one
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::Kudos => Some(Self::Hashtags),
+            Self::Hashtags => Some(Self::Mentions),
+            Self::Mentions => None,
+        }
+    }
+}
+
+impl Display for MigrationSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Kudos => f.write_str("kudos"),
+            Self::Hashtags => f.write_str("hashtags"),
+            Self::Mentions => f.write_str("mentions"),
+        }
+    }
+}
+
+/// Progress of kudos data migration from the current NEAR social db to a new one
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExternalDbMigration {
+    /// A valid [`AccountId`] of NEAR social db smart contract where data is copied to
+    pub new_external_db_id: AccountId,
+    /// Section which is being copied, `None` if all sections are copied and external db could be switched
+    pub section: Option<MigrationSection>,
+    /// The last copied entry of the current section, it's a top-level key, e.g. receiver's account id for kudos
+    /// or a hashtag, if all entries of it are copied, or `{key}/{kudos_id}` if the key is copied partially
+    pub cursor: Option<String>,
+    /// Total number of copied entries
+    pub migrated_keys: u64,
+}
+
+impl ExternalDbMigration {
+    pub fn new(new_external_db_id: AccountId) -> Self {
+        Self {
+            new_external_db_id,
+            section: Some(MigrationSection::Kudos),
+            cursor: None,
+            migrated_keys: 0,
+        }
+    }
+}
+
+/// Layout of kudos data written to NEAR social db
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Default,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum SocialDbLayout {
+    /// Layout used by previous versions of this contract, hashtags are stored as JSON array serialized
+    /// to a string, e.g. "tags": "[\"firstkudos\"]", and icon is stored as an empty string if not provided
+    #[default]
+    Compat,
+    /// Hashtags are stored as an object, e.g. "tags": { "firstkudos": "" }, and icon is not stored
+    /// if not provided
+    V2,
+}
+
+/// Encoding of commentary messages [`EncodedCommentary`] written to a storage backend. Commentary messages
+/// of both versions are decoded, so the encoding could be switched at any time.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Default,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum CommentaryEncoding {
+    /// Encoding used by previous versions of this contract, a JSON object with short keys is encoded
+    /// to base64, e.g. "eyJtIjoiaGVsbG8iLC...", which adds about 33% to its length
+    #[default]
+    V1,
+    /// A JSON object with short keys and a version key is stored as is, e.g. "{\"v\":2,\"m\":\"hello\",...}"
+    V2,
+}
+
+impl CommentaryEncoding {
+    /// Return deposit required to leave a commentary message written in this encoding
+    pub fn leave_comment_cost(self) -> Balance {
+        match self {
+            Self::V1 => LEAVE_COMMENT_COST,
+            Self::V2 => LEAVE_COMMENT_V2_COST,
+        }
+    }
+}
+
+/// Cached result of user's humanity verification
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+pub struct HumanityCacheEntry {
+    /// Cache epoch when user was verified, the entry is invalid if cache epoch has changed since then
+    pub epoch: u32,
+    /// The timestamp in milliseconds until which user is considered to be verified
+    pub verified_until: u64,
+    /// Number of user's i-am-human SBTs by issuer [`AccountId`], used to verify humanity policy
+    pub tokens: Vec<(AccountId, u32)>,
+}
+
+/// Sliding window counter of actions performed by a user.
+///
+/// Number of actions performed within the last window is approximated by a number of actions performed
+/// within the current fixed window and a weighted number of actions performed within the previous one.
+#[derive(BorshDeserialize, BorshSerialize, Default, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+pub struct SlidingWindowCounter {
+    /// The timestamp in milliseconds when the current fixed window started
+    pub window_start: u64,
+    /// Number of actions performed within the current fixed window
+    pub current: u32,
+    /// Number of actions performed within the previous fixed window
+    pub previous: u32,
+}
+
+impl SlidingWindowCounter {
+    /// Return counter moved to a fixed window which includes provided timestamp
+    fn rolled(&self, window: u64, now: u64) -> Self {
+        let window_start = now - now % window;
+
+        match window_start.checked_sub(self.window_start) {
+            Some(0) => self.clone(),
+            Some(elapsed) if elapsed == window => Self {
+                window_start,
+                current: 0,
+                previous: self.current,
+            },
+            _ => Self {
+                window_start,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Return approximate number of actions performed within the last window before provided timestamp
+    pub fn count_at(&self, window: u64, now: u64) -> u32 {
+        let counter = self.rolled(window, now);
+        let remaining = window - (now - counter.window_start);
+        let previous = (counter.previous as u128 * remaining as u128 / window as u128) as u32;
+
+        previous.saturating_add(counter.current)
+    }
+
+    /// Increment number of actions performed within a fixed window which includes provided timestamp
+    pub fn inc(&mut self, window: u64, now: u64) {
+        *self = self.rolled(window, now);
+        self.current = self.current.saturating_add(1);
+    }
 }
 
 /// Commentary message data struct which serializes to base64-encoded [`String`] for subsequent store in NEAR social db
@@ -121,6 +401,9 @@ pub struct Commentary<'a> {
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct CommentaryRaw<'a> {
+    /// Version of encoding, which is not written by [`CommentaryEncoding::V1`]
+    #[serde(rename = "v", skip_serializing_if = "Option::is_none")]
+    pub version: Option<u8>,
     /// A message with escaped characters to guarantee safety of stringification
     #[serde(rename = "m")]
     pub message: &'a Value,
@@ -142,6 +425,7 @@ impl Serialize for Commentary<'_> {
     {
         let encoded = near_sdk::base64::encode(
             serde_json::to_string(&CommentaryRaw {
+                version: None,
                 message: self.message,
                 sender_id: self.sender_id,
                 timestamp: self.timestamp,
@@ -155,27 +439,38 @@ impl Serialize for Commentary<'_> {
 }
 
 /// This type represents a [`String`] for which only ascii alphanumeric characters, underscores and gyphens are allowed to use
-#[derive(Deserialize, Serialize, Ord, PartialOrd, PartialEq, Eq)]
-#[cfg_attr(not(target_arch = "wasm32"), derive(Clone, Debug))]
+#[derive(
+    BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone, Ord, PartialOrd, PartialEq, Eq,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Hashtag(String);
 
 impl Hashtag {
     /// Create [`Hashtag`] from ascii ref string, verify maximum length and check for allowed characters
-    pub fn new(hashtag: &str, max_lenth: usize) -> Result<Self, &'static str> {
+    pub fn new(hashtag: &str, max_lenth: usize) -> Result<Self, KudosError> {
         if hashtag.len() > max_lenth {
-            return Err("Hashtag max text length exceeded");
+            return Err(KudosError::HashtagMaxLengthExceeded);
         }
 
         if hashtag.contains(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '_' | '-')) {
-            return Err(
-                "Only alphanumeric characters, underscores and gyphens are allowed for hashtag",
-            );
+            return Err(KudosError::InvalidHashtag);
         }
 
         Ok(Self(hashtag.to_owned()))
     }
 
+    /// Return canonical form of [`Hashtag`] in lowercase with separators folded according to [`HashtagSeparator`]
+    pub fn canonicalize(self, separator: HashtagSeparator) -> Self {
+        let hashtag = self.0.to_ascii_lowercase();
+
+        Self(match separator {
+            HashtagSeparator::Keep => hashtag,
+            HashtagSeparator::Underscore => hashtag.replace('-', "_"),
+            HashtagSeparator::Hyphen => hashtag.replace('_', "-"),
+        })
+    }
+
     /// Creates [`Hashtag`] from ref string without length and characters check
     #[cfg(not(target_arch = "wasm32"))]
     pub fn new_unchecked(hashtag: &str) -> Self {
@@ -183,10 +478,228 @@ impl Hashtag {
     }
 }
 
-/// This type represents a JSON [`String`] view of [`Commentary`]
+/// Kudos message text which allows to use a markdown subset and to mention users, e.g. `@alex.near`.
+///
+/// Supported markdown subset includes emphasis, strikethrough, inline code and http(s) links.
+/// Html tags, images and links with other schemes are not allowed. Mentions inside inline code are ignored.
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(not(target_arch = "wasm32"), derive(Clone, Debug, PartialEq))]
 #[serde(crate = "near_sdk::serde")]
+pub struct KudosMessage {
+    pub text: String,
+    /// Unique mentioned users in order of appearance
+    pub mentions: Vec<AccountId>,
+}
+
+impl KudosMessage {
+    /// Create [`KudosMessage`] from a text, verify maximum length, markup and mentioned users
+    pub fn new(text: String, max_length: usize, max_mentions: usize) -> Result<Self, KudosError> {
+        if text.len() > max_length {
+            return Err(KudosError::MessageMaxLengthExceeded);
+        }
+
+        let is_account_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.');
+        let is_html_start = |c: char| c.is_ascii_alphabetic() || matches!(c, '/' | '!');
+        let mut mentions = Vec::<AccountId>::new();
+        let mut in_code = false;
+        let mut prev_char = None;
+
+        for (idx, c) in text.char_indices() {
+            let rest = &text[idx + c.len_utf8()..];
+
+            match c {
+                '`' => in_code = !in_code,
+                _ if in_code => (),
+                '<' if rest.starts_with(is_html_start) => {
+                    return Err(KudosError::InvalidMessageMarkup);
+                }
+                '!' if rest.starts_with('[') => return Err(KudosError::InvalidMessageMarkup),
+                ']' if rest.starts_with('(')
+                    && !rest[1..].starts_with("https://")
+                    && !rest[1..].starts_with("http://") =>
+                {
+                    return Err(KudosError::InvalidMessageMarkup);
+                }
+                // Skip `@` inside words, e.g. emails
+                '@' if !prev_char.map(is_account_char).unwrap_or(false) => {
+                    let len = rest
+                        .find(|c: char| !is_account_char(c))
+                        .unwrap_or(rest.len());
+                    // Trailing dot is a punctuation mark, e.g. "Thanks @alex.near."
+                    let mention = rest[..len].trim_end_matches('.');
+
+                    if !mention.is_empty() {
+                        let account_id = AccountId::from_str(mention).map_err(|_| {
+                            KudosError::InvalidMention {
+                                mention: mention.to_owned(),
+                            }
+                        })?;
+
+                        if !mentions.contains(&account_id) {
+                            mentions.push(account_id);
+                        }
+                    }
+                }
+                _ => (),
+            }
+
+            prev_char = Some(c);
+        }
+
+        if in_code {
+            return Err(KudosError::InvalidMessageMarkup);
+        }
+
+        if mentions.len() > max_mentions {
+            return Err(KudosError::MentionsLimitExceeded);
+        }
+
+        Ok(Self { text, mentions })
+    }
+}
+
+impl Display for Hashtag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Folding of hashtag separators, so e.g. `love_ndc` and `love-ndc` become the same hashtag
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Default,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum HashtagSeparator {
+    /// Underscores and hyphens are different separators
+    #[default]
+    Keep,
+    /// Hyphens are replaced with underscores
+    Underscore,
+    /// Underscores are replaced with hyphens
+    Hyphen,
+}
+
+/// Restriction of hashtags which could be used for kudos
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Default,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum HashtagMode {
+    /// Any valid hashtags are allowed
+    #[default]
+    Open,
+    /// Only curated hashtags are allowed
+    CuratedOnly,
+    /// At least one curated hashtag is required, other valid hashtags are allowed as well
+    CuratedRequired,
+}
+
+/// Official hashtag curated by an owner or moderators of this contract, e.g. for NDC campaigns
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct CuratedHashtag {
+    pub description: String,
+    /// The timestamp in milliseconds when hashtag stops being curated, never expires if not set
+    pub expires_at: Option<u64>,
+}
+
+impl CuratedHashtag {
+    /// Return [`bool`] which represents if hashtag is still curated at provided timestamp
+    pub fn is_active(&self, now: u64) -> bool {
+        self.expires_at
+            .map(|expires_at| now < expires_at)
+            .unwrap_or(true)
+    }
+}
+
+/// View of curated hashtag [`CuratedHashtag`]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct CuratedHashtagView {
+    pub hashtag: Hashtag,
+    #[serde(flatten)]
+    pub info: CuratedHashtag,
+}
+
+/// Usage statistics of a hashtag counted by this contract
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+pub struct HashtagStats {
+    /// Total number of kudos given with a hashtag
+    pub count: u64,
+    /// Number of kudos given with a hashtag per period, where a period is represented by the timestamp in
+    /// milliseconds when it started. Only periods within [`HASHTAG_TRENDING_MAX_WINDOW`](crate::HASHTAG_TRENDING_MAX_WINDOW) are kept.
+    pub periods: Vec<(u64, u32)>,
+}
+
+impl HashtagStats {
+    /// Increment number of kudos given with a hashtag at provided timestamp and forget outdated periods
+    pub fn inc(&mut self, now: u64) {
+        let period_start = now - now % HASHTAG_USAGE_PERIOD;
+
+        self.count = self.count.saturating_add(1);
+        self.periods
+            .retain(|(start, _)| start + HASHTAG_TRENDING_MAX_WINDOW > period_start);
+
+        match self.periods.last_mut() {
+            Some((start, count)) if *start == period_start => *count = count.saturating_add(1),
+            _ => self.periods.push((period_start, 1)),
+        }
+    }
+
+    /// Return number of kudos given with a hashtag within periods which overlap a window before provided timestamp
+    pub fn count_within(&self, window: u64, now: u64) -> u64 {
+        let since = now.saturating_sub(window);
+        let since = since - since % HASHTAG_USAGE_PERIOD;
+
+        self.periods
+            .iter()
+            .filter(|(start, _)| *start >= since)
+            .map(|(_, count)| *count as u64)
+            .sum()
+    }
+}
+
+/// Position of a hashtag [`Hashtag`] in a ranking of top hashtags. Counter is inverted, so positions are
+/// ordered by a total number of kudos given with a hashtag descending, then by a hashtag.
+#[derive(BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct HashtagPosition {
+    count_inv: u64,
+    pub hashtag: Hashtag,
+}
+
+impl HashtagPosition {
+    pub fn new(hashtag: &Hashtag, stats: &HashtagStats) -> Self {
+        Self {
+            count_inv: u64::MAX - stats.count,
+            hashtag: hashtag.clone(),
+        }
+    }
+
+    /// Return a total number of kudos given with a hashtag
+    pub fn count(&self) -> u64 {
+        u64::MAX - self.count_inv
+    }
+}
+
+/// Number of kudos given with a hashtag
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct HashtagUsage {
+    pub hashtag: Hashtag,
+    pub count: u64,
+}
+
+/// This type represents a JSON [`String`] view of [`Commentary`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
 pub struct EncodedCommentary(String);
 
 impl EncodedCommentary {
@@ -194,6 +707,59 @@ impl EncodedCommentary {
         self.0.as_str()
     }
 
+    /// Return [`AccountId`] of a commentary message sender, `None` if commentary can't be decoded
+    pub fn sender_id(&self) -> Option<AccountId> {
+        self.decode()?.get("s")?.as_str()?.parse().ok()
+    }
+
+    /// Return parent [`CommentId`] of a commentary message which was replied, `None` if commentary
+    /// is not a reply or can't be decoded
+    pub fn parent_comment_id(&self) -> Option<CommentId> {
+        serde_json::from_value(self.decode()?.get_mut("p")?.take()).ok()
+    }
+
+    /// Return [`CommentaryEncoding`] of a commentary message. Base64 alphabet has no curly brackets,
+    /// so a JSON object is distinguished from base64-encoded data by its first character.
+    pub fn encoding(&self) -> CommentaryEncoding {
+        match self.0.starts_with('{') {
+            true => CommentaryEncoding::V2,
+            false => CommentaryEncoding::V1,
+        }
+    }
+
+    /// Encode commentary message [`Commentary`] according to provided [`CommentaryEncoding`]
+    pub fn encode(
+        commentary: &Commentary<'_>,
+        encoding: CommentaryEncoding,
+    ) -> Result<Self, KudosError> {
+        match encoding {
+            CommentaryEncoding::V1 => Self::try_from(commentary),
+            CommentaryEncoding::V2 => serde_json::to_string(&CommentaryRaw {
+                version: Some(2),
+                message: commentary.message,
+                sender_id: commentary.sender_id,
+                timestamp: commentary.timestamp,
+                parent_comment_id: commentary.parent_comment_id,
+            })
+            .map(Self)
+            .map_err(|_| KudosError::SerializationFailure),
+        }
+    }
+
+    /// Decode commentary message of any [`CommentaryEncoding`] to JSON [`Value`]
+    fn decode(&self) -> Option<Value> {
+        match self.encoding() {
+            CommentaryEncoding::V1 => {
+                let decoded = near_sdk::base64::decode(&self.0).ok()?;
+
+                serde_json::from_slice::<Value>(&decoded).ok()
+            }
+            CommentaryEncoding::V2 => serde_json::from_str::<Value>(&self.0)
+                .ok()
+                .filter(|value| value.get("v").and_then(Value::as_u64) == Some(2)),
+        }
+    }
+
     /// Creates [`EncodedCommentary`] from [`String`] without verification if it can be deserialized
     #[cfg(not(target_arch = "wasm32"))]
     pub fn new_unchecked(encoded: String) -> Self {
@@ -202,7 +768,7 @@ impl EncodedCommentary {
 }
 
 impl TryFrom<&Commentary<'_>> for EncodedCommentary {
-    type Error = String;
+    type Error = KudosError;
 
     fn try_from(value: &Commentary<'_>) -> Result<Self, Self::Error> {
         serde_json::to_value(value)
@@ -211,7 +777,7 @@ impl TryFrom<&Commentary<'_>> for EncodedCommentary {
                     .map(|s| Self(s.to_owned()))
                     .ok_or(serde::ser::Error::custom("Not a string"))
             })
-            .map_err(|e| format!("Unable to encode commentary: {e}"))
+            .map_err(|_| KudosError::SerializationFailure)
     }
 }
 
@@ -222,18 +788,27 @@ impl Display for EncodedCommentary {
 }
 
 /// This type represents a wrapped serializable version of [`Cid`]
+#[derive(Clone)]
 pub struct WrappedCid(Cid);
 
 impl WrappedCid {
     /// Creates [`WrappedCid`] from ref string
-    pub fn new(cid: &str) -> Result<Self, &'static str> {
-        if cid.len() > 64 {
-            return Err("Cid is too long");
+    pub fn new(cid: &str) -> Result<Self, KudosError> {
+        if cid.len() > CID_MAX_LENGTH {
+            return Err(KudosError::CidTooLong);
         }
         Cid::from_str(cid)
-            .map_err(|_| "Not a valid Cid")
+            .map_err(|_| KudosError::InvalidCid)
             .map(WrappedCid)
     }
+
+    /// Return [`bool`] which represents if codec and multihash of this CID are allowed
+    pub fn is_allowed(&self, codecs: &[CidCodec], multihashes: &[CidMultihash]) -> bool {
+        codecs.iter().any(|codec| codec.code() == self.0.codec())
+            && multihashes
+                .iter()
+                .any(|multihash| multihash.code() == self.0.hash().code())
+    }
 }
 
 impl Display for WrappedCid {
@@ -249,9 +824,8 @@ impl<'de> Deserialize<'de> for WrappedCid {
     {
         let cid_text = <String as Deserialize>::deserialize(deserializer)?;
 
-        Cid::from_str(&cid_text)
-            .map(WrappedCid)
-            .map_err(|e| de::Error::custom(format!("Failed to deserialize CID: {e:?}")))
+        WrappedCid::new(&cid_text)
+            .map_err(|e| de::Error::custom(format!("Failed to deserialize CID: {}", e.message())))
     }
 }
 
@@ -264,37 +838,638 @@ impl Serialize for WrappedCid {
     }
 }
 
-/// The type of a kudos given
-///
-/// [`Kudos`](KudosKind::Kudos) represents a positive kudos, while [`Ding`](KudosKind::Ding) represents a negative one
-#[derive(Serialize, Deserialize, Default, PartialEq)]
-#[cfg_attr(not(target_arch = "wasm32"), derive(Clone, Debug))]
+/// Multicodec of content addressed by a CID [`WrappedCid`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "kebab-case")]
+pub enum CidCodec {
+    /// Raw binary content
+    Raw,
+    /// MerkleDAG protobuf content, every V0 CID has this codec
+    DagPb,
+}
+
+impl CidCodec {
+    /// Return multicodec code of this codec
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::Raw => 0x55,
+            Self::DagPb => 0x70,
+        }
+    }
+}
+
+/// Multihash type of a CID [`WrappedCid`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub enum CidMultihash {
+    /// SHA2-256 hash, every V0 CID has this multihash type
+    #[serde(rename = "sha2-256")]
+    Sha2_256,
+    /// BLAKE2b-256 hash
+    #[serde(rename = "blake2b-256")]
+    Blake2b256,
+    /// BLAKE3 hash
+    #[serde(rename = "blake3")]
+    Blake3,
+}
+
+impl CidMultihash {
+    /// Return multicodec code of this multihash type
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::Sha2_256 => 0x12,
+            Self::Blake2b256 => 0xb220,
+            Self::Blake3 => 0x1e,
+        }
+    }
+}
+
+/// Attachment of kudos stored at ipfs, e.g. a screenshot or a document used as evidence
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct KudosAttachment {
+    /// A valid CID of attached content at ipfs
+    pub cid: WrappedCid,
+    /// MIME type of attached content, e.g. "image/png"
+    pub mime_type: String,
+    /// Optional caption of attached content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+impl KudosAttachment {
+    /// Verify MIME type in "type/subtype" format and maximum lengths of MIME type and caption
+    pub fn validate(&self) -> Result<(), KudosError> {
+        let is_token = |part: &str| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&b))
+        };
+
+        let is_mime_type = match self.mime_type.split_once('/') {
+            Some((kind, subtype)) => is_token(kind) && is_token(subtype),
+            None => false,
+        };
+
+        if !is_mime_type || self.mime_type.len() > KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH {
+            return Err(KudosError::InvalidAttachmentMimeType);
+        }
+
+        if self
+            .caption
+            .as_ref()
+            .is_some_and(|caption| caption.len() > KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH)
+        {
+            return Err(KudosError::AttachmentCaptionMaxLengthExceeded);
+        }
+
+        Ok(())
+    }
+}
+
+/// Media of kudos, an optional icon and attachments [`KudosAttachment`]
+#[derive(Serialize, Deserialize, Default)]
 #[serde(crate = "near_sdk::serde")]
+pub struct KudosMedia {
+    pub icon_cid: Option<WrappedCid>,
+    pub attachments: Vec<KudosAttachment>,
+}
+
+/// The type of a kudos given, serialized as a single lowercase letter
+///
+/// [`Kudos`](KudosKind::Kudos) ("k") represents a positive kudos, while [`Ding`](KudosKind::Ding) ("d")
+/// represents a negative one. Other kinds [`Custom`](KudosKind::Custom), e.g. mentorship or code review,
+/// should be registered by an owner/admin with their information [`KudosKindInfo`].
+#[derive(BorshDeserialize, BorshSerialize, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
 pub enum KudosKind {
     #[default]
-    #[serde(rename = "k")]
     Kudos,
-    #[serde(rename = "d")]
     Ding,
+    /// Registered kind of kudos represented by a lowercase ASCII letter other than "k" and "d"
+    Custom(u8),
+}
+
+impl KudosKind {
+    /// Creates [`KudosKind`] from a single lowercase letter
+    pub fn new(code: &str) -> Result<Self, KudosError> {
+        match code.as_bytes() {
+            b"k" => Ok(Self::Kudos),
+            b"d" => Ok(Self::Ding),
+            &[letter] if letter.is_ascii_lowercase() => Ok(Self::Custom(letter)),
+            _ => Err(KudosError::InvalidKudosKind),
+        }
+    }
+
+    /// Return information [`KudosKindInfo`] of built-in kinds, which is used unless it was
+    /// overridden by an owner/admin
+    pub fn builtin_info(&self) -> Option<KudosKindInfo> {
+        match self {
+            Self::Kudos => Some(KudosKindInfo {
+                name: "Kudos".to_owned(),
+                icon_cid: None,
+                exchangeable: true,
+                allowed_in_batch: true,
+                needs_upvotes: true,
+            }),
+            Self::Ding => Some(KudosKindInfo {
+                name: "Ding".to_owned(),
+                icon_cid: None,
+                exchangeable: false,
+                allowed_in_batch: false,
+                needs_upvotes: true,
+            }),
+            Self::Custom(_) => None,
+        }
+    }
 }
 
 impl Display for KudosKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let kind = match self {
-            Self::Kudos => "k",
-            Self::Ding => "d",
+        match self {
+            Self::Kudos => write!(f, "k"),
+            Self::Ding => write!(f, "d"),
+            Self::Custom(letter) => write!(f, "{}", char::from(*letter)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KudosKind {
+    fn deserialize<D>(deserializer: D) -> Result<KudosKind, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let code = <String as Deserialize>::deserialize(deserializer)?;
+
+        KudosKind::new(&code).map_err(|_| {
+            de::Error::custom(format!(
+                "Invalid kudos kind `{code}`, expected a single lowercase letter"
+            ))
+        })
+    }
+}
+
+impl Serialize for KudosKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Information about a kind of kudos [`KudosKind`] and its exchange rules
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct KudosKindInfo {
+    /// Human readable name of a kind, e.g. "Mentorship"
+    pub name: String,
+    /// Optional valid CID of an icon (image) at ipfs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_cid: Option<String>,
+    /// Whether kudos of this kind could be exchanged for ProofOfKudos SBT
+    pub exchangeable: bool,
+    /// Whether kudos of this kind could be given to multiple receivers at once. Entry points which give
+    /// kudos to multiple receivers in a single call should reject kinds which are not allowed in batch.
+    pub allowed_in_batch: bool,
+    /// Whether kudos of this kind should reach a minimum number of upvotes to be exchanged
+    pub needs_upvotes: bool,
+}
+
+/// Kind of kudos [`KudosKind`] with its information [`KudosKindInfo`]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct KudosKindView {
+    pub kind: KudosKind,
+    #[serde(flatten)]
+    pub info: KudosKindInfo,
+}
+
+/// The type of an action which number is limited per user within a window
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum RateLimitedAction {
+    Kudos,
+    Ding,
+    Upvote,
+    Comment,
+}
+
+impl RateLimitedAction {
+    /// All actions which are rate limited
+    pub const ALL: [Self; 4] = [Self::Kudos, Self::Ding, Self::Upvote, Self::Comment];
+}
+
+impl From<&KudosKind> for RateLimitedAction {
+    fn from(kind: &KudosKind) -> Self {
+        match kind {
+            KudosKind::Kudos | KudosKind::Custom(_) => Self::Kudos,
+            KudosKind::Ding => Self::Ding,
+        }
+    }
+}
+
+/// The type of an action which requires user to be verified as a human
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum HumanityAction {
+    Kudos,
+    Ding,
+    Upvote,
+    Comment,
+    Exchange,
+    Report,
+}
+
+impl From<&KudosKind> for HumanityAction {
+    fn from(kind: &KudosKind) -> Self {
+        match kind {
+            KudosKind::Kudos | KudosKind::Custom(_) => Self::Kudos,
+            KudosKind::Ding => Self::Ding,
+        }
+    }
+}
+
+/// Requirement which user's i-am-human SBTs should satisfy for user to be verified as a human
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+pub struct HumanityRequirement {
+    /// Accepted i-am-human SBT issuers, SBTs issued by any issuer are accepted if empty
+    #[serde(default)]
+    pub issuers: Vec<AccountId>,
+    /// Minimum number of i-am-human SBTs issued by accepted issuers
+    pub min_tokens: u8,
+}
+
+impl HumanityRequirement {
+    /// Return [`bool`] which represents if i-am-human SBTs issued by provided issuer [`AccountId`] are accepted
+    pub fn accepts(&self, issuer_id: &AccountId) -> bool {
+        self.issuers.is_empty() || self.issuers.contains(issuer_id)
+    }
+
+    /// Return [`bool`] which represents if provided numbers of i-am-human SBTs by issuer [`AccountId`]
+    /// satisfy this requirement. At least one SBT is always required.
+    pub fn is_satisfied_by<'a>(
+        &self,
+        tokens: impl IntoIterator<Item = (&'a AccountId, usize)>,
+    ) -> bool {
+        let number_of_tokens: usize = tokens
+            .into_iter()
+            .filter(|(issuer_id, _)| self.accepts(issuer_id))
+            .map(|(_, number_of_tokens)| number_of_tokens)
+            .sum();
+
+        number_of_tokens >= self.min_tokens.max(1) as usize
+    }
+}
+
+impl Default for HumanityRequirement {
+    fn default() -> Self {
+        Self {
+            issuers: vec![],
+            min_tokens: 1,
+        }
+    }
+}
+
+/// Humanity requirements [`HumanityRequirement`] for every action [`HumanityAction`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct HumanityPolicy {
+    #[serde(default)]
+    pub kudos: HumanityRequirement,
+    #[serde(default)]
+    pub ding: HumanityRequirement,
+    #[serde(default)]
+    pub upvote: HumanityRequirement,
+    #[serde(default)]
+    pub comment: HumanityRequirement,
+    #[serde(default)]
+    pub exchange: HumanityRequirement,
+    #[serde(default)]
+    pub report: HumanityRequirement,
+}
+
+impl HumanityPolicy {
+    /// Return humanity requirement [`HumanityRequirement`] for provided action [`HumanityAction`]
+    pub fn requirement(&self, action: HumanityAction) -> &HumanityRequirement {
+        match action {
+            HumanityAction::Kudos => &self.kudos,
+            HumanityAction::Ding => &self.ding,
+            HumanityAction::Upvote => &self.upvote,
+            HumanityAction::Comment => &self.comment,
+            HumanityAction::Exchange => &self.exchange,
+            HumanityAction::Report => &self.report,
+        }
+    }
+}
+
+/// The status of kudos set by its receiver
+///
+/// Kudos without status are public. [`Proposed`](KudosStatus::Proposed) status is set for dings which
+/// await a quorum of upvotes or a moderator approval, and [`Pending`](KudosStatus::Pending) status is set
+/// for dings given to receivers who require dings to be accepted before they become public.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum KudosStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Hidden,
+    Proposed,
+}
+
+impl KudosStatus {
+    /// Return [`bool`] which represents if kudos with this status is visible
+    pub fn is_public(&self) -> bool {
+        matches!(self, Self::Accepted)
+    }
+
+    /// Return [`bool`] which represents if kudos with this status could be upvoted.
+    /// Proposed dings are upvoted to reach a quorum.
+    pub fn is_upvotable(&self) -> bool {
+        matches!(self, Self::Accepted | Self::Proposed)
+    }
+}
+
+impl Display for KudosStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            Self::Pending => "pending",
+            Self::Accepted => "accepted",
+            Self::Declined => "declined",
+            Self::Hidden => "hidden",
+            Self::Proposed => "proposed",
         };
 
-        write!(f, "{kind}")
+        write!(f, "{status}")
+    }
+}
+
+/// Distinct human upvoters of a proposed ding [`KudosStatus::Proposed`], which becomes public once
+/// a quorum is reached
+#[derive(BorshDeserialize, BorshSerialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+pub struct DingQuorum {
+    /// Sender of a ding, who is counted as a ding sender once a ding is published
+    pub sender_id: AccountId,
+    /// Receiver of a ding, used to publish a ding approved by a moderator
+    pub receiver_id: AccountId,
+    pub upvoters: Vec<AccountId>,
+}
+
+impl DingQuorum {
+    /// Return [`bool`] which represents if an upvote of a user [`AccountId`] reaches a quorum.
+    /// Repeated upvotes of the same user are not counted.
+    pub fn is_reached_by(&self, upvoter_id: &AccountId, quorum: usize) -> bool {
+        !self.upvoters.contains(upvoter_id) && self.upvoters.len() + 1 >= quorum
+    }
+
+    /// Memorise an upvote of a user [`AccountId`] unless it was already counted
+    pub fn add_upvoter(&mut self, upvoter_id: &AccountId) {
+        if !self.upvoters.contains(upvoter_id) {
+            self.upvoters.push(upvoter_id.clone());
+        }
+    }
+}
+
+/// Activity counters of a user, updated once kudos, upvotes, commentary messages and ProofOfKudos SBTs
+/// are saved. Kudos of custom kinds are counted as kudos.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStats {
+    pub kudos_given: u64,
+    pub kudos_received: u64,
+    pub dings_given: u64,
+    pub dings_received: u64,
+    pub upvotes_given: u64,
+    pub upvotes_received: u64,
+    pub comments_given: u64,
+    pub comments_received: u64,
+    /// Number of ProofOfKudos SBTs minted in exchange for upvoted kudos
+    pub pok_sbts: u64,
+}
+
+/// View of user's activity counters [`AccountStats`], used by a leaderboard
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStatsView {
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub stats: AccountStats,
+}
+
+/// Position of a user [`AccountId`] in a leaderboard of top kudos receivers. Counters are inverted, so
+/// positions are ordered by a number of received kudos and upvotes descending, then by [`AccountId`].
+#[derive(BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct LeaderboardPosition {
+    kudos_received_inv: u64,
+    upvotes_received_inv: u64,
+    pub account_id: AccountId,
+}
+
+impl LeaderboardPosition {
+    /// Return [`LeaderboardPosition`] of a user by activity counters [`AccountStats`], `None` for users
+    /// without received kudos, who are not ranked
+    pub fn new(account_id: &AccountId, stats: &AccountStats) -> Option<Self> {
+        (stats.kudos_received > 0).then(|| Self {
+            kudos_received_inv: u64::MAX - stats.kudos_received,
+            upvotes_received_inv: u64::MAX - stats.upvotes_received,
+            account_id: account_id.clone(),
+        })
+    }
+}
+
+/// This type represents a unique identifier of a season, which is an index of a season in order of creation
+pub type SeasonId = u32;
+
+/// Recognition round defined by an owner/admin, within which kudos, upvotes and exchanges are tallied
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct Season {
+    /// The timestamp in milliseconds when a season starts
+    pub start_at: u64,
+    /// The timestamp in milliseconds when a season ends, exclusive
+    pub end_at: u64,
+    /// Whether a ranked snapshot of a season was frozen
+    pub closed: bool,
+}
+
+impl Season {
+    /// Return [`bool`] which represents if a season is active at provided timestamp
+    pub fn is_active(&self, now: u64) -> bool {
+        self.start_at <= now && now < self.end_at
+    }
+}
+
+/// View of [`Season`]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeasonView {
+    pub season_id: SeasonId,
+    #[serde(flatten)]
+    pub season: Season,
+}
+
+/// Number of kudos, upvotes and exchanges of a receiver tallied within a season.
+/// Kudos of custom kinds are counted as kudos, dings are not counted.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeasonTally {
+    pub kudos_received: u64,
+    pub upvotes_received: u64,
+    /// Number of kudos exchanged for ProofOfKudos SBTs
+    pub kudos_exchanged: u64,
+}
+
+/// Position of a receiver [`AccountId`] in a season ranking. Counters are inverted, so positions are
+/// ordered by a number of received kudos, upvotes and exchanged kudos descending, then by [`AccountId`].
+#[derive(BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct SeasonPosition {
+    kudos_received_inv: u64,
+    upvotes_received_inv: u64,
+    kudos_exchanged_inv: u64,
+    pub account_id: AccountId,
+}
+
+impl SeasonPosition {
+    /// Return [`SeasonPosition`] of a receiver by its [`SeasonTally`], `None` for receivers without
+    /// kudos, who are not ranked
+    pub fn new(account_id: &AccountId, tally: &SeasonTally) -> Option<Self> {
+        (tally.kudos_received > 0).then(|| Self {
+            kudos_received_inv: u64::MAX - tally.kudos_received,
+            upvotes_received_inv: u64::MAX - tally.upvotes_received,
+            kudos_exchanged_inv: u64::MAX - tally.kudos_exchanged,
+            account_id: account_id.clone(),
+        })
     }
 }
 
+/// Position of a receiver in a season ranking with its [`SeasonTally`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeasonStanding {
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub tally: SeasonTally,
+}
+
+/// Special SBT minted by i-am-human-registry to the top receivers of a closed season
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeasonSbt {
+    /// Class id of SBT registered for this contract by i-am-human-registry
+    pub class: u64,
+    /// Number of the top receivers to mint SBT to
+    pub recipients: u16,
+}
+
+/// Position of a commentary message in a thread of kudos commentary messages
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommentThread {
+    pub kudos_id: KudosId,
+    /// Parent commentary id which was replied, `None` for a top-level commentary message
+    pub parent_comment_id: Option<CommentId>,
+    /// Number of parent commentary messages up to a top-level one, which depth is zero
+    pub depth: u8,
+}
+
+/// View of [`CommentThread`] of a commentary message
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug, PartialEq))]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommentThreadView {
+    pub comment_id: CommentId,
+    #[serde(flatten)]
+    pub thread: CommentThread,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{CommentId, Commentary, EncodedCommentary, Hashtag, WrappedCid};
+    use crate::{
+        CidCodec, CidMultihash, CommentId, Commentary, CommentaryEncoding, EncodedCommentary,
+        Hashtag, HashtagSeparator, HashtagStats, KudosAttachment, KudosError, KudosKind,
+        KudosMessage, SlidingWindowCounter, WrappedCid, HASHTAG_TRENDING_MAX_WINDOW,
+        HASHTAG_USAGE_PERIOD,
+    };
+    use cid::multihash::Multihash;
+    use cid::Cid;
     use near_sdk::json_types::U64;
+    use near_sdk::serde_json::{self, json, Value};
     use near_sdk::AccountId;
-    use near_sdk::serde_json::Value;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_sliding_window_counter() {
+        let window = 100;
+        let mut counter = SlidingWindowCounter::default();
+
+        counter.inc(window, 1_010);
+        counter.inc(window, 1_050);
+        counter.inc(window, 1_090);
+        assert_eq!(counter.count_at(window, 1_099), 3);
+
+        // Half of the previous window is weighted
+        assert_eq!(counter.count_at(window, 1_150), 1);
+        counter.inc(window, 1_150);
+        assert_eq!(counter.count_at(window, 1_150), 2);
+        assert_eq!(
+            counter,
+            SlidingWindowCounter {
+                window_start: 1_100,
+                current: 1,
+                previous: 3,
+            }
+        );
+
+        // Counter is reset if more than one window elapsed
+        assert_eq!(counter.count_at(window, 1_300), 0);
+        counter.inc(window, 1_300);
+        assert_eq!(counter.count_at(window, 1_300), 1);
+    }
 
     #[test]
     fn test_commentary_encoding() {
@@ -321,6 +1496,74 @@ mod tests {
             comment.as_str(),
             "eyJtIjoiY29tbWVudGFyeSB0ZXN0IiwicyI6InVzZXIubmVhciIsInQiOiIxMjM0NTY3ODkwIiwicCI6IjEifQ=="
         );
+        assert_eq!(
+            comment.sender_id(),
+            Some(AccountId::new_unchecked("user.near".to_owned()))
+        );
+        assert_eq!(
+            comment.parent_comment_id(),
+            Some(CommentId::new_unchecked(1u64))
+        );
+        assert_eq!(
+            EncodedCommentary::new_unchecked("invalid".to_owned()).sender_id(),
+            None
+        );
+        assert_eq!(
+            EncodedCommentary::new_unchecked("invalid".to_owned()).parent_comment_id(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_commentary_encoding_v2() {
+        let sender_id = AccountId::new_unchecked("user.near".to_owned());
+        let message = Value::String("commentary \"test\"".to_string());
+        let parent_comment_id = CommentId::new_unchecked(1u64);
+        let commentary = Commentary {
+            sender_id: &sender_id,
+            message: &message,
+            timestamp: U64(1234567890),
+            parent_comment_id: Some(&parent_comment_id),
+        };
+
+        let comment = EncodedCommentary::encode(&commentary, CommentaryEncoding::V2).unwrap();
+        assert_eq!(
+            comment.as_str(),
+            r#"{"v":2,"m":"commentary \"test\"","s":"user.near","t":"1234567890","p":"1"}"#
+        );
+        assert_eq!(comment.encoding(), CommentaryEncoding::V2);
+        assert_eq!(comment.sender_id(), Some(sender_id.clone()));
+        assert_eq!(comment.parent_comment_id(), Some(parent_comment_id.clone()));
+
+        // Commentary messages of the previous version are still decoded
+        let comment = EncodedCommentary::encode(&commentary, CommentaryEncoding::V1).unwrap();
+        assert_eq!(comment.encoding(), CommentaryEncoding::V1);
+        assert_eq!(comment.sender_id(), Some(sender_id));
+
+        // Unknown version is not decoded
+        assert_eq!(
+            EncodedCommentary::new_unchecked(r#"{"v":3,"s":"user.near"}"#.to_owned()).sender_id(),
+            None
+        );
+        assert_eq!(
+            CommentaryEncoding::V2.leave_comment_cost(),
+            crate::LEAVE_COMMENT_V2_COST
+        );
+    }
+
+    #[test]
+    fn test_kudos_kind_serialization() {
+        assert_eq!(
+            serde_json::from_value::<Vec<KudosKind>>(json!(["k", "d", "m"])).unwrap(),
+            vec![KudosKind::Kudos, KudosKind::Ding, KudosKind::Custom(b'm')]
+        );
+        assert_eq!(
+            serde_json::to_value(KudosKind::Custom(b'm')).unwrap(),
+            json!("m")
+        );
+        assert_eq!(KudosKind::new("M"), Err(KudosError::InvalidKudosKind));
+        assert_eq!(KudosKind::new("mk"), Err(KudosError::InvalidKudosKind));
+        assert!(serde_json::from_value::<KudosKind>(json!("")).is_err());
     }
 
     #[test]
@@ -333,6 +1576,97 @@ mod tests {
         assert!(Hashtag::new("toolonghashtag", 8).is_err());
     }
 
+    #[test]
+    fn test_hashtag_canonicalize() {
+        let hashtag = || Hashtag::new_unchecked("Love_NDC-2023");
+        assert_eq!(
+            hashtag().canonicalize(HashtagSeparator::Keep),
+            Hashtag::new_unchecked("love_ndc-2023")
+        );
+        assert_eq!(
+            hashtag().canonicalize(HashtagSeparator::Underscore),
+            Hashtag::new_unchecked("love_ndc_2023")
+        );
+        assert_eq!(
+            hashtag().canonicalize(HashtagSeparator::Hyphen),
+            Hashtag::new_unchecked("love-ndc-2023")
+        );
+    }
+
+    #[test]
+    fn test_hashtag_stats() {
+        let day = HASHTAG_USAGE_PERIOD;
+        let mut stats = HashtagStats::default();
+
+        stats.inc(10 * day + 1);
+        stats.inc(10 * day + 2);
+        stats.inc(12 * day);
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.periods, vec![(10 * day, 2), (12 * day, 1)]);
+        assert_eq!(stats.count_within(day, 12 * day + 5), 1);
+        assert_eq!(stats.count_within(2 * day, 12 * day + 5), 3);
+
+        // Periods outside of the maximum trending window are forgotten
+        stats.inc(10 * day + HASHTAG_TRENDING_MAX_WINDOW);
+        assert_eq!(stats.count, 4);
+        assert_eq!(
+            stats.periods,
+            vec![(12 * day, 1), (10 * day + HASHTAG_TRENDING_MAX_WINDOW, 1)]
+        );
+    }
+
+    #[test]
+    fn test_kudos_message() {
+        let message = |text: &str| KudosMessage::new(text.to_owned(), 1000, 2);
+
+        assert_eq!(
+            message(
+                "**Thanks** @alex.near and @bob.near, see [PR](https://github.com) @alex.near."
+            )
+            .unwrap()
+            .mentions,
+            vec![
+                AccountId::new_unchecked("alex.near".to_owned()),
+                AccountId::new_unchecked("bob.near".to_owned())
+            ]
+        );
+        // Emails, standalone `@` and mentions inside inline code are ignored
+        assert_eq!(
+            message("mail user@mail.com @ `@Not.Valid`")
+                .unwrap()
+                .mentions,
+            vec![]
+        );
+        assert_eq!(
+            message("hi @Alex.near"),
+            Err(KudosError::InvalidMention {
+                mention: "Alex.near".to_owned()
+            })
+        );
+        assert_eq!(
+            message("@a.near @b.near @c.near"),
+            Err(KudosError::MentionsLimitExceeded)
+        );
+        assert!(message("1 < 2 and `<b>`").is_ok());
+        assert_eq!(message("<script>"), Err(KudosError::InvalidMessageMarkup));
+        assert_eq!(
+            message("![img](https://a.b/c.png)"),
+            Err(KudosError::InvalidMessageMarkup)
+        );
+        assert_eq!(
+            message("[link](javascript:alert(1))"),
+            Err(KudosError::InvalidMessageMarkup)
+        );
+        assert_eq!(
+            message("unclosed `code"),
+            Err(KudosError::InvalidMessageMarkup)
+        );
+        assert_eq!(
+            KudosMessage::new("too long".to_owned(), 4, 2),
+            Err(KudosError::MessageMaxLengthExceeded)
+        );
+    }
+
     #[test]
     fn test_wrapped_cid() {
         assert!(WrappedCid::new("invalid_cid").is_err());
@@ -352,5 +1686,85 @@ mod tests {
             ),
             "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"
         );
+
+        // Verify the same rules are applied for deserialization
+        assert_eq!(
+            serde_json::from_value::<WrappedCid>(json!(
+                "bafkreieq5jui4j25lacwomsqgjeswwl3y5zcdrresptwgmfylxo2depppq"
+            ))
+            .unwrap()
+            .to_string(),
+            "bafkreieq5jui4j25lacwomsqgjeswwl3y5zcdrresptwgmfylxo2depppq"
+        );
+        assert!(serde_json::from_value::<WrappedCid>(json!("invalid_cid")).is_err());
+        let long_cid = Cid::new_v1(0x55, Multihash::wrap(0x13, &[1; 64]).unwrap()).to_string();
+        assert!(Cid::from_str(&long_cid).is_ok());
+        assert_eq!(
+            WrappedCid::new(&long_cid).err(),
+            Some(KudosError::CidTooLong)
+        );
+        assert!(serde_json::from_value::<WrappedCid>(json!(long_cid))
+            .err()
+            .is_some_and(|e| e.to_string().contains("Cid is too long")));
+    }
+
+    #[test]
+    fn test_wrapped_cid_is_allowed() {
+        let v0_cid = WrappedCid::new("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        let raw_cid =
+            WrappedCid::new("bafkreieq5jui4j25lacwomsqgjeswwl3y5zcdrresptwgmfylxo2depppq").unwrap();
+        let dag_cbor_cid = WrappedCid::new(
+            &Cid::new_v1(0x71, Multihash::wrap(0x12, &[1; 32]).unwrap()).to_string(),
+        )
+        .unwrap();
+        let blake3_cid = WrappedCid::new(
+            &Cid::new_v1(0x55, Multihash::wrap(0x1e, &[1; 32]).unwrap()).to_string(),
+        )
+        .unwrap();
+
+        let codecs = [CidCodec::Raw, CidCodec::DagPb];
+        let multihashes = [CidMultihash::Sha2_256];
+        assert!(v0_cid.is_allowed(&codecs, &multihashes));
+        assert!(raw_cid.is_allowed(&codecs, &multihashes));
+        assert!(!dag_cbor_cid.is_allowed(&codecs, &multihashes));
+        assert!(!blake3_cid.is_allowed(&codecs, &multihashes));
+        assert!(blake3_cid.is_allowed(&codecs, &[CidMultihash::Sha2_256, CidMultihash::Blake3]));
+        assert!(!v0_cid.is_allowed(&[CidCodec::Raw], &multihashes));
+    }
+
+    #[test]
+    fn test_kudos_attachment() {
+        let attachment = |mime_type: &str, caption: Option<&str>| KudosAttachment {
+            cid: WrappedCid::new("bafkreieq5jui4j25lacwomsqgjeswwl3y5zcdrresptwgmfylxo2depppq")
+                .unwrap(),
+            mime_type: mime_type.to_owned(),
+            caption: caption.map(str::to_owned),
+        };
+
+        assert_eq!(attachment("image/png", None).validate(), Ok(()));
+        assert_eq!(
+            attachment("application/vnd.api+json", Some("caption")).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            attachment("image", None).validate(),
+            Err(KudosError::InvalidAttachmentMimeType)
+        );
+        assert_eq!(
+            attachment("image/", None).validate(),
+            Err(KudosError::InvalidAttachmentMimeType)
+        );
+        assert_eq!(
+            attachment("image/p n g", None).validate(),
+            Err(KudosError::InvalidAttachmentMimeType)
+        );
+        assert_eq!(
+            attachment(&format!("image/{}", "a".repeat(64)), None).validate(),
+            Err(KudosError::InvalidAttachmentMimeType)
+        );
+        assert_eq!(
+            attachment("image/png", Some(&"a".repeat(101))).validate(),
+            Err(KudosError::AttachmentCaptionMaxLengthExceeded)
+        );
     }
 }
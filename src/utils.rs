@@ -1,10 +1,16 @@
-use crate::consts::PROOF_OF_KUDOS_SBT_CLASS_ID;
+use crate::consts::{
+    GIVE_KUDOS_COST, HASHTAG_USAGE_COST, KUDOS_ATTACHMENT_COST, PROOF_OF_KUDOS_SBT_CLASS_ID,
+};
 use crate::registry::TokenMetadata;
-use crate::types::KudosId;
-use crate::{CommentId, EncodedCommentary, Hashtag, KudosKind, WrappedCid};
+use crate::storage::{KudosRecord, NewKudos, NotificationKind, SocialDbWrite};
+use crate::types::{KudosId, SocialDbLayout};
+use crate::{
+    CommentId, EncodedCommentary, KudosError, KudosStatus, MigrationSection, ReportedContent,
+};
 use near_sdk::env::STORAGE_PRICE_PER_BYTE;
 use near_sdk::serde_json::{self, Value};
 use near_sdk::{AccountId, Balance, Gas};
+use std::collections::BTreeMap;
 
 /// Return initial object as JSON [`String`] which will be stored in NEAR social db
 ///
@@ -20,7 +26,7 @@ use near_sdk::{AccountId, Balance, Gas};
 ///
 /// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
 /// of deposit requirement for all public methods
-pub fn build_initial_json_for_socialdb(root_id: &AccountId) -> Result<Value, &'static str> {
+pub fn build_initial_json_for_socialdb(root_id: &AccountId) -> Result<Value, KudosError> {
     serde_json::from_str::<Value>(&format!(
         r#"{{
           "{root_id}": {{
@@ -29,70 +35,12 @@ pub fn build_initial_json_for_socialdb(root_id: &AccountId) -> Result<Value, &'s
           }}
         }}"#
     ))
-    .map_err(|_| "Internal serialization error")
+    .map_err(|_| KudosError::SerializationFailure)
 }
 
-/// Return hashtags relationship to kudos and it's owner as JSON [`String`] which will be stored in NEAR social db
+/// Return kudos object as JSON [`Value`] which will be stored in NEAR social db
 ///
-/// Example of JSON output:
-/// ```json
-/// {
-///   "lovendc": {
-///     "1": "ndc.near",
-///     ...
-///   },
-///   ...
-/// }
-/// ```
-///
-/// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
-/// of deposit requirement for public method [`give_kudos`](kudos_contract::public::Contract::give_kudos)
-pub fn build_hashtags(
-    receiver_id: &AccountId,
-    kudos_id: &KudosId,
-    hashtags: Option<&[Hashtag]>,
-) -> Result<String, &'static str> {
-    hashtags
-        .map(|hashtags| {
-            hashtags
-                .iter()
-                .map(|ht| {
-                    serde_json::from_str::<Value>(&format!(
-                        r#"{{
-                          "{kudos_id}": "{receiver_id}"
-                        }}"#
-                    ))
-                    .map(|v| (ht, v))
-                })
-                .collect::<Result<std::collections::BTreeMap<_, _>, _>>()
-                .and_then(|map| serde_json::to_string(&map))
-                .map_err(|_| "Internal serialization error")
-        })
-        .unwrap_or_else(|| Ok("{}".to_owned()))
-}
-
-/// Return hashtags array as JSON [`String`] which will be stored in NEAR social db
-///
-/// Example of JSON output:
-/// ```json
-/// [
-///   "nearcommunity",
-///   "ndckudos",
-///   ...
-/// ]
-/// ```
-///
-/// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
-/// of deposit requirement for public method [`give_kudos`](kudos_contract::public::Contract::give_kudos)
-pub fn hashtags_to_json_array(hashtags: &[Hashtag]) -> Result<String, &'static str> {
-    serde_json::to_string(&hashtags)
-        .map(|s| s.escape_default().to_string())
-        .map_err(|_| "Internal hashtags serialization error")
-}
-
-/// Return kudos object as JSON [`String`] which will be stored in NEAR social db
-///
-/// Example of JSON output:
+/// Example of JSON output for [`SocialDbLayout::Compat`] layout:
 /// ```json
 /// {
 ///   "kudos.near": {
@@ -104,6 +52,7 @@ pub fn hashtags_to_json_array(hashtags: &[Hashtag]) -> Result<String, &'static s
 ///           "kind": "k",
 ///           "message": "that user is awesome",
 ///           "icon": "bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa",
+///           "status": "pending", // only for dings which should be accepted by receiver
 ///           "upvotes": {},
 ///           "comments": {},
 ///           "tags": "[\"firstkudos\",\"awesomework\"]",
@@ -117,57 +66,58 @@ pub fn hashtags_to_json_array(hashtags: &[Hashtag]) -> Result<String, &'static s
 ///       "awesomework": {
 ///         "1": "alex.near"
 ///       }
+///     },
+///     "mentions": { // only if users are mentioned in the message
+///       "bob.near": {
+///         "1": "some_user.near"
+///       }
+///     },
+///     "index": {
+///       "notify": "[{\"key\":\"some_user.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/some_user.near/1\",\"kudosKind\":\"k\"}}},{\"key\":\"bob.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/some_user.near/1\"}}}]"
 ///     }
 ///   }
 /// }
 /// ```
 ///
+/// Receiver and mentioned users, except a sender, are notified by standard near.social notifications.
+/// Receiver's notification additionally refers to a kind of received kudos, e.g. `"kudosKind":"k"`.
+///
+/// [`SocialDbLayout::V2`] layout stores hashtags as an object, e.g. `"tags": { "firstkudos": "", "awesomework": "" }`,
+/// and omits an icon if it's not provided.
+///
 /// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
 /// of deposit requirement for public method [`give_kudos`](kudos_contract::public::Contract::give_kudos)
 pub fn build_give_kudos_request(
     root_id: &AccountId,
-    sender_id: &AccountId,
-    receiver_id: &AccountId,
-    kudos_id: &KudosId,
-    created_at: u64,
-    kind: KudosKind,
-    message: &str,
-    icon_cid: Option<&WrappedCid>,
-    hashtags: Option<&[Hashtag]>,
-) -> Result<Value, &'static str> {
-    let hashtags_as_array_json = hashtags_to_json_array(hashtags.unwrap_or(&[]))?;
-    let hashtags_with_kudos = build_hashtags(receiver_id, kudos_id, hashtags)?;
-    let icon_cid = icon_cid.map(|cid| cid.to_string()).unwrap_or_default();
-
-    let mes = near_sdk::serde_json::Value::String(message.to_string());
-    serde_json::from_str::<Value>(&format!(
-        r#"{{
-          "{root_id}": {{
-            "kudos": {{
-              "{receiver_id}": {{
-                "{kudos_id}": {{
-                  "created_at": "{created_at}",
-                  "sender_id": "{sender_id}",
-                  "kind": "{kind}",
-                  "message": {mes},
-                  "icon": "{icon_cid}",
-                  "upvotes": {{}},
-                  "comments": {{}},
-                  "tags": "{hashtags_as_array_json}"
-                }}
-              }}
-            }},
-            "hashtags": {hashtags_with_kudos}
-          }}
-        }}"#
-    ))
-    .map_err(|e| {
-        println!("{e:?}");
-        "Internal serialization error"
-    })
+    kudos: &NewKudos,
+    layout: SocialDbLayout,
+) -> Result<Value, KudosError> {
+    let request = SocialDbWrite::kudos(
+        root_id,
+        &kudos.receiver_id,
+        &kudos.kudos_id,
+        KudosRecord::new(kudos, layout),
+    )
+    .with_hashtags(
+        &kudos.receiver_id,
+        &kudos.kudos_id,
+        kudos.hashtags.as_deref().unwrap_or_default(),
+    )
+    .with_mentions(&kudos.receiver_id, &kudos.kudos_id, &kudos.mentions)
+    .notify_receiver(&kudos.receiver_id, &kudos.kind);
+
+    kudos
+        .mentions
+        .iter()
+        .filter(|account_id| *account_id != &kudos.sender_id)
+        .fold(request, |request, account_id| {
+            request.notify(account_id, NotificationKind::Mention)
+        })
+        .into_json()
 }
 
-/// Return upvotes for kudos object as JSON [`String`] which will be stored in NEAR social db
+/// Return upvotes for kudos object as JSON [`Value`] which will be stored in NEAR social db.
+/// Kudos status is updated additionally if provided.
 ///
 /// Example of JSON output:
 /// ```json
@@ -181,6 +131,9 @@ pub fn build_give_kudos_request(
 ///           }
 ///         }
 ///       }
+///     },
+///     "index": {
+///       "notify": "[{\"key\":\"some_user.near\",\"value\":{\"type\":\"like\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/some_user.near/1\"}}}]"
 ///     }
 ///   }
 /// }
@@ -193,26 +146,19 @@ pub fn build_upvote_kudos_request(
     sender_id: &AccountId,
     receiver_id: &AccountId,
     kudos_id: &KudosId,
-) -> Result<Value, &'static str> {
-    serde_json::from_str::<Value>(&format!(
-        r#"{{
-          "{root_id}": {{
-            "kudos": {{
-              "{receiver_id}": {{
-                "{kudos_id}": {{
-                  "upvotes": {{
-                    "{sender_id}": ""
-                  }}
-                }}
-              }}
-            }}
-          }}
-        }}"#
-    ))
-    .map_err(|_| "Internal serialization error")
+    status: Option<&KudosStatus>,
+) -> Result<Value, KudosError> {
+    SocialDbWrite::kudos(
+        root_id,
+        receiver_id,
+        kudos_id,
+        KudosRecord::upvote(sender_id, status),
+    )
+    .notify(receiver_id, NotificationKind::Like)
+    .into_json()
 }
 
-/// Return base64-encoded commentary for kudos object as JSON [`String`] which will be stored in NEAR social db
+/// Return base64-encoded commentary for kudos object as JSON [`Value`] which will be stored in NEAR social db
 ///
 /// Example of JSON output:
 /// ```json
@@ -226,37 +172,165 @@ pub fn build_upvote_kudos_request(
 ///           }
 ///         }
 ///       }
+///     },
+///     "index": {
+///       "notify": "[{\"key\":\"some_user.near\",\"value\":{\"type\":\"comment\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/some_user.near/1\"}}}]"
 ///     }
 ///   }
 /// }
 /// ```
 ///
+/// Receiver and an author of a parent commentary message, except a sender, are notified by standard
+/// near.social notifications.
+///
 /// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
 /// of deposit requirement for public method [`leave_comment`](kudos_contract::public::Contract::leave_comment)
 pub fn build_leave_comment_request(
     root_id: &AccountId,
+    sender_id: &AccountId,
     receiver_id: &AccountId,
     kudos_id: &KudosId,
     comment_id: &CommentId,
     comment: &EncodedCommentary,
-) -> Result<Value, &'static str> {
-    let comment = comment.as_str();
-    let json = format!(
-        r#"{{
-          "{root_id}": {{
-            "kudos": {{
-              "{receiver_id}": {{
-                "{kudos_id}": {{
-                  "comments": {{
-                    "{comment_id}": "{comment}"
-                  }}
-                }}
-              }}
-            }}
-          }}
-        }}"#
+    parent_comment_sender_id: Option<&AccountId>,
+) -> Result<Value, KudosError> {
+    let request = SocialDbWrite::kudos(
+        root_id,
+        receiver_id,
+        kudos_id,
+        KudosRecord::comment(comment_id, comment),
     );
-    serde_json::from_str::<Value>(&json).map_err(|_| "Internal serialization error")
+
+    [Some(receiver_id), parent_comment_sender_id]
+        .into_iter()
+        .flatten()
+        .filter(|account_id| *account_id != sender_id)
+        .fold(request, |request, account_id| {
+            request.notify(account_id, NotificationKind::Comment)
+        })
+        .into_json()
+}
+
+/// Return a reaction of a user to a commentary message as JSON [`Value`] which will be stored in NEAR social db.
+/// Every user has a single reaction to a commentary message, so a previous reaction is replaced.
+///
+/// Example of JSON output:
+/// ```json
+/// {
+///   "kudos.near": {
+///     "kudos": {
+///       "some_user.near": {
+///         "1": {
+///           "comments_reactions": {
+///             "2": {
+///               "alex.near": "👍"
+///             }
+///           }
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
+/// of deposit requirement for public method [`react_to_comment`](kudos_contract::public::Contract::react_to_comment)
+pub fn build_react_to_comment_request(
+    root_id: &AccountId,
+    receiver_id: &AccountId,
+    kudos_id: &KudosId,
+    comment_id: &CommentId,
+    sender_id: &AccountId,
+    reaction: &str,
+) -> Result<Value, KudosError> {
+    SocialDbWrite::kudos(
+        root_id,
+        receiver_id,
+        kudos_id,
+        KudosRecord::reaction(comment_id, sender_id, reaction),
+    )
+    .into_json()
+}
+
+/// Return kudos status object as JSON [`Value`] which will be stored in NEAR social db
+///
+/// Example of JSON output:
+/// ```json
+/// {
+///   "kudos.near": {
+///     "kudos": {
+///       "some_user.near": {
+///         "1": {
+///           "status": "hidden"
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
+/// of deposit requirement for public methods which update kudos status, e.g.
+/// [`hide_kudos`](kudos_contract::public::Contract::hide_kudos)
+pub fn build_update_kudos_status_request(
+    root_id: &AccountId,
+    receiver_id: &AccountId,
+    kudos_id: &KudosId,
+    status: &KudosStatus,
+) -> Result<Value, KudosError> {
+    SocialDbWrite::kudos(root_id, receiver_id, kudos_id, KudosRecord::status(status)).into_json()
+}
+
+/// Return moderation flag of reported kudos or commentary message as JSON [`Value`] which will be stored
+/// in NEAR social db. The flag is removed if reported content should not be hidden.
+///
+/// Example of JSON output for hidden kudos:
+/// ```json
+/// {
+///   "kudos.near": {
+///     "kudos": {
+///       "some_user.near": {
+///         "1": {
+///           "hidden": "true"
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// Example of JSON output for hidden commentary message:
+/// ```json
+/// {
+///   "kudos.near": {
+///     "kudos": {
+///       "some_user.near": {
+///         "1": {
+///           "hidden_comments": {
+///             "2": "true"
+///           }
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// ATTENTION: Changing this JSON output will require contract refactoring and re-computation
+/// of deposit requirement for public methods which report content or resolve reports, e.g.
+/// [`report_kudos`](kudos_contract::public::Contract::report_kudos)
+pub fn build_hide_content_request(
+    root_id: &AccountId,
+    content: &ReportedContent,
+    hidden: bool,
+) -> Result<Value, KudosError> {
+    SocialDbWrite::kudos(
+        root_id,
+        &content.receiver_id,
+        &content.kudos_id,
+        KudosRecord::hidden(content.comment_id.as_ref(), hidden),
+    )
+    .into_json()
 }
 
 /// Return [`String`] path to a stored kudos JSON with unique [`KudosId`] for a valid [`AccountId`]
@@ -284,6 +358,18 @@ pub fn build_get_kudos_comment_by_id_request(
     format!("{root_id}/kudos/{receiver_id}/{kudos_id}/comments/{comment_id}")
 }
 
+/// Return [`String`] path to all stored kudos base64-encoded comments with unique [`KudosId`]
+/// for a valid [`AccountId`] used to query from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/alex.near/1/comments/*"
+pub fn build_get_kudos_comments_request(
+    root_id: &AccountId,
+    receiver_id: &AccountId,
+    kudos_id: &KudosId,
+) -> String {
+    format!("{root_id}/kudos/{receiver_id}/{kudos_id}/comments/*")
+}
+
 /// Return [`String`] path to a stored upvotes information JSON with unique [`KudosId`] for a valid [`AccountId`]
 /// used to query from NEAR social db.
 ///
@@ -308,6 +394,80 @@ pub fn build_kudos_kind_path(
     format!("{root_id}/kudos/{receiver_id}/{kudos_id}/kind")
 }
 
+/// Return [`String`] path to a stored kudos sender with unique [`KudosId`] for a valid [`AccountId`]
+/// used to query from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/bob.near/1/sender_id"
+pub fn build_kudos_sender_path(
+    root_id: &AccountId,
+    receiver_id: &AccountId,
+    kudos_id: &KudosId,
+) -> String {
+    format!("{root_id}/kudos/{receiver_id}/{kudos_id}/sender_id")
+}
+
+/// Return [`String`] path to a stored kudos creation timestamp with unique [`KudosId`] for a valid [`AccountId`]
+/// used to query from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/bob.near/1/created_at"
+pub fn build_kudos_created_at_path(
+    root_id: &AccountId,
+    receiver_id: &AccountId,
+    kudos_id: &KudosId,
+) -> String {
+    format!("{root_id}/kudos/{receiver_id}/{kudos_id}/created_at")
+}
+
+/// Return [`String`] path to a stored reported content, which is kudos sender for reported kudos
+/// or base64-encoded commentary for reported commentary message, used to query from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/bob.near/1/sender_id" or "kudos.near/kudos/bob.near/1/comments/2"
+pub fn build_reported_content_path(root_id: &AccountId, content: &ReportedContent) -> String {
+    match content.comment_id.as_ref() {
+        Some(comment_id) => build_get_kudos_comment_by_id_request(
+            root_id,
+            &content.receiver_id,
+            &content.kudos_id,
+            comment_id,
+        ),
+        None => build_kudos_sender_path(root_id, &content.receiver_id, &content.kudos_id),
+    }
+}
+
+/// Return [`String`] path to top-level keys of a migrated section, used to query from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/*" or "kudos.near/hashtags/*"
+pub fn build_migration_section_keys_request(
+    root_id: &AccountId,
+    section: MigrationSection,
+) -> String {
+    format!("{root_id}/{section}/*")
+}
+
+/// Return [`String`] path to kudos ids stored under a top-level key of a migrated section, used to query
+/// keys from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/bob.near/*" or "kudos.near/hashtags/lovendc/*"
+pub fn build_migration_key_entries_request(
+    root_id: &AccountId,
+    section: MigrationSection,
+    key: &str,
+) -> String {
+    format!("{root_id}/{section}/{key}/*")
+}
+
+/// Return [`String`] path to all data stored under an entry `{key}/{kudos_id}` of a migrated section,
+/// used to query from NEAR social db.
+///
+/// Example of query: "kudos.near/kudos/bob.near/1/**" or "kudos.near/hashtags/lovendc/1/**"
+pub fn build_migration_entry_data_request(
+    root_id: &AccountId,
+    section: MigrationSection,
+    entry: &str,
+) -> String {
+    format!("{root_id}/{section}/{entry}/**")
+}
+
 /// Return [`TokenMetadata`] used as an argument for call [`sbt_mint`](kudos_contract::registry::ExtSbtRegistry::sbt_mint)
 /// to mint ProofOfKudos SBT
 pub fn build_pok_sbt_metadata(issued_at: u64, expires_at: u64) -> TokenMetadata {
@@ -336,6 +496,17 @@ pub fn extract_kudos_encoded_comment_by_id_from_response(
         .and_then(|val| serde_json::from_value::<EncodedCommentary>(val).ok())
 }
 
+/// Extract all base64-encoded comments [`EncodedCommentary`] by their [`CommentId`] from stored kudos JSON
+/// acquired from NEAR social db, kudos without comments have none
+pub fn extract_kudos_encoded_comments_from_response(
+    req: &str,
+    res: &mut Value,
+) -> BTreeMap<CommentId, EncodedCommentary> {
+    remove_key_from_json(res, &req.replace('*', "comments"))
+        .and_then(|val| serde_json::from_value(val).ok())
+        .unwrap_or_default()
+}
+
 /// Remove and return (if removed) [`serde_json::Value`] by key name [`str`] from JSON [`serde_json::Value`]
 ///
 /// # Example:
@@ -410,6 +581,13 @@ pub(crate) fn display_deposit_requirement_in_near(value: Balance) -> String {
     )
 }
 
+/// Return deposit required to give kudos with provided number of attachments and hashtags
+pub fn give_kudos_cost(number_of_attachments: usize, number_of_hashtags: usize) -> Balance {
+    GIVE_KUDOS_COST
+        + KUDOS_ATTACHMENT_COST * number_of_attachments as Balance
+        + HASHTAG_USAGE_COST * number_of_hashtags as Balance
+}
+
 /// Return [`String`] which represents human-readable Ⓝ amount
 pub fn display_deposit_in_near(value: Balance) -> String {
     format!(
@@ -421,8 +599,8 @@ pub fn display_deposit_in_near(value: Balance) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::EncodedCommentary;
     use crate::{types::IncrementalUniqueId, Commentary};
+    use crate::{EncodedCommentary, Hashtag, KudosAttachment, KudosKind, WrappedCid};
     use near_sdk::json_types::U64;
     use near_sdk::serde_json::json;
     use near_units::parse_near;
@@ -443,65 +621,208 @@ mod tests {
         )
     }
 
+    fn new_kudos(
+        kind: KudosKind,
+        icon_cid: Option<WrappedCid>,
+        hashtags: Option<Vec<Hashtag>>,
+        status: Option<KudosStatus>,
+    ) -> NewKudos {
+        NewKudos {
+            sender_id: AccountId::new_unchecked("test1.near".to_owned()),
+            receiver_id: AccountId::new_unchecked("test2.near".to_owned()),
+            kudos_id: KudosId::from(IncrementalUniqueId::default().next()),
+            created_at: U64(1234567890),
+            kind,
+            message: r#""a","b":{"t":"multi\nline"},"#.to_owned(),
+            icon_cid,
+            hashtags,
+            mentions: vec![],
+            status,
+            attachments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_build_kudos_request() {
+        const CID: &str = "bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa";
+        let root_id = AccountId::new_unchecked("kudos.near".to_owned());
+        let icon_cid = WrappedCid::new(CID).unwrap();
+        let hashtags = vec![
+            Hashtag::new("abc", 32).unwrap(),
+            Hashtag::new("def", 32).unwrap(),
+        ];
+
+        let json_text = serde_json::to_string(
+            &super::build_give_kudos_request(
+                &root_id,
+                &new_kudos(
+                    KudosKind::Kudos,
+                    Some(icon_cid),
+                    Some(hashtags.clone()),
+                    None,
+                ),
+                SocialDbLayout::Compat,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"hashtags":{"abc":{"1":"test2.near"},"def":{"1":"test2.near"}},"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\",\"kudosKind\":\"k\"}}}]"},"kudos":{"test2.near":{"1":{"comments":{},"created_at":"1234567890","icon":"bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa","kind":"k","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","tags":"[\"abc\",\"def\"]","upvotes":{}}}}}}"#
+        );
+
+        let json_text = serde_json::to_string(
+            &super::build_give_kudos_request(
+                &root_id,
+                &new_kudos(KudosKind::Ding, None, Some(hashtags.clone()), None),
+                SocialDbLayout::Compat,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"hashtags":{"abc":{"1":"test2.near"},"def":{"1":"test2.near"}},"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\",\"kudosKind\":\"d\"}}}]"},"kudos":{"test2.near":{"1":{"comments":{},"created_at":"1234567890","icon":"","kind":"d","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","tags":"[\"abc\",\"def\"]","upvotes":{}}}}}}"#
+        );
+
+        let json_text = serde_json::to_string(
+            &super::build_give_kudos_request(
+                &root_id,
+                &new_kudos(KudosKind::Ding, None, None, Some(KudosStatus::Pending)),
+                SocialDbLayout::Compat,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"hashtags":{},"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\",\"kudosKind\":\"d\"}}}]"},"kudos":{"test2.near":{"1":{"comments":{},"created_at":"1234567890","icon":"","kind":"d","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","status":"pending","tags":"[]","upvotes":{}}}}}}"#
+        );
+
+        let json_text = serde_json::to_string(
+            &super::build_give_kudos_request(
+                &root_id,
+                &NewKudos {
+                    mentions: vec![AccountId::new_unchecked("alex.near".to_owned())],
+                    ..new_kudos(KudosKind::Kudos, None, Some(hashtags), None)
+                },
+                SocialDbLayout::V2,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"hashtags":{"abc":{"1":"test2.near"},"def":{"1":"test2.near"}},"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\",\"kudosKind\":\"k\"}}},{\"key\":\"alex.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\"}}}]"},"kudos":{"test2.near":{"1":{"comments":{},"created_at":"1234567890","kind":"k","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","tags":{"abc":"","def":""},"upvotes":{}}}},"mentions":{"alex.near":{"1":"test2.near"}}}}"#
+        );
+
+        let json_text = serde_json::to_string(
+            &super::build_give_kudos_request(
+                &root_id,
+                &NewKudos {
+                    attachments: vec![
+                        KudosAttachment {
+                            cid: WrappedCid::new(CID).unwrap(),
+                            mime_type: "image/png".to_owned(),
+                            caption: Some("screenshot".to_owned()),
+                        },
+                        KudosAttachment {
+                            cid: WrappedCid::new(CID).unwrap(),
+                            mime_type: "application/pdf".to_owned(),
+                            caption: None,
+                        },
+                    ],
+                    ..new_kudos(KudosKind::Kudos, None, None, None)
+                },
+                SocialDbLayout::V2,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"hashtags":{},"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"mention\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\",\"kudosKind\":\"k\"}}}]"},"kudos":{"test2.near":{"1":{"attachments":{"0":{"caption":"screenshot","cid":"bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa","mime_type":"image/png"},"1":{"cid":"bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa","mime_type":"application/pdf"}},"comments":{},"created_at":"1234567890","kind":"k","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","tags":{},"upvotes":{}}}}}}"#
+        );
+    }
+
+    #[test]
+    fn test_give_kudos_cost() {
+        assert_eq!(give_kudos_cost(0, 0), GIVE_KUDOS_COST);
+        assert_eq!(give_kudos_cost(2, 0), parse_near!("0.15798 N"));
+        assert_eq!(give_kudos_cost(0, 10), parse_near!("0.2077 N"));
+    }
+
     #[test]
-    fn test_build_hashtags() {
+    fn test_build_update_kudos_status_request() {
+        let root_id = AccountId::new_unchecked("kudos.near".to_owned());
         let receiver_id = AccountId::new_unchecked("test2.near".to_owned());
         let next_kudos_id = KudosId::from(IncrementalUniqueId::default().next());
 
-        let json_text = super::build_hashtags(
-            &receiver_id,
-            &next_kudos_id,
-            Some(&[Hashtag::new("hashtaga", 32).unwrap(),
-                Hashtag::new("hashtagb", 32).unwrap(),
-                Hashtag::new("hashtagc", 32).unwrap()]),
+        let json_text = serde_json::to_string(
+            &super::build_update_kudos_status_request(
+                &root_id,
+                &receiver_id,
+                &next_kudos_id,
+                &KudosStatus::Hidden,
+            )
+            .unwrap(),
         )
         .unwrap();
 
         assert_eq!(
             json_text,
-            r#"{"hashtaga":{"1":"test2.near"},"hashtagb":{"1":"test2.near"},"hashtagc":{"1":"test2.near"}}"#
+            r#"{"kudos.near":{"kudos":{"test2.near":{"1":{"status":"hidden"}}}}}"#
         );
     }
 
     #[test]
-    fn test_hashtags_to_json_array() {
+    fn test_build_hide_content_request() {
+        let root_id = AccountId::new_unchecked("kudos.near".to_owned());
+        let mut content = ReportedContent {
+            receiver_id: AccountId::new_unchecked("test2.near".to_owned()),
+            kudos_id: KudosId::from(IncrementalUniqueId::default().next()),
+            comment_id: None,
+        };
+
+        let json_text = serde_json::to_string(
+            &super::build_hide_content_request(&root_id, &content, true).unwrap(),
+        )
+        .unwrap();
         assert_eq!(
-            hashtags_to_json_array(&[
-                Hashtag::new("a1", 32).unwrap(),
-                Hashtag::new("b1", 32).unwrap(),
-                Hashtag::new("c1", 32).unwrap(),
-            ])
-            .unwrap(),
-            r#"[\"a1\",\"b1\",\"c1\"]"#
+            json_text,
+            r#"{"kudos.near":{"kudos":{"test2.near":{"1":{"hidden":"true"}}}}}"#
+        );
+
+        content.comment_id = Some(CommentId::new_unchecked(2));
+        let json_text = serde_json::to_string(
+            &super::build_hide_content_request(&root_id, &content, false).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"kudos":{"test2.near":{"1":{"hidden_comments":{"2":null}}}}}}"#
         );
-        assert_eq!(hashtags_to_json_array(&[]).unwrap(), r#"[]"#);
     }
 
     #[test]
-    fn test_build_kudos_request() {
+    fn test_build_upvote_kudos_request() {
         let root_id = AccountId::new_unchecked("kudos.near".to_owned());
         let sender_id = AccountId::new_unchecked("test1.near".to_owned());
         let receiver_id = AccountId::new_unchecked("test2.near".to_owned());
         let next_kudos_id = KudosId::from(IncrementalUniqueId::default().next());
-        //let message = EscapedMessage::new(r#""a","b":{"t":"multi\nline"},"#, 1000).unwrap();
-        let message = r#""a","b":{"t":"multi\nline"},"#;
-        let icon_cid =
-            WrappedCid::new("bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa").unwrap();
 
         let json_text = serde_json::to_string(
-            &super::build_give_kudos_request(
+            &super::build_upvote_kudos_request(
                 &root_id,
                 &sender_id,
                 &receiver_id,
                 &next_kudos_id,
-                1234567890u64,
-                KudosKind::Kudos,
-                message,
-                Some(&icon_cid),
-                Some(&[
-                    Hashtag::new("abc", 32).unwrap(),
-                    Hashtag::new("def", 32).unwrap(),
-                ]),
+                None,
             )
             .unwrap(),
         )
@@ -509,23 +830,17 @@ mod tests {
 
         assert_eq!(
             json_text,
-            r#"{"kudos.near":{"hashtags":{"abc":{"1":"test2.near"},"def":{"1":"test2.near"}},"kudos":{"test2.near":{"1":{"comments":{},"created_at":"1234567890","icon":"bafybeigrf2dwtpjkiovnigysyto3d55opf6qkdikx6d65onrqnfzwgdkfa","kind":"k","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","tags":"[\"abc\",\"def\"]","upvotes":{}}}}}}"#
+            r#"{"kudos.near":{"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"like\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\"}}}]"},"kudos":{"test2.near":{"1":{"upvotes":{"test1.near":""}}}}}}"#
         );
 
+        // Upvote which reaches a quorum of a proposed ding publishes it
         let json_text = serde_json::to_string(
-            &super::build_give_kudos_request(
+            &super::build_upvote_kudos_request(
                 &root_id,
                 &sender_id,
                 &receiver_id,
                 &next_kudos_id,
-                1234567890u64,
-                KudosKind::Ding,
-                message,
-                None,
-                Some(&[
-                    Hashtag::new("abc", 32).unwrap(),
-                    Hashtag::new("def", 32).unwrap(),
-                ]),
+                Some(&KudosStatus::Accepted),
             )
             .unwrap(),
         )
@@ -533,31 +848,69 @@ mod tests {
 
         assert_eq!(
             json_text,
-            r#"{"kudos.near":{"hashtags":{"abc":{"1":"test2.near"},"def":{"1":"test2.near"}},"kudos":{"test2.near":{"1":{"comments":{},"created_at":"1234567890","icon":"","kind":"d","message":"\"a\",\"b\":{\"t\":\"multi\\nline\"},","sender_id":"test1.near","tags":"[\"abc\",\"def\"]","upvotes":{}}}}}}"#
+            r#"{"kudos.near":{"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"like\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\"}}}]"},"kudos":{"test2.near":{"1":{"status":"accepted","upvotes":{"test1.near":""}}}}}}"#
         );
     }
 
     #[test]
-    fn test_build_upvote_kudos_request() {
+    fn test_build_leave_comment_request() {
         let root_id = AccountId::new_unchecked("kudos.near".to_owned());
         let sender_id = AccountId::new_unchecked("test1.near".to_owned());
         let receiver_id = AccountId::new_unchecked("test2.near".to_owned());
-        let next_kudos_id = KudosId::from(IncrementalUniqueId::default().next());
+        let mut unique_id = IncrementalUniqueId::default();
+        let kudos_id = KudosId::from(unique_id.inc());
+        let comment_id = CommentId::from(unique_id.inc());
+
+        let comment = EncodedCommentary::try_from(&Commentary {
+            sender_id: &sender_id,
+            message: &Value::String("some commentary text".to_string()),
+            timestamp: U64(1234567890),
+            parent_comment_id: None,
+        })
+        .unwrap();
+
+        let json_text = serde_json::to_string(
+            &super::build_leave_comment_request(
+                &root_id,
+                &sender_id,
+                &receiver_id,
+                &kudos_id,
+                &comment_id,
+                &comment,
+                None,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json_text,
+            r#"{"kudos.near":{"index":{"notify":"[{\"key\":\"test2.near\",\"value\":{\"type\":\"comment\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\"}}}]"},"kudos":{"test2.near":{"1":{"comments":{"2":"eyJtIjoic29tZSBjb21tZW50YXJ5IHRleHQiLCJzIjoidGVzdDEubmVhciIsInQiOiIxMjM0NTY3ODkwIn0="}}}}}}"#
+        );
 
+        // Sender is not notified about own commentary
         let json_text = serde_json::to_string(
-            &super::build_upvote_kudos_request(&root_id, &sender_id, &receiver_id, &next_kudos_id)
-                .unwrap(),
+            &super::build_leave_comment_request(
+                &root_id,
+                &receiver_id,
+                &receiver_id,
+                &kudos_id,
+                &comment_id,
+                &comment,
+                Some(&sender_id),
+            )
+            .unwrap(),
         )
         .unwrap();
 
         assert_eq!(
             json_text,
-            r#"{"kudos.near":{"kudos":{"test2.near":{"1":{"upvotes":{"test1.near":""}}}}}}"#
+            r#"{"kudos.near":{"index":{"notify":"[{\"key\":\"test1.near\",\"value\":{\"type\":\"comment\",\"item\":{\"type\":\"social\",\"path\":\"kudos.near/kudos/test2.near/1\"}}}]"},"kudos":{"test2.near":{"1":{"comments":{"2":"eyJtIjoic29tZSBjb21tZW50YXJ5IHRleHQiLCJzIjoidGVzdDEubmVhciIsInQiOiIxMjM0NTY3ODkwIn0="}}}}}}"#
         );
     }
 
     #[test]
-    fn test_build_leave_comment_request() {
+    fn test_build_react_to_comment_request() {
         let root_id = AccountId::new_unchecked("kudos.near".to_owned());
         let sender_id = AccountId::new_unchecked("test1.near".to_owned());
         let receiver_id = AccountId::new_unchecked("test2.near".to_owned());
@@ -566,18 +919,13 @@ mod tests {
         let comment_id = CommentId::from(unique_id.inc());
 
         let json_text = serde_json::to_string(
-            &super::build_leave_comment_request(
+            &super::build_react_to_comment_request(
                 &root_id,
                 &receiver_id,
                 &kudos_id,
                 &comment_id,
-                &EncodedCommentary::try_from(&Commentary {
-                    sender_id: &sender_id,
-                    message: &Value::String("some commentary text".to_string()),
-                    timestamp: U64(1234567890),
-                    parent_comment_id: None,
-                })
-                .unwrap(),
+                &sender_id,
+                "👍",
             )
             .unwrap(),
         )
@@ -585,7 +933,7 @@ mod tests {
 
         assert_eq!(
             json_text,
-            r#"{"kudos.near":{"kudos":{"test2.near":{"1":{"comments":{"2":"eyJtIjoic29tZSBjb21tZW50YXJ5IHRleHQiLCJzIjoidGVzdDEubmVhciIsInQiOiIxMjM0NTY3ODkwIn0="}}}}}}"#
+            r#"{"kudos.near":{"kudos":{"test2.near":{"1":{"comments_reactions":{"2":{"test1.near":"👍"}}}}}}}"#
         );
     }
 
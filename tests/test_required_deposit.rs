@@ -4,8 +4,11 @@ mod workspaces;
 
 use crate::utils::*;
 use crate::workspaces::{build_contract, gen_user_account, get_block_timestamp, transfer_near};
-use kudos_contract::{utils::*, WrappedCid};
-use kudos_contract::{GIVE_KUDOS_COST, LEAVE_COMMENT_COST, UPVOTE_KUDOS_COST};
+use kudos_contract::{utils::*, KudosAttachment, WrappedCid};
+use kudos_contract::{
+    GIVE_KUDOS_COST, KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH, KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH,
+    LEAVE_COMMENT_COST, LEAVE_COMMENT_V2_COST, REACT_TO_COMMENT_COST, UPVOTE_KUDOS_SOCIAL_DB_COST,
+};
 use near_sdk::serde_json::json;
 use near_units::parse_near;
 
@@ -114,6 +117,14 @@ async fn test_required_deposit() -> anyhow::Result<()> {
         .map(|n| format!("{}{n}", "a".repeat(31)))
         .collect::<Vec<_>>();
     let kudos_text = "a".repeat(1000);
+    // Maximum number of mentioned users (5) with maximum account name length (64 characters)
+    let mentions_text = (0..5)
+        .map(|n| format!("@{}{n}.near ", "m".repeat(58)))
+        .collect::<String>();
+    let kudos_message = format!(
+        "{mentions_text}{}",
+        "a".repeat(kudos_text.len() - mentions_text.len())
+    );
 
     // Give kudos
     let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
@@ -124,7 +135,7 @@ async fn test_required_deposit() -> anyhow::Result<()> {
         kudos_contract.id(),
         &test1_account,
         test2_account.id(),
-        &kudos_text,
+        &kudos_message,
         Some(
             WrappedCid::new("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi").unwrap(),
         )
@@ -145,6 +156,43 @@ async fn test_required_deposit() -> anyhow::Result<()> {
         display_deposit_in_near(consumed)
     );
 
+    // Give kudos with maximum number of attachments (5)
+    let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
+        anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
+    };
+
+    let attachments = (0..5)
+        .map(|_| KudosAttachment {
+            cid: WrappedCid::new("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi")
+                .unwrap(),
+            mime_type: format!(
+                "a/{}",
+                "b".repeat(KUDOS_ATTACHMENT_MIME_TYPE_MAX_LENGTH - 2)
+            ),
+            caption: Some("c".repeat(KUDOS_ATTACHMENT_CAPTION_MAX_LENGTH)),
+        })
+        .collect::<Vec<_>>();
+    let _ = give_kudos_with_attachments(
+        kudos_contract.id(),
+        &test1_account,
+        test3_account.id(),
+        &kudos_text,
+        &attachments,
+    )
+    .await?;
+
+    let Some(balance_2) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
+        anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
+    };
+
+    let consumed =
+        (balance_2.total.0 - balance_2.available.0) - (balance_1.total.0 - balance_1.available.0);
+    assert!(
+        consumed <= give_kudos_cost(5, 0),
+        "`give_kudos` call with attachments should cost at least {} Ⓝ",
+        display_deposit_in_near(consumed)
+    );
+
     // Leave comment (no parent)
     let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
         anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
@@ -172,17 +220,17 @@ async fn test_required_deposit() -> anyhow::Result<()> {
         display_deposit_in_near(consumed)
     );
 
-    // Leave comment (with parent)
+    // Leave comment (with parent left by another user, who is notified)
     let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
         anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
     };
 
     let _ = leave_comment(
         kudos_contract.id(),
-        &test1_account,
+        &test3_account,
         test2_account.id(),
         &kudos_id,
-        Some(comment_id),
+        Some(comment_id.clone()),
         &kudos_text,
     )
     .await?;
@@ -200,6 +248,41 @@ async fn test_required_deposit() -> anyhow::Result<()> {
         display_deposit_in_near(consumed)
     );
 
+    // Leave comment encoded by v2 (with parent left by another user, who is notified)
+    update_settings(
+        kudos_contract.id(),
+        &admin_account,
+        json!({ "commentaryEncoding": "v2" }),
+    )
+    .await?;
+
+    let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
+        anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
+    };
+
+    let _ = leave_comment_with_deposit(
+        kudos_contract.id(),
+        &test3_account,
+        test2_account.id(),
+        &kudos_id,
+        Some(comment_id.clone()),
+        &kudos_text,
+        LEAVE_COMMENT_V2_COST,
+    )
+    .await?;
+
+    let Some(balance_2) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
+        anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
+    };
+
+    let consumed =
+        (balance_2.total.0 - balance_2.available.0) - (balance_1.total.0 - balance_1.available.0);
+    assert!(
+        consumed <= LEAVE_COMMENT_V2_COST,
+        "`leave_comment` call with v2 encoding should cost at least {} Ⓝ",
+        display_deposit_in_near(consumed)
+    );
+
     // Upvote kudos
     let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
         anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
@@ -220,10 +303,37 @@ async fn test_required_deposit() -> anyhow::Result<()> {
     let consumed =
         (balance_2.total.0 - balance_2.available.0) - (balance_1.total.0 - balance_1.available.0);
     assert!(
-        consumed <= UPVOTE_KUDOS_COST,
+        consumed <= UPVOTE_KUDOS_SOCIAL_DB_COST,
         "`upvote_kudos` call should cost at least {} Ⓝ",
         display_deposit_in_near(consumed)
     );
 
+    // React to comment
+    let Some(balance_1) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
+        anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
+    };
+
+    let _ = react_to_comment(
+        kudos_contract.id(),
+        &test3_account,
+        test2_account.id(),
+        &kudos_id,
+        &comment_id,
+        "❤️",
+    )
+    .await?;
+
+    let Some(balance_2) = storage_balance_of(&near_social_id, kudos_contract.as_account()).await? else {
+        anyhow::bail!("Kudos contract wasn't properly initialized at SocialDB!")
+    };
+
+    let consumed =
+        (balance_2.total.0 - balance_2.available.0) - (balance_1.total.0 - balance_1.available.0);
+    assert!(
+        consumed <= REACT_TO_COMMENT_COST,
+        "`react_to_comment` call should cost at least {} Ⓝ",
+        display_deposit_in_near(consumed)
+    );
+
     Ok(())
 }
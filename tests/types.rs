@@ -30,11 +30,16 @@ impl<'de> Deserialize<'de> for CommentaryOwned {
     {
         let encoded = <String as Deserialize>::deserialize(deserializer)?;
 
-        let raw = near_sdk::base64::decode(&encoded).map_err(|e| {
-            serde::de::Error::custom(format!(
-                "Unable to deserialize commentary from base64 encoded data: {encoded}. {e:?}"
-            ))
-        })?;
+        // Commentary messages encoded by v2 are stored as JSON, previous ones are base64-encoded
+        let raw = if encoded.starts_with('{') {
+            encoded.as_bytes().to_vec()
+        } else {
+            near_sdk::base64::decode(&encoded).map_err(|e| {
+                serde::de::Error::custom(format!(
+                    "Unable to deserialize commentary from base64 encoded data: {encoded}. {e:?}"
+                ))
+            })?
+        };
 
         serde_json::from_slice::<serde_json::Value>(&raw)
             .map_err(|e| {
@@ -1,13 +1,15 @@
 use anyhow::anyhow;
 use kudos_contract::registry::{OwnedToken, TokenMetadata};
+use kudos_contract::utils::give_kudos_cost;
 use kudos_contract::{
-    CommentId, KudosId, WrappedCid, EXCHANGE_KUDOS_COST, GIVE_KUDOS_COST, LEAVE_COMMENT_COST,
-    PROOF_OF_KUDOS_SBT_MINT_COST, SOCIAL_DB_GRANT_WRITE_PERMISSION_COST, UPVOTE_KUDOS_COST,
+    CommentId, KudosAttachment, KudosId, WrappedCid, EXCHANGE_KUDOS_COST, LEAVE_COMMENT_COST,
+    PROOF_OF_KUDOS_SBT_MINT_COST, REACT_TO_COMMENT_COST, SOCIAL_DB_GRANT_WRITE_PERMISSION_COST,
+    UPVOTE_KUDOS_COST,
 };
 use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
 use near_sdk::json_types::U64;
-use near_sdk::serde_json::json;
-use near_sdk::{AccountId, ONE_YOCTO};
+use near_sdk::serde_json::{json, Value};
+use near_sdk::{AccountId, Balance, ONE_YOCTO};
 use workspaces::result::ExecutionOutcome;
 
 pub async fn mint_fv_sbt(
@@ -137,7 +139,44 @@ pub async fn give_kudos(
             "hashtags": hashtags,
             "icon_cid": icon_cid
         }))
-        .deposit(GIVE_KUDOS_COST)
+        .deposit(give_kudos_cost(0, hashtags.len()))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()
+        .map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Give kudos failure: {:?}",
+                extract_error(e.outcomes().into_iter())
+            ))
+        });
+
+    res.and_then(|res| {
+        println!("gas burnt: {}", res.total_gas_burnt);
+        res.json().map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Failed to deserialize give kudos response: {e:?}. Receipts: {:?}",
+                res.receipt_outcomes()
+            ))
+        })
+    })
+}
+
+pub async fn give_kudos_with_attachments(
+    kudos_contract_id: &workspaces::AccountId,
+    sender: &workspaces::Account,
+    receiver_id: &workspaces::AccountId,
+    message: &str,
+    attachments: &[KudosAttachment],
+) -> anyhow::Result<KudosId> {
+    let res = sender
+        .call(kudos_contract_id, "give_kudos")
+        .args_json(json!({
+            "receiver_id": receiver_id,
+            "message": message,
+            "attachments": attachments,
+        }))
+        .deposit(give_kudos_cost(attachments.len(), 0))
         .max_gas()
         .transact()
         .await?
@@ -202,6 +241,27 @@ pub async fn leave_comment(
     kudos_id: &KudosId,
     parent_comment_id: Option<CommentId>,
     message: &str,
+) -> anyhow::Result<CommentId> {
+    leave_comment_with_deposit(
+        kudos_contract_id,
+        sender,
+        receiver_id,
+        kudos_id,
+        parent_comment_id,
+        message,
+        LEAVE_COMMENT_COST,
+    )
+    .await
+}
+
+pub async fn leave_comment_with_deposit(
+    kudos_contract_id: &workspaces::AccountId,
+    sender: &workspaces::Account,
+    receiver_id: &workspaces::AccountId,
+    kudos_id: &KudosId,
+    parent_comment_id: Option<CommentId>,
+    message: &str,
+    deposit: Balance,
 ) -> anyhow::Result<CommentId> {
     let res = sender
         .call(kudos_contract_id, "leave_comment")
@@ -211,7 +271,7 @@ pub async fn leave_comment(
             "parent_comment_id": parent_comment_id,
             "message": message,
         }))
-        .deposit(LEAVE_COMMENT_COST)
+        .deposit(deposit)
         .max_gas()
         .transact()
         .await?
@@ -234,6 +294,45 @@ pub async fn leave_comment(
     })
 }
 
+pub async fn react_to_comment(
+    kudos_contract_id: &workspaces::AccountId,
+    sender: &workspaces::Account,
+    receiver_id: &workspaces::AccountId,
+    kudos_id: &KudosId,
+    comment_id: &CommentId,
+    reaction: &str,
+) -> anyhow::Result<String> {
+    let res = sender
+        .call(kudos_contract_id, "react_to_comment")
+        .args_json(json!({
+            "receiver_id": receiver_id,
+            "kudos_id": kudos_id,
+            "comment_id": comment_id,
+            "reaction": reaction,
+        }))
+        .deposit(REACT_TO_COMMENT_COST)
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()
+        .map_err(|e| {
+            anyhow::Error::msg(format!(
+                "React to comment failure: {:?}",
+                extract_error(e.outcomes().into_iter())
+            ))
+        });
+
+    res.and_then(|res| {
+        println!("gas burnt: {}", res.total_gas_burnt);
+        res.json().map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Failed to deserialize react to comment response: {e:?}. Receipts: {:?}",
+                res.receipt_outcomes()
+            ))
+        })
+    })
+}
+
 pub async fn exchange_kudos_for_sbt(
     kudos_contract_id: &workspaces::AccountId,
     requestor: &workspaces::Account,
@@ -297,6 +396,28 @@ pub async fn set_external_db(
     Ok(())
 }
 
+pub async fn update_settings(
+    kudos_contract_id: &workspaces::AccountId,
+    owner: &workspaces::Account,
+    settings_json: Value,
+) -> anyhow::Result<()> {
+    let _ = owner
+        .call(kudos_contract_id, "update_settings")
+        .args_json(json!({ "settings_json": settings_json }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()
+        .map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Update settings failure: {:?}",
+                extract_error(e.outcomes().into_iter())
+            ))
+        })?;
+
+    Ok(())
+}
+
 pub async fn update_iah_registry(
     kudos_contract_id: &workspaces::AccountId,
     owner: &workspaces::Account,